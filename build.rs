@@ -0,0 +1,39 @@
+//! Embeds build metadata reported by `rafctl version`.
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let target = std::env::var("TARGET").unwrap_or_default();
+    println!("cargo:rustc-env=RAFCTL_BUILD_TARGET={}", target);
+
+    // Builds from a published crate have no git checkout; the SHA is optional
+    let git_dir = Path::new(".git");
+    if !git_dir.is_dir() {
+        return;
+    }
+    for path in ["HEAD", "packed-refs"] {
+        if git_dir.join(path).exists() {
+            println!("cargo:rerun-if-changed=.git/{}", path);
+        }
+    }
+    if let Ok(head) = std::fs::read_to_string(git_dir.join("HEAD")) {
+        if let Some(reference) = head.trim().strip_prefix("ref: ") {
+            if git_dir.join(reference).exists() {
+                println!("cargo:rerun-if-changed=.git/{}", reference);
+            }
+        }
+    }
+
+    let sha = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    if let Some(sha) = sha.filter(|s| !s.is_empty()) {
+        println!("cargo:rustc-env=RAFCTL_GIT_SHA={}", sha);
+    }
+}
@@ -1,58 +1,39 @@
 //! Analytics command handler - displays local usage statistics from stats-cache.json
 
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
 use colored::Colorize;
 use comfy_table::{presets::UTF8_FULL_CONDENSED, Cell, Color, Table};
 use serde::Serialize;
 
-use super::output::print_json;
+use super::output::{print_json, warn_corrupted_profile};
+use super::sessions::progress_bar as plain_progress_bar;
 use super::OutputFormat;
 use crate::core::config::get_default_profile;
-use crate::core::profile::{list_profiles, load_profile};
-use crate::core::stats::{load_global_stats, load_profile_stats, StatsCache};
+use crate::core::pricing::{
+    load_pricing, ModelPricing, PricingEntry, PricingSource, FALLBACK_PRICING,
+};
+use crate::core::profile::{list_profiles, list_profiles_with_tag, load_profile};
+use crate::core::stats::{
+    base_model_id, load_cost_entries, load_global_stats, load_profile_stats, CostEntry,
+    Granularity, StatsCache,
+};
+use crate::core::transcript::{
+    get_global_transcripts_dir, get_profile_transcripts_dir, list_all_sessions, parse_transcript,
+    SessionSummary,
+};
 use crate::error::RafctlError;
 
-struct ModelPricing {
-    input_per_million: f64,
-    output_per_million: f64,
-}
-
-const PRICING: &[(&str, ModelPricing)] = &[
-    (
-        "claude-sonnet-4-5",
-        ModelPricing {
-            input_per_million: 3.0,
-            output_per_million: 15.0,
-        },
-    ),
-    (
-        "claude-opus-4-5",
-        ModelPricing {
-            input_per_million: 15.0,
-            output_per_million: 75.0,
-        },
-    ),
-    (
-        "claude-haiku-4-5",
-        ModelPricing {
-            input_per_million: 0.80,
-            output_per_million: 4.0,
-        },
-    ),
-    (
-        "claude-haiku-3-5",
-        ModelPricing {
-            input_per_million: 0.25,
-            output_per_million: 1.25,
-        },
-    ),
-];
-
 const OUTPUT_TO_INPUT_RATIO: f64 = 3.0;
 
 #[derive(Debug, Serialize)]
 struct AnalyticsOutput {
     profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
     days: usize,
+    granularity: Granularity,
     daily_activity: Vec<DailyActivityOutput>,
     totals: TotalsOutput,
     models: Vec<ModelOutput>,
@@ -97,14 +78,97 @@ struct ProfileSummary {
     last_active: Option<String>,
 }
 
+/// One profile's column in `analytics --compare`.
+#[derive(Debug, Serialize)]
+struct ComparisonSummary {
+    profile: String,
+    tool: String,
+    messages: u64,
+    sessions: u64,
+    tools: u64,
+    tokens: u64,
+    estimated_cost: f64,
+    cost_source: CostSource,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorRateOutput {
+    profile: Option<String>,
+    days: usize,
+    daily: Vec<DailyErrorRate>,
+    totals: ErrorTotals,
+}
+
+#[derive(Debug, Serialize)]
+struct DailyErrorRate {
+    date: String,
+    sessions: u64,
+    tool_calls: u64,
+    tool_errors: u64,
+    /// Percentage of tool calls that returned an error
+    error_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorTotals {
+    tool_calls: u64,
+    tool_errors: u64,
+    error_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct SessionAnalyticsOutput {
+    profile: Option<String>,
+    days: usize,
+    sessions: Vec<SessionTokensOutput>,
+    total_tokens: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct SessionTokensOutput {
+    session_id: String,
+    started_at: Option<String>,
+    model: Option<String>,
+    messages: u64,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_tokens: u64,
+    total_tokens: u64,
+}
+
 #[derive(Debug, Serialize)]
 struct CostOutput {
     profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
     days: usize,
+    source: CostSource,
     models: Vec<ModelCostOutput>,
     total_estimated: f64,
 }
 
+/// Where `analytics --cost` took its numbers from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CostSource {
+    /// Sessions recorded by `run --capture-cost`, priced from real token counts
+    Ledger,
+    /// Stats-cache input tokens with output assumed at `OUTPUT_TO_INPUT_RATIO`
+    Estimate,
+    /// Ledger for the days it has sessions for, estimate for the other days
+    Mixed,
+}
+
+impl CostSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CostSource::Ledger => "ledger",
+            CostSource::Estimate => "estimate",
+            CostSource::Mixed => "mixed",
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct ModelCostOutput {
     name: String,
@@ -114,27 +178,69 @@ struct ModelCostOutput {
     total_cost_estimated: f64,
 }
 
+/// Optional behaviors for `rafctl analytics`
+#[derive(Debug, Clone, Default)]
+pub struct AnalyticsOptions {
+    /// Only count usage of models whose name contains this string
+    pub model: Option<String>,
+    /// Merge model versions into their family (e.g. all Sonnet releases)
+    pub aggregate_models: bool,
+    /// How many days of history to include
+    pub days: usize,
+    /// Summarize every profile instead of a single one
+    pub all: bool,
+    /// Restrict `all` to profiles carrying this tag
+    pub tag: Option<String>,
+    /// Show estimated costs instead of activity
+    pub cost: bool,
+    /// Include days without activity in the daily breakdown
+    pub zero_fill: bool,
+    /// Bucket size for the daily breakdown
+    pub group_by: Granularity,
+    /// Break usage down per session transcript
+    pub by_session: bool,
+    /// Show tool errors recorded in session transcripts
+    pub errors: bool,
+}
+
+/// Returns `false` when the selected view found no usage data.
 pub fn handle_analytics(
     profile_name: Option<&str>,
-    days: usize,
-    show_all: bool,
-    show_cost: bool,
+    options: &AnalyticsOptions,
     format: OutputFormat,
-) -> Result<(), RafctlError> {
-    if show_cost {
-        show_cost_estimate(profile_name, days, format)
-    } else if show_all {
-        show_all_profiles_analytics(days, format)
+) -> Result<bool, RafctlError> {
+    let model = options.model.as_deref();
+    let days = options.days;
+    if options.cost {
+        show_cost_estimate(profile_name, model, options.aggregate_models, days, format)
+    } else if options.errors {
+        show_error_analytics(profile_name, days, format)
+    } else if options.by_session {
+        show_session_analytics(profile_name, days, format)
+    } else if options.all {
+        show_all_profiles_analytics(days, options.tag.as_deref(), format)
     } else {
-        show_single_analytics(profile_name, days, format)
+        show_single_analytics(
+            profile_name,
+            model,
+            options.aggregate_models,
+            days,
+            options.zero_fill,
+            options.group_by,
+            format,
+        )
     }
 }
 
 fn show_single_analytics(
     profile_name: Option<&str>,
+    model: Option<&str>,
+    aggregate_models: bool,
     days: usize,
+    zero_fill: bool,
+    group_by: Granularity,
     format: OutputFormat,
-) -> Result<(), RafctlError> {
+) -> Result<bool, RafctlError> {
     // Determine which profile/stats to use
     let (stats, profile_display) = match profile_name {
         Some(name) => {
@@ -163,7 +269,9 @@ fn show_single_analytics(
             OutputFormat::Json => {
                 print_json(&AnalyticsOutput {
                     profile: profile_display,
+                    model: model.map(str::to_string),
                     days,
+                    granularity: group_by,
                     daily_activity: vec![],
                     totals: TotalsOutput {
                         messages: 0,
@@ -172,7 +280,7 @@ fn show_single_analytics(
                         tokens: 0,
                     },
                     models: vec![],
-                });
+                })?;
             }
             _ => {
                 println!(
@@ -181,17 +289,25 @@ fn show_single_analytics(
                 );
             }
         }
-        return Ok(());
+        return Ok(false);
     }
 
     // Build output data
-    let output = build_analytics_output(&stats, profile_display.clone(), days);
+    let output = build_analytics_output(
+        &stats,
+        profile_display.clone(),
+        model,
+        aggregate_models,
+        days,
+        zero_fill,
+        group_by,
+    );
 
     match format {
         OutputFormat::Json => {
-            print_json(&output);
+            print_json(&output)?;
         }
-        OutputFormat::Plain => {
+        OutputFormat::Plain | OutputFormat::Porcelain => {
             print_plain_analytics(&output);
         }
         OutputFormat::Human => {
@@ -199,31 +315,56 @@ fn show_single_analytics(
         }
     }
 
-    Ok(())
+    Ok(true)
 }
 
+/// With `model`, token columns and the model breakdown only count matching
+/// models; percentages are relative to the matching subset. `aggregate_models`
+/// merges dated snapshots of a model into one row. `zero_fill` only applies to
+/// per-day rows.
 fn build_analytics_output(
     stats: &StatsCache,
     profile: Option<String>,
+    model: Option<&str>,
+    aggregate_models: bool,
     days: usize,
+    zero_fill: bool,
+    granularity: Granularity,
 ) -> AnalyticsOutput {
-    let recent_activity = stats.recent_activity(days);
-    let _recent_tokens = stats.recent_tokens(days);
+    let stats = select_models(stats, model, aggregate_models);
+    let stats = &stats;
 
     // Build daily activity with tokens
-    let daily_activity: Vec<DailyActivityOutput> = recent_activity
-        .iter()
-        .map(|a| {
-            let tokens = stats.tokens_for_date(&a.date);
-            DailyActivityOutput {
-                date: a.date.clone(),
-                messages: a.message_count,
-                sessions: a.session_count,
-                tools: a.tool_call_count,
-                tokens,
-            }
-        })
-        .collect();
+    let daily_activity: Vec<DailyActivityOutput> = if granularity != Granularity::Day {
+        stats
+            .aggregate_by_period(days, granularity)
+            .into_iter()
+            .map(|p| DailyActivityOutput {
+                date: p.period,
+                messages: p.message_count,
+                sessions: p.session_count,
+                tools: p.tool_call_count,
+                tokens: p.tokens,
+            })
+            .collect()
+    } else if zero_fill {
+        zero_filled_activity(stats, days, Local::now().date_naive())
+    } else {
+        stats
+            .recent_activity(days)
+            .iter()
+            .map(|a| {
+                let tokens = stats.tokens_for_date(&a.date);
+                DailyActivityOutput {
+                    date: a.date.clone(),
+                    messages: a.message_count,
+                    sessions: a.session_count,
+                    tools: a.tool_call_count,
+                    tokens,
+                }
+            })
+            .collect()
+    };
 
     // Calculate totals
     let totals = TotalsOutput {
@@ -254,17 +395,56 @@ fn build_analytics_output(
         .collect();
 
     // Sort by tokens descending
-    models.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+    models.sort_by_key(|m| std::cmp::Reverse(m.tokens));
 
     AnalyticsOutput {
         profile,
+        model: model.map(str::to_string),
         days,
+        granularity,
         daily_activity,
         totals,
         models,
     }
 }
 
+/// Apply `--model` filtering and `--aggregate-models` merging to the stats.
+fn select_models(stats: &StatsCache, model: Option<&str>, aggregate_models: bool) -> StatsCache {
+    let stats = match model {
+        Some(pattern) => stats.filter_models(pattern),
+        None => stats.clone(),
+    };
+    if aggregate_models {
+        stats.aggregate_models()
+    } else {
+        stats
+    }
+}
+
+/// Build one row per calendar day ending at `today` (most recent first),
+/// with zeros for days that have no recorded activity.
+fn zero_filled_activity(
+    stats: &StatsCache,
+    days: usize,
+    today: NaiveDate,
+) -> Vec<DailyActivityOutput> {
+    (0..days)
+        .map(|offset| {
+            let date = (today - Duration::days(offset as i64))
+                .format("%Y-%m-%d")
+                .to_string();
+            let activity = stats.activity_for_date(&date);
+            DailyActivityOutput {
+                messages: activity.map(|a| a.message_count).unwrap_or(0),
+                sessions: activity.map(|a| a.session_count).unwrap_or(0),
+                tools: activity.map(|a| a.tool_call_count).unwrap_or(0),
+                tokens: stats.tokens_for_date(&date),
+                date,
+            }
+        })
+        .collect()
+}
+
 fn print_human_analytics(output: &AnalyticsOutput, _stats: &StatsCache) {
     // Header
     let profile_str = output
@@ -272,21 +452,54 @@ fn print_human_analytics(output: &AnalyticsOutput, _stats: &StatsCache) {
         .as_ref()
         .map(|p| format!(" — Profile: {}", p))
         .unwrap_or_default();
+    let model_str = output
+        .model
+        .as_ref()
+        .map(|m| format!(" — Model: {}", m))
+        .unwrap_or_default();
+
+    let grouping_str = match output.granularity {
+        Granularity::Day => String::new(),
+        other => format!(", by {}", other),
+    };
 
     println!(
-        "\n{} {} (last {} days)\n",
+        "\n{} {} (last {} days{})\n",
         "📊".cyan(),
-        format!("Usage Analytics{}", profile_str).bold(),
-        output.days
+        format!("Usage Analytics{}{}", profile_str, model_str).bold(),
+        output.days,
+        grouping_str
     );
 
-    // Daily activity table
+    // Activity table
     if !output.daily_activity.is_empty() {
+        let period_header = match output.granularity {
+            Granularity::Day => "Date",
+            Granularity::Week => "Week",
+            Granularity::Month => "Month",
+        };
         let mut table = Table::new();
         table.load_preset(UTF8_FULL_CONDENSED);
-        table.set_header(vec!["Date", "Messages", "Sessions", "Tools", "Tokens"]);
+        table.set_header(vec![
+            period_header,
+            "Messages",
+            "Sessions",
+            "Tools",
+            "Tokens",
+        ]);
 
         for day in &output.daily_activity {
+            if day.messages == 0 && day.tokens == 0 {
+                table.add_row(vec![
+                    Cell::new(&day.date).fg(Color::DarkGrey),
+                    Cell::new("—").fg(Color::DarkGrey),
+                    Cell::new("—").fg(Color::DarkGrey),
+                    Cell::new("—").fg(Color::DarkGrey),
+                    Cell::new("—").fg(Color::DarkGrey),
+                ]);
+                continue;
+            }
+
             table.add_row(vec![
                 Cell::new(&day.date),
                 Cell::new(day.messages),
@@ -333,7 +546,12 @@ fn print_plain_analytics(output: &AnalyticsOutput) {
         output.profile.as_deref().unwrap_or("global"),
         output.days
     );
-    println!("DATE\tMESSAGES\tSESSIONS\tTOOLS\tTOKENS");
+    let period_header = match output.granularity {
+        Granularity::Day => "DATE",
+        Granularity::Week => "WEEK",
+        Granularity::Month => "MONTH",
+    };
+    println!("{}\tMESSAGES\tSESSIONS\tTOOLS\tTOKENS", period_header);
     for day in &output.daily_activity {
         println!(
             "{}\t{}\t{}\t{}\t{}",
@@ -346,8 +564,16 @@ fn print_plain_analytics(output: &AnalyticsOutput) {
     );
 }
 
-fn show_all_profiles_analytics(days: usize, format: OutputFormat) -> Result<(), RafctlError> {
-    let profile_names = list_profiles()?;
+/// Returns `false` when no profile had activity in the window.
+fn show_all_profiles_analytics(
+    days: usize,
+    tag: Option<&str>,
+    format: OutputFormat,
+) -> Result<bool, RafctlError> {
+    let profile_names = match tag {
+        Some(tag) => list_profiles_with_tag(tag)?,
+        None => list_profiles()?,
+    };
 
     if profile_names.is_empty() {
         match format {
@@ -360,13 +586,13 @@ fn show_all_profiles_analytics(days: usize, format: OutputFormat) -> Result<(),
                         tools: 0,
                         tokens: 0,
                     },
-                });
+                })?;
             }
             _ => {
                 println!("{} No profiles found.", "ℹ".cyan());
             }
         }
-        return Ok(());
+        return Ok(false);
     }
 
     let mut summaries: Vec<ProfileSummary> = Vec::new();
@@ -374,30 +600,35 @@ fn show_all_profiles_analytics(days: usize, format: OutputFormat) -> Result<(),
     let mut total_tokens = 0u64;
 
     for name in &profile_names {
-        if let Ok(profile) = load_profile(name) {
-            let stats = load_profile_stats(name, profile.tool);
+        let profile = match load_profile(name) {
+            Ok(profile) => profile,
+            Err(e) => {
+                warn_corrupted_profile(name, &e, format);
+                continue;
+            }
+        };
+        let stats = load_profile_stats(name, profile.tool);
 
-            let recent_activity = stats.recent_activity(days);
-            let messages_7d: u64 = recent_activity.iter().map(|a| a.message_count).sum();
-            let tokens_7d = stats.total_tokens(Some(days));
+        let recent_activity = stats.recent_activity(days);
+        let messages_7d: u64 = recent_activity.iter().map(|a| a.message_count).sum();
+        let tokens_7d = stats.total_tokens(Some(days));
 
-            let last_active = recent_activity.first().map(|a| a.date.clone());
+        let last_active = recent_activity.first().map(|a| a.date.clone());
 
-            total_messages += messages_7d;
-            total_tokens += tokens_7d;
+        total_messages += messages_7d;
+        total_tokens += tokens_7d;
 
-            summaries.push(ProfileSummary {
-                name: name.clone(),
-                tool: profile.tool.to_string(),
-                messages_7d,
-                tokens_7d,
-                last_active,
-            });
-        }
+        summaries.push(ProfileSummary {
+            name: name.clone(),
+            tool: profile.tool.to_string(),
+            messages_7d,
+            tokens_7d,
+            last_active,
+        });
     }
 
     // Sort by tokens descending
-    summaries.sort_by(|a, b| b.tokens_7d.cmp(&a.tokens_7d));
+    summaries.sort_by_key(|s| std::cmp::Reverse(s.tokens_7d));
 
     let output = AllProfilesOutput {
         profiles: summaries.clone(),
@@ -411,9 +642,9 @@ fn show_all_profiles_analytics(days: usize, format: OutputFormat) -> Result<(),
 
     match format {
         OutputFormat::Json => {
-            print_json(&output);
+            print_json(&output)?;
         }
-        OutputFormat::Plain => {
+        OutputFormat::Plain | OutputFormat::Porcelain => {
             println!("PROFILE\tTOOL\tMESSAGES_7D\tTOKENS_7D\tLAST_ACTIVE");
             for s in &summaries {
                 println!(
@@ -462,11 +693,455 @@ fn show_all_profiles_analytics(days: usize, format: OutputFormat) -> Result<(),
         }
     }
 
-    Ok(())
+    Ok(total_messages > 0 || total_tokens > 0)
+}
+
+/// Side-by-side totals for `profiles` over the window. Returns `false` when
+/// none of them has usage data.
+pub fn handle_compare(
+    profiles: &[String],
+    model: Option<&str>,
+    aggregate_models: bool,
+    days: usize,
+    format: OutputFormat,
+) -> Result<bool, RafctlError> {
+    let mut summaries: Vec<ComparisonSummary> = Vec::new();
+
+    for name in profiles {
+        let name_lower = name.to_lowercase();
+        let profile = load_profile(&name_lower)?;
+        let stats = load_profile_stats(&name_lower, profile.tool);
+        let totals = build_analytics_output(
+            &stats,
+            Some(name_lower.clone()),
+            model,
+            aggregate_models,
+            days,
+            false,
+            Granularity::Day,
+        )
+        .totals;
+
+        let ledger = load_cost_entries(&name_lower, days);
+        let (cost_source, model_costs) =
+            model_costs(&stats, &ledger, model, aggregate_models, days)?;
+
+        summaries.push(ComparisonSummary {
+            profile: name_lower,
+            tool: profile.tool.to_string(),
+            messages: totals.messages,
+            sessions: totals.sessions,
+            tools: totals.tools,
+            tokens: totals.tokens,
+            estimated_cost: model_costs.iter().map(|m| m.total_cost_estimated).sum(),
+            cost_source,
+        });
+    }
+
+    let found_data = summaries
+        .iter()
+        .any(|s| s.messages > 0 || s.tokens > 0 || s.estimated_cost > 0.0);
+
+    match format {
+        OutputFormat::Json => {
+            print_json(&summaries)?;
+        }
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("PROFILE\tTOOL\tMESSAGES\tSESSIONS\tTOOLS\tTOKENS\tCOST\tSOURCE");
+            for s in &summaries {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{:.2}\t{}",
+                    s.profile,
+                    s.tool,
+                    s.messages,
+                    s.sessions,
+                    s.tools,
+                    s.tokens,
+                    s.estimated_cost,
+                    s.cost_source.as_str()
+                );
+            }
+        }
+        OutputFormat::Human => {
+            println!(
+                "\n{} {} (last {} days)\n",
+                "📊".cyan(),
+                "Profile Comparison".bold(),
+                days
+            );
+
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL_CONDENSED);
+            let mut header = vec![Cell::new("")];
+            header.extend(
+                summaries
+                    .iter()
+                    .map(|s| Cell::new(&s.profile).fg(Color::Cyan)),
+            );
+            table.set_header(header);
+
+            let row = |label: &str, value: &dyn Fn(&ComparisonSummary) -> String| {
+                let mut cells = vec![Cell::new(label)];
+                cells.extend(summaries.iter().map(|s| Cell::new(value(s))));
+                cells
+            };
+            table.add_row(row("Messages", &|s| s.messages.to_string()));
+            table.add_row(row("Sessions", &|s| s.sessions.to_string()));
+            table.add_row(row("Tool calls", &|s| s.tools.to_string()));
+            table.add_row(row("Tokens", &|s| format_tokens(s.tokens)));
+            table.add_row(row("Est. cost", &|s| {
+                let approx = if s.cost_source == CostSource::Ledger {
+                    ""
+                } else {
+                    "~"
+                };
+                format!("{}${:.2}", approx, s.estimated_cost)
+            }));
+
+            println!("{table}");
+            if summaries
+                .iter()
+                .any(|s| s.cost_source != CostSource::Ledger)
+            {
+                println!(
+                    "{}",
+                    "~ estimated from stats-cache input tokens; see 'analytics --cost'".dimmed()
+                );
+            }
+            println!();
+        }
+    }
+
+    Ok(found_data)
+}
+
+/// The profile to report on (explicit, else the default) and its parsed
+/// sessions: per-profile transcripts when the profile has any, otherwise the
+/// global ones.
+fn load_session_summaries(
+    profile_name: Option<&str>,
+) -> Result<(Option<String>, Vec<SessionSummary>), RafctlError> {
+    let profile_display = match profile_name {
+        Some(name) => {
+            let name_lower = name.to_lowercase();
+            load_profile(&name_lower)?;
+            Some(name_lower)
+        }
+        None => get_default_profile().ok().flatten(),
+    };
+
+    let transcripts_dir = profile_display
+        .as_deref()
+        .and_then(get_profile_transcripts_dir)
+        .filter(|dir| dir.exists())
+        .or_else(get_global_transcripts_dir);
+
+    let summaries = transcripts_dir
+        .map(|dir| list_all_sessions(&dir))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|path| parse_transcript(path))
+        .map(|detail| detail.summary)
+        .collect();
+
+    Ok((profile_display, summaries))
+}
+
+/// Daily tool error rate from transcripts (the stats cache has no error data).
+/// Returns `false` when no session fell inside the window.
+fn show_error_analytics(
+    profile_name: Option<&str>,
+    days: usize,
+    format: OutputFormat,
+) -> Result<bool, RafctlError> {
+    let (profile_display, summaries) = load_session_summaries(profile_name)?;
+    let daily = build_error_rows(&summaries, days, Utc::now());
+
+    let tool_calls = daily.iter().map(|d| d.tool_calls).sum();
+    let tool_errors = daily.iter().map(|d| d.tool_errors).sum();
+    let output = ErrorRateOutput {
+        profile: profile_display,
+        days,
+        totals: ErrorTotals {
+            tool_calls,
+            tool_errors,
+            error_rate: error_rate(tool_errors, tool_calls),
+        },
+        daily,
+    };
+
+    match format {
+        OutputFormat::Json => {
+            print_json(&output)?;
+        }
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("DATE\tSESSIONS\tTOOL_CALLS\tTOOL_ERRORS\tERROR_RATE");
+            for d in &output.daily {
+                println!(
+                    "{}\t{}\t{}\t{}\t{:.1}",
+                    d.date, d.sessions, d.tool_calls, d.tool_errors, d.error_rate
+                );
+            }
+            println!(
+                "TOTAL\t-\t{}\t{}\t{:.1}",
+                output.totals.tool_calls, output.totals.tool_errors, output.totals.error_rate
+            );
+        }
+        OutputFormat::Human => {
+            let profile_str = output
+                .profile
+                .as_ref()
+                .map(|p| format!(" — Profile: {}", p))
+                .unwrap_or_default();
+
+            println!(
+                "\n{} {} (last {} days)\n",
+                "📊".cyan(),
+                format!("Tool Error Rate{}", profile_str).bold(),
+                output.days
+            );
+
+            if output.daily.is_empty() {
+                println!(
+                    "{} No sessions found. Run Claude Code to create sessions.",
+                    "ℹ".cyan()
+                );
+                return Ok(false);
+            }
+
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL_CONDENSED);
+            table.set_header(vec![
+                "Date",
+                "Sessions",
+                "Tool Calls",
+                "Errors",
+                "Error Rate",
+            ]);
+
+            for d in &output.daily {
+                table.add_row(vec![
+                    Cell::new(&d.date),
+                    Cell::new(d.sessions),
+                    Cell::new(d.tool_calls),
+                    Cell::new(d.tool_errors),
+                    Cell::new(format!(
+                        "{} {:5.1}%",
+                        plain_progress_bar(d.error_rate, 20),
+                        d.error_rate
+                    ))
+                    .fg(error_rate_color(d.error_rate)),
+                ]);
+            }
+
+            println!("{table}\n");
+            println!(
+                "{}: {} of {} tool calls failed ({:.1}%)\n",
+                "Totals".bold(),
+                output.totals.tool_errors.to_string().cyan(),
+                output.totals.tool_calls.to_string().cyan(),
+                output.totals.error_rate
+            );
+        }
+    }
+
+    Ok(!output.daily.is_empty())
+}
+
+/// Per-day tool call and error counts for sessions started within `days`,
+/// newest day first. Days are local dates of the session start.
+fn build_error_rows(
+    summaries: &[SessionSummary],
+    days: usize,
+    now: DateTime<Utc>,
+) -> Vec<DailyErrorRate> {
+    let cutoff = now - Duration::days(days as i64);
+    let mut by_day: std::collections::BTreeMap<String, (u64, u64, u64)> =
+        std::collections::BTreeMap::new();
+
+    for s in summaries {
+        let Some(started) = s.started_at.filter(|t| *t >= cutoff) else {
+            continue;
+        };
+        let date = started.with_timezone(&Local).format("%Y-%m-%d").to_string();
+        let day = by_day.entry(date).or_default();
+        day.0 += 1;
+        day.1 += s.tool_calls;
+        day.2 += s.tool_errors;
+    }
+
+    by_day
+        .into_iter()
+        .rev()
+        .map(
+            |(date, (sessions, tool_calls, tool_errors))| DailyErrorRate {
+                date,
+                sessions,
+                tool_calls,
+                tool_errors,
+                error_rate: error_rate(tool_errors, tool_calls),
+            },
+        )
+        .collect()
+}
+
+fn error_rate(errors: u64, calls: u64) -> f64 {
+    if calls == 0 {
+        0.0
+    } else {
+        errors as f64 / calls as f64 * 100.0
+    }
+}
+
+fn error_rate_color(rate: f64) -> Color {
+    if rate >= 10.0 {
+        Color::Red
+    } else if rate >= 5.0 {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+/// Returns `false` when no session started within the window.
+fn show_session_analytics(
+    profile_name: Option<&str>,
+    days: usize,
+    format: OutputFormat,
+) -> Result<bool, RafctlError> {
+    let (profile_display, summaries) = load_session_summaries(profile_name)?;
+
+    let sessions = build_session_rows(summaries, days, Utc::now());
+    let total_tokens = sessions.iter().map(|s| s.total_tokens).sum();
+    let found_data = !sessions.is_empty();
+
+    let output = SessionAnalyticsOutput {
+        profile: profile_display,
+        days,
+        sessions,
+        total_tokens,
+    };
+
+    match format {
+        OutputFormat::Json => {
+            print_json(&output)?;
+        }
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("SESSION_ID\tSTARTED\tMESSAGES\tINPUT\tOUTPUT\tCACHE\tTOTAL");
+            for s in &output.sessions {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    s.session_id,
+                    s.started_at.as_deref().unwrap_or("-"),
+                    s.messages,
+                    s.input_tokens,
+                    s.output_tokens,
+                    s.cache_tokens,
+                    s.total_tokens
+                );
+            }
+            println!("TOTAL\t-\t-\t-\t-\t-\t{}", output.total_tokens);
+        }
+        OutputFormat::Human => {
+            let profile_str = output
+                .profile
+                .as_ref()
+                .map(|p| format!(" — Profile: {}", p))
+                .unwrap_or_default();
+
+            println!(
+                "\n{} {} (last {} days)\n",
+                "📊".cyan(),
+                format!("Token Usage by Session{}", profile_str).bold(),
+                output.days
+            );
+
+            if output.sessions.is_empty() {
+                println!(
+                    "{} No sessions found. Run Claude Code to create sessions.",
+                    "ℹ".cyan()
+                );
+                return Ok(false);
+            }
+
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL_CONDENSED);
+            table.set_header(vec![
+                "Session ID",
+                "Started",
+                "Messages",
+                "Input",
+                "Output",
+                "Cache",
+                "Total",
+            ]);
+
+            for s in &output.sessions {
+                table.add_row(vec![
+                    Cell::new(shorten_session_id(&s.session_id)).fg(Color::Cyan),
+                    Cell::new(s.started_at.as_deref().unwrap_or("-")),
+                    Cell::new(s.messages),
+                    Cell::new(format_tokens(s.input_tokens)),
+                    Cell::new(format_tokens(s.output_tokens)),
+                    Cell::new(format_tokens(s.cache_tokens)),
+                    Cell::new(format_tokens(s.total_tokens)).fg(Color::Yellow),
+                ]);
+            }
+
+            println!("{table}\n");
+            println!(
+                "{}: {} tokens across {} sessions\n",
+                "Totals".bold(),
+                format_tokens(output.total_tokens).cyan(),
+                output.sessions.len().to_string().cyan()
+            );
+        }
+    }
+
+    Ok(found_data)
+}
+
+/// Keep sessions started within the last `days` days, heaviest first.
+fn build_session_rows(
+    summaries: Vec<SessionSummary>,
+    days: usize,
+    now: DateTime<Utc>,
+) -> Vec<SessionTokensOutput> {
+    let cutoff = now - Duration::days(days as i64);
+
+    let mut rows: Vec<SessionTokensOutput> = summaries
+        .into_iter()
+        .filter(|s| s.started_at.is_some_and(|t| t >= cutoff))
+        .map(|s| SessionTokensOutput {
+            total_tokens: s.total_tokens(),
+            started_at: s.started_at.map(|dt| {
+                dt.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            }),
+            model: s.model,
+            messages: s.message_count,
+            input_tokens: s.input_tokens,
+            output_tokens: s.output_tokens,
+            cache_tokens: s.cache_creation_tokens + s.cache_read_tokens,
+            session_id: s.session_id,
+        })
+        .collect();
+
+    rows.sort_by_key(|r| std::cmp::Reverse(r.total_tokens));
+    rows
+}
+
+fn shorten_session_id(id: &str) -> String {
+    if id.len() > 12 {
+        format!("{}...", &id[..12])
+    } else {
+        id.to_string()
+    }
 }
 
 /// Format token count for display (e.g., 1.5M, 320K, 1234)
-fn format_tokens(n: u64) -> String {
+pub fn format_tokens(n: u64) -> String {
     if n >= 1_000_000 {
         format!("{:.1}M", n as f64 / 1_000_000.0)
     } else if n >= 1_000 {
@@ -502,9 +1177,11 @@ fn shorten_model_name(name: &str) -> String {
 
 fn show_cost_estimate(
     profile_name: Option<&str>,
+    model: Option<&str>,
+    aggregate_models: bool,
     days: usize,
     format: OutputFormat,
-) -> Result<(), RafctlError> {
+) -> Result<bool, RafctlError> {
     let (stats, profile_display) = match profile_name {
         Some(name) => {
             let name_lower = name.to_lowercase();
@@ -526,15 +1203,22 @@ fn show_cost_estimate(
         }
     };
 
-    if stats.is_empty() {
+    let ledger = profile_display
+        .as_deref()
+        .map(|name| load_cost_entries(name, days))
+        .unwrap_or_default();
+
+    if stats.is_empty() && ledger.is_empty() {
         match format {
             OutputFormat::Json => {
                 print_json(&CostOutput {
                     profile: profile_display,
+                    model: model.map(str::to_string),
                     days,
+                    source: CostSource::Estimate,
                     models: vec![],
                     total_estimated: 0.0,
-                });
+                })?;
             }
             _ => {
                 println!(
@@ -543,14 +1227,114 @@ fn show_cost_estimate(
                 );
             }
         }
-        return Ok(());
+        return Ok(false);
     }
 
-    let model_tokens = stats.aggregate_tokens_by_model(Some(days));
-    let mut model_costs: Vec<ModelCostOutput> = model_tokens
+    let (source, mut model_costs) = model_costs(&stats, &ledger, model, aggregate_models, days)?;
+
+    model_costs.sort_by(|a, b| {
+        b.total_cost_estimated
+            .partial_cmp(&a.total_cost_estimated)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let total_estimated: f64 = model_costs.iter().map(|m| m.total_cost_estimated).sum();
+
+    let output = CostOutput {
+        profile: profile_display.clone(),
+        model: model.map(str::to_string),
+        days,
+        source,
+        models: model_costs,
+        total_estimated,
+    };
+
+    match format {
+        OutputFormat::Json => {
+            print_json(&output)?;
+        }
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            print_plain_cost(&output);
+        }
+        OutputFormat::Human => {
+            print_human_cost(&output);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Cost per model. Costs rafctl recorded itself are exact, so they replace the
+/// estimate for the days the ledger has sessions for; every other day is
+/// still estimated, so runs without `--capture-cost` are not dropped.
+fn model_costs(
+    stats: &StatsCache,
+    ledger: &[CostEntry],
+    model: Option<&str>,
+    aggregate_models: bool,
+    days: usize,
+) -> Result<(CostSource, Vec<ModelCostOutput>), RafctlError> {
+    if ledger.is_empty() {
+        return Ok((
+            CostSource::Estimate,
+            estimate_model_costs(stats, model, aggregate_models, days)?,
+        ));
+    }
+
+    let ledger_days: HashSet<String> = ledger
+        .iter()
+        .map(|e| {
+            e.recorded_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .collect();
+    // Fix the --days window first, so uncovered days never pull in older ones
+    let window: HashSet<String> = stats
+        .recent_tokens(days)
+        .into_iter()
+        .map(|d| d.date.clone())
+        .collect();
+    let mut uncovered = stats.clone();
+    uncovered
+        .daily_model_tokens
+        .retain(|d| window.contains(&d.date) && !ledger_days.contains(&d.date));
+    let estimated = estimate_model_costs(&uncovered, model, aggregate_models, days)?;
+
+    let mut costs = ledger_model_costs(ledger, model, aggregate_models);
+    if estimated.iter().all(|m| m.input_tokens == 0) {
+        return Ok((CostSource::Ledger, costs));
+    }
+
+    for row in estimated {
+        match costs.iter_mut().find(|m| m.name == row.name) {
+            Some(existing) => {
+                existing.input_tokens += row.input_tokens;
+                existing.input_cost += row.input_cost;
+                existing.output_cost_estimated += row.output_cost_estimated;
+                existing.total_cost_estimated += row.total_cost_estimated;
+            }
+            None => costs.push(row),
+        }
+    }
+    Ok((CostSource::Mixed, costs))
+}
+
+fn estimate_model_costs(
+    stats: &StatsCache,
+    model: Option<&str>,
+    aggregate_models: bool,
+    days: usize,
+) -> Result<Vec<ModelCostOutput>, RafctlError> {
+    let pricing_table = load_pricing()?;
+    let model_tokens =
+        select_models(stats, model, aggregate_models).aggregate_tokens_by_model(Some(days));
+
+    Ok(model_tokens
         .into_iter()
         .map(|(name, input_tokens)| {
-            let pricing = get_model_pricing(&name);
+            let pricing = pricing_table.lookup(&name);
             let input_cost = (input_tokens as f64 / 1_000_000.0) * pricing.input_per_million;
             let estimated_output_tokens = (input_tokens as f64 * OUTPUT_TO_INPUT_RATIO) as u64;
             let output_cost =
@@ -565,50 +1349,126 @@ fn show_cost_estimate(
                 total_cost_estimated: total,
             }
         })
-        .collect();
+        .collect())
+}
 
-    model_costs.sort_by(|a, b| {
-        b.total_cost_estimated
-            .partial_cmp(&a.total_cost_estimated)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+/// Per-model totals of the sessions in the cost ledger
+fn ledger_model_costs(
+    entries: &[CostEntry],
+    model: Option<&str>,
+    aggregate_models: bool,
+) -> Vec<ModelCostOutput> {
+    let pattern = model.map(str::to_lowercase);
+    let mut by_model: Vec<ModelCostOutput> = Vec::new();
+
+    for entry in entries {
+        if let Some(ref pattern) = pattern {
+            if !entry.model.to_lowercase().contains(pattern) {
+                continue;
+            }
+        }
+        let name = if aggregate_models {
+            base_model_id(&entry.model)
+        } else {
+            entry.model.clone()
+        };
+
+        let row = match by_model.iter().position(|m| m.name == name) {
+            Some(i) => &mut by_model[i],
+            None => {
+                by_model.push(ModelCostOutput {
+                    name,
+                    input_tokens: 0,
+                    input_cost: 0.0,
+                    output_cost_estimated: 0.0,
+                    total_cost_estimated: 0.0,
+                });
+                by_model.last_mut().expect("just pushed")
+            }
+        };
+        row.input_tokens += entry.input_tokens;
+        row.input_cost += entry.input_cost;
+        row.output_cost_estimated += entry.output_cost;
+        row.total_cost_estimated += entry.total_cost();
+    }
 
-    let total_estimated: f64 = model_costs.iter().map(|m| m.total_cost_estimated).sum();
+    by_model
+}
 
-    let output = CostOutput {
-        profile: profile_display.clone(),
-        days,
-        models: model_costs,
-        total_estimated,
-    };
+#[derive(Debug, Serialize)]
+struct PricingOutput {
+    models: Vec<PricingEntry>,
+    fallback: ModelPricing,
+}
+
+/// Print the effective pricing table (built-in prices merged with `pricing.toml`).
+pub fn handle_show_pricing(format: OutputFormat) -> Result<(), RafctlError> {
+    let table = load_pricing()?;
 
     match format {
         OutputFormat::Json => {
-            print_json(&output);
+            print_json(&PricingOutput {
+                models: table.entries().to_vec(),
+                fallback: FALLBACK_PRICING,
+            })?;
         }
-        OutputFormat::Plain => {
-            print_plain_cost(&output);
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("PATTERN\tINPUT_PER_M\tOUTPUT_PER_M\tSOURCE");
+            for entry in table.entries() {
+                println!(
+                    "{}\t{:.2}\t{:.2}\t{}",
+                    entry.pattern,
+                    entry.pricing.input_per_million,
+                    entry.pricing.output_per_million,
+                    pricing_source_label(entry.source)
+                );
+            }
+            println!(
+                "*\t{:.2}\t{:.2}\tfallback",
+                FALLBACK_PRICING.input_per_million, FALLBACK_PRICING.output_per_million
+            );
         }
         OutputFormat::Human => {
-            print_human_cost(&output);
+            println!(
+                "\n{} {}\n",
+                "💲".cyan(),
+                "Model Pricing (per 1M tokens)".bold()
+            );
+
+            let mut table_out = Table::new();
+            table_out.load_preset(UTF8_FULL_CONDENSED);
+            table_out.set_header(vec!["Model pattern", "Input", "Output", "Source"]);
+            for entry in table.entries() {
+                let source = match entry.source {
+                    PricingSource::Override => Cell::new("override").fg(Color::Yellow),
+                    PricingSource::Builtin => Cell::new("built-in"),
+                };
+                table_out.add_row(vec![
+                    Cell::new(&entry.pattern),
+                    Cell::new(format!("${:.2}", entry.pricing.input_per_million)),
+                    Cell::new(format!("${:.2}", entry.pricing.output_per_million)),
+                    source,
+                ]);
+            }
+            println!("{table_out}");
+            println!(
+                "  {}",
+                format!(
+                    "Other models: ${:.2} input / ${:.2} output",
+                    FALLBACK_PRICING.input_per_million, FALLBACK_PRICING.output_per_million
+                )
+                .dimmed()
+            );
         }
     }
 
     Ok(())
 }
 
-fn get_model_pricing(model_name: &str) -> ModelPricing {
-    for (pattern, pricing) in PRICING {
-        if model_name.contains(pattern) {
-            return ModelPricing {
-                input_per_million: pricing.input_per_million,
-                output_per_million: pricing.output_per_million,
-            };
-        }
-    }
-    ModelPricing {
-        input_per_million: 3.0,
-        output_per_million: 15.0,
+fn pricing_source_label(source: PricingSource) -> &'static str {
+    match source {
+        PricingSource::Builtin => "builtin",
+        PricingSource::Override => "override",
     }
 }
 
@@ -618,11 +1478,22 @@ fn print_human_cost(output: &CostOutput) {
         .as_ref()
         .map(|p| format!(" — Profile: {}", p))
         .unwrap_or_default();
+    let model_str = output
+        .model
+        .as_ref()
+        .map(|m| format!(" — Model: {}", m))
+        .unwrap_or_default();
+
+    let (title, approx) = match output.source {
+        CostSource::Ledger => ("Recorded Costs", ""),
+        CostSource::Estimate => ("Estimated Costs", "~"),
+        CostSource::Mixed => ("Recorded + Estimated Costs", "~"),
+    };
 
     println!(
         "\n{} {} (last {} days)\n",
         "💰".cyan(),
-        format!("Estimated Costs{}", profile_str).bold(),
+        format!("{}{}{}", title, profile_str, model_str).bold(),
         output.days
     );
 
@@ -641,8 +1512,8 @@ fn print_human_cost(output: &CostOutput) {
             Cell::new(shorten_model_name(&model.name)),
             Cell::new(format_tokens(model.input_tokens)),
             Cell::new(format!("${:.2}", model.input_cost)),
-            Cell::new(format!("~${:.2}", model.output_cost_estimated)),
-            Cell::new(format!("~${:.2}", model.total_cost_estimated)).fg(Color::Cyan),
+            Cell::new(format!("{}${:.2}", approx, model.output_cost_estimated)),
+            Cell::new(format!("{}${:.2}", approx, model.total_cost_estimated)).fg(Color::Cyan),
         ]);
     }
 
@@ -651,23 +1522,28 @@ fn print_human_cost(output: &CostOutput) {
         Cell::new(""),
         Cell::new(""),
         Cell::new("Total:").fg(Color::Yellow),
-        Cell::new(format!("~${:.2}", output.total_estimated)).fg(Color::Yellow),
+        Cell::new(format!("{}${:.2}", approx, output.total_estimated)).fg(Color::Yellow),
     ]);
 
     println!("{table}\n");
 
-    println!(
-        "{}",
-        "* Output tokens estimated at 3:1 ratio (not tracked locally)".dimmed()
-    );
+    let note = match output.source {
+        CostSource::Ledger => "* From sessions recorded with `rafctl run --capture-cost`",
+        CostSource::Estimate => "* Output tokens estimated at 3:1 ratio (not tracked locally)",
+        CostSource::Mixed => {
+            "* Recorded by `rafctl run --capture-cost` where available; other days estimated at a 3:1 output ratio"
+        }
+    };
+    println!("{}", note.dimmed());
     println!();
 }
 
 fn print_plain_cost(output: &CostOutput) {
     println!(
-        "PROFILE\t{}\tDAYS\t{}",
+        "PROFILE\t{}\tDAYS\t{}\tSOURCE\t{}",
         output.profile.as_deref().unwrap_or("global"),
-        output.days
+        output.days,
+        output.source.as_str()
     );
     println!("MODEL\tINPUT_TOKENS\tINPUT_COST\tOUTPUT_COST_EST\tTOTAL_EST");
     for model in &output.models {
@@ -703,6 +1579,204 @@ mod tests {
         assert_eq!(shorten_model_name("claude-haiku-3-5"), "haiku 3.5");
     }
 
+    #[test]
+    fn test_ledger_model_costs() {
+        let entry = |model: &str, input_cost: f64| CostEntry {
+            recorded_at: Utc::now(),
+            session_id: "s".to_string(),
+            model: model.to_string(),
+            input_tokens: 1000,
+            output_tokens: 500,
+            input_cost,
+            output_cost: 1.0,
+        };
+        let entries = vec![
+            entry("claude-sonnet-4-5-20250929", 0.5),
+            entry("claude-sonnet-4-5", 0.25),
+            entry("claude-opus-4-5", 2.0),
+        ];
+
+        let rows = ledger_model_costs(&entries, None, true);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].name, "claude-sonnet-4-5");
+        assert_eq!(rows[0].input_tokens, 2000);
+        assert!((rows[0].total_cost_estimated - 2.75).abs() < 1e-9);
+
+        let rows = ledger_model_costs(&entries, Some("OPUS"), false);
+        assert_eq!(rows.len(), 1);
+        assert!((rows[0].total_cost_estimated - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_zero_filled_activity() {
+        let stats: StatsCache = serde_json::from_str(
+            r#"{
+                "dailyActivity": [
+                    {"date": "2026-01-06", "messageCount": 10, "sessionCount": 2},
+                    {"date": "2026-01-04", "messageCount": 5, "sessionCount": 1}
+                ],
+                "dailyModelTokens": [
+                    {"date": "2026-01-06", "tokensByModel": {"claude-sonnet-4-5": 1000}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 1, 6).unwrap();
+
+        let rows = zero_filled_activity(&stats, 4, today);
+        let dates: Vec<&str> = rows.iter().map(|r| r.date.as_str()).collect();
+        assert_eq!(
+            dates,
+            vec!["2026-01-06", "2026-01-05", "2026-01-04", "2026-01-03"]
+        );
+        assert_eq!(rows[0].messages, 10);
+        assert_eq!(rows[0].tokens, 1000);
+        assert_eq!(rows[1].messages, 0);
+        assert_eq!(rows[2].sessions, 1);
+        assert_eq!(rows[3].tools, 0);
+    }
+
+    #[test]
+    fn test_build_analytics_output_model_filter() {
+        let stats: StatsCache = serde_json::from_str(
+            r#"{
+                "dailyActivity": [
+                    {"date": "2026-01-06", "messageCount": 10, "sessionCount": 2},
+                    {"date": "2026-01-05", "messageCount": 5, "sessionCount": 1}
+                ],
+                "dailyModelTokens": [
+                    {"date": "2026-01-06", "tokensByModel": {"claude-sonnet-4-5": 450000, "claude-opus-4-5": 50000}},
+                    {"date": "2026-01-05", "tokensByModel": {"claude-sonnet-4-5": 320000}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let output = build_analytics_output(
+            &stats,
+            None,
+            Some("opus"),
+            false,
+            7,
+            false,
+            Granularity::Day,
+        );
+
+        assert_eq!(output.models.len(), 1);
+        assert_eq!(output.models[0].name, "claude-opus-4-5");
+        assert_eq!(output.models[0].percentage, 100.0);
+        let tokens: Vec<u64> = output.daily_activity.iter().map(|d| d.tokens).collect();
+        assert_eq!(tokens, vec![50000, 0]);
+        assert_eq!(output.totals.tokens, 50000);
+        assert_eq!(output.totals.messages, 15);
+    }
+
+    #[test]
+    fn test_build_analytics_output_aggregate_models() {
+        let stats: StatsCache = serde_json::from_str(
+            r#"{
+                "dailyActivity": [{"date": "2026-01-06", "messageCount": 10, "sessionCount": 2}],
+                "dailyModelTokens": [
+                    {"date": "2026-01-06", "tokensByModel": {"claude-sonnet-4-5-20250929": 300, "claude-sonnet-4-5": 100, "claude-opus-4-5": 100}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let output = build_analytics_output(&stats, None, None, true, 7, false, Granularity::Day);
+
+        let models: Vec<(&str, u64)> = output
+            .models
+            .iter()
+            .map(|m| (m.name.as_str(), m.tokens))
+            .collect();
+        assert_eq!(
+            models,
+            vec![("claude-sonnet-4-5", 400), ("claude-opus-4-5", 100)]
+        );
+        assert_eq!(output.models[0].percentage, 80.0);
+        assert_eq!(output.totals.tokens, 500);
+    }
+
+    #[test]
+    fn test_build_error_rows_groups_by_day() {
+        let now = Utc::now();
+        let summary = |age_days: i64, calls: u64, errors: u64| SessionSummary {
+            session_id: String::new(),
+            project_path: None,
+            cwd: None,
+            git_branch: None,
+            started_at: Some(now - Duration::days(age_days)),
+            ended_at: None,
+            message_count: 1,
+            tool_calls: calls,
+            tool_errors: errors,
+            agent_calls: 0,
+            model: None,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            has_usage: true,
+        };
+
+        let rows = build_error_rows(
+            &[
+                summary(0, 10, 1),
+                summary(0, 10, 3),
+                summary(2, 4, 0),
+                summary(30, 100, 100),
+            ],
+            7,
+            now,
+        );
+
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].date > rows[1].date);
+        assert_eq!(rows[0].sessions, 2);
+        assert_eq!(rows[0].tool_calls, 20);
+        assert_eq!(rows[0].tool_errors, 4);
+        assert!((rows[0].error_rate - 20.0).abs() < f64::EPSILON);
+        assert_eq!(rows[1].error_rate, 0.0);
+        assert_eq!(error_rate(0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_build_session_rows_sorts_and_filters() {
+        let now = Utc::now();
+        let summary = |id: &str, age_days: i64, input: u64| SessionSummary {
+            session_id: id.to_string(),
+            project_path: None,
+            cwd: None,
+            git_branch: None,
+            started_at: Some(now - Duration::days(age_days)),
+            ended_at: None,
+            message_count: 1,
+            tool_calls: 0,
+            tool_errors: 0,
+            agent_calls: 0,
+            model: None,
+            input_tokens: input,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            has_usage: true,
+        };
+
+        let rows = build_session_rows(
+            vec![
+                summary("small", 1, 100),
+                summary("old", 30, 1_000_000),
+                summary("big", 2, 5_000),
+            ],
+            7,
+            now,
+        );
+
+        let ids: Vec<&str> = rows.iter().map(|r| r.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["big", "small"]);
+    }
+
     #[test]
     fn test_progress_bar() {
         let bar = progress_bar(50.0, 10);
@@ -1,15 +1,22 @@
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::process::{Command, Stdio};
+use std::time::Duration;
 
 use colored::Colorize;
 use rpassword::read_password;
+use serde::Serialize;
 
+use super::output::{confirm, print_json, warn_corrupted_profile};
+use super::OutputFormat;
+use crate::core::constants::{ANTHROPIC_API_VERSION, ANTHROPIC_MODELS_API, API_TIMEOUT_SECS};
 use crate::core::credentials::{self, CredentialType};
 use crate::core::profile::{
     list_profiles, load_profile, profile_exists, resolve_profile_alias, save_profile, AuthMode,
-    ToolType,
+    Profile, ToolType,
 };
 use crate::error::RafctlError;
+#[cfg(target_os = "macos")]
+use crate::tools::keychain;
 use crate::tools::{check_tool_available, is_authenticated};
 
 pub fn handle_login(profile_name: &str) -> Result<(), RafctlError> {
@@ -71,14 +78,64 @@ pub fn handle_login(profile_name: &str) -> Result<(), RafctlError> {
     }
 }
 
-pub fn handle_status(profile_name: Option<&str>) -> Result<(), RafctlError> {
+#[derive(Serialize)]
+struct AuthStatus {
+    profile: String,
+    tool: String,
+    authenticated: bool,
+    auth_mode: Option<String>,
+    last_used: Option<String>,
+    /// Whole days since the profile was last run
+    staleness_days: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct AuthStatusOutput {
+    profiles: Vec<AuthStatus>,
+}
+
+impl AuthStatus {
+    fn new(profile: &Profile, authenticated: bool) -> Self {
+        Self {
+            profile: profile.name.clone(),
+            tool: profile.tool.to_string(),
+            authenticated,
+            auth_mode: (profile.tool == ToolType::Claude).then(|| profile.auth_mode.to_string()),
+            last_used: profile
+                .last_used
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+            staleness_days: profile
+                .last_used
+                .map(|dt| (chrono::Utc::now() - dt).num_days()),
+        }
+    }
+}
+
+fn print_auth_status_plain(statuses: &[AuthStatus]) {
+    println!("PROFILE\tTOOL\tAUTH_MODE\tAUTHENTICATED\tLAST_USED\tSTALENESS_DAYS");
+    for s in statuses {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            s.profile,
+            s.tool,
+            s.auth_mode.as_deref().unwrap_or("-"),
+            if s.authenticated { "yes" } else { "no" },
+            s.last_used.as_deref().unwrap_or("never"),
+            s.staleness_days
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| "-".to_string())
+        );
+    }
+}
+
+pub fn handle_status(profile_name: Option<&str>, format: OutputFormat) -> Result<(), RafctlError> {
     match profile_name {
-        Some(name) => show_single_status(name),
-        None => show_all_status(),
+        Some(name) => show_single_status(name, format),
+        None => show_all_status(format),
     }
 }
 
-fn show_single_status(profile_name: &str) -> Result<(), RafctlError> {
+fn show_single_status(profile_name: &str, format: OutputFormat) -> Result<(), RafctlError> {
     let name_lower = profile_name.to_lowercase();
 
     if !profile_exists(&name_lower)? {
@@ -88,19 +145,81 @@ fn show_single_status(profile_name: &str) -> Result<(), RafctlError> {
     let profile = load_profile(&name_lower)?;
     let authenticated = is_authenticated(profile.tool, &name_lower)?;
 
+    match format {
+        OutputFormat::Json => {
+            print_json(&AuthStatus::new(&profile, authenticated))?;
+            return Ok(());
+        }
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            print_auth_status_plain(&[AuthStatus::new(&profile, authenticated)]);
+            return Ok(());
+        }
+        OutputFormat::Human => {}
+    }
+
     println!("{}", format!("Profile: {}", profile.name).bold());
     println!("  Tool: {}", profile.tool);
 
+    if profile.tool == ToolType::Claude && profile.auth_mode == AuthMode::ApiKey {
+        let count = credentials::count_credentials(&name_lower, CredentialType::ApiKey)?;
+        if count > 0 {
+            println!(
+                "  API keys: {} stored, key {} active",
+                count,
+                profile.active_api_key.unwrap_or(0) + 1
+            );
+        }
+    }
+
     if authenticated {
         println!("  Auth: {} Authenticated", "✓".green());
 
-        if let Some(last_used) = profile.last_used {
-            let days_ago = (chrono::Utc::now() - last_used).num_days();
-            if days_ago > 7 {
-                println!(
-                    "  {}",
-                    format!("⚠ Last used {} days ago - auth may need refresh", days_ago).yellow()
-                );
+        let token = credentials::get_credential(&name_lower, CredentialType::OAuthToken)
+            .ok()
+            .flatten();
+        let expiry = token.as_deref().and_then(credentials::oauth_token_expiry);
+        let refreshable = token
+            .as_deref()
+            .is_some_and(credentials::oauth_token_refreshable);
+
+        match expiry {
+            // expiresAt only covers the access token, which Claude Code renews itself
+            Some(_) if refreshable => {
+                println!("  Token: access token refreshes automatically");
+            }
+            Some(expires_at) => {
+                let remaining = expires_at - chrono::Utc::now();
+                if remaining <= chrono::Duration::zero() {
+                    println!(
+                        "  {}",
+                        format!(
+                            "✗ Token expired {} ago - run: rafctl auth login {}",
+                            format_remaining(-remaining),
+                            name_lower
+                        )
+                        .red()
+                    );
+                } else if remaining < chrono::Duration::hours(1) {
+                    println!(
+                        "  {}",
+                        format!("⚠ Token expires in {}", format_remaining(remaining)).yellow()
+                    );
+                } else {
+                    println!("  Token: expires in {}", format_remaining(remaining));
+                }
+            }
+            None => {
+                // No readable expiry; fall back to how long the profile has been idle
+                if let Some(last_used) = profile.last_used {
+                    let days_ago = (chrono::Utc::now() - last_used).num_days();
+                    if days_ago > 7 {
+                        println!(
+                            "  {}",
+                            format!("⚠ Last used {} days ago - auth may need refresh", days_ago)
+                                .yellow()
+                        );
+                    }
+                }
             }
         }
     } else {
@@ -114,12 +233,45 @@ fn show_single_status(profile_name: &str) -> Result<(), RafctlError> {
     Ok(())
 }
 
-fn show_all_status() -> Result<(), RafctlError> {
+/// Compact "3d", "5h", "42m" rendering of a positive duration.
+fn format_remaining(duration: chrono::Duration) -> String {
+    if duration.num_days() > 0 {
+        format!("{}d", duration.num_days())
+    } else if duration.num_hours() > 0 {
+        format!("{}h", duration.num_hours())
+    } else {
+        format!("{}m", duration.num_minutes().max(1))
+    }
+}
+
+fn show_all_status(format: OutputFormat) -> Result<(), RafctlError> {
     let profiles = list_profiles()?;
 
+    if format != OutputFormat::Human {
+        let statuses: Vec<AuthStatus> = profiles
+            .iter()
+            .filter_map(|name| match load_profile(name) {
+                Ok(profile) => {
+                    let authenticated = is_authenticated(profile.tool, name).unwrap_or(false);
+                    Some(AuthStatus::new(&profile, authenticated))
+                }
+                Err(e) => {
+                    warn_corrupted_profile(name, &e, format);
+                    None
+                }
+            })
+            .collect();
+        if format == OutputFormat::Json {
+            print_json(&AuthStatusOutput { profiles: statuses })?;
+        } else {
+            print_auth_status_plain(&statuses);
+        }
+        return Ok(());
+    }
+
     if profiles.is_empty() {
         println!(
-            "No profiles found. Create one with: rafctl profile add <name> --tool <claude|codex>"
+            "No profiles found. Create one with: rafctl profile add <name> --tool <claude|codex|gemini>"
         );
         return Ok(());
     }
@@ -135,11 +287,22 @@ fn show_all_status() -> Result<(), RafctlError> {
                 } else {
                     "✗".red()
                 };
-                let status_text = if authenticated {
+                let mut status_text = if authenticated {
                     "authenticated".to_string()
                 } else {
                     "not authenticated".to_string()
                 };
+                if profile.auth_mode == AuthMode::ApiKey {
+                    let count =
+                        credentials::count_credentials(&name, CredentialType::ApiKey).unwrap_or(0);
+                    if count > 1 {
+                        status_text.push_str(&format!(
+                            " (key {} of {})",
+                            profile.active_api_key.unwrap_or(0) + 1,
+                            count
+                        ));
+                    }
+                }
                 println!(
                     "  {} {} [{}]: {}",
                     status_icon, profile.name, profile.tool, status_text
@@ -169,84 +332,458 @@ pub fn handle_logout(profile_name: &str, dry_run: bool) -> Result<(), RafctlErro
         if credentials::has_credential(&name_lower, CredentialType::OAuthToken)? {
             println!("  • Would delete OAuth token from keyring");
         }
-        if credentials::has_credential(&name_lower, CredentialType::ApiKey)? {
-            println!("  • Would delete API key from keyring");
+        match credentials::count_credentials(&name_lower, CredentialType::ApiKey)? {
+            0 => {}
+            1 => println!("  • Would delete API key from keyring"),
+            n => println!("  • Would delete {} API keys from keyring", n),
+        }
+        #[allow(deprecated)]
+        if profile.api_key.is_some() {
+            println!("  • Would remove plaintext API key from meta.yaml");
         }
         return Ok(());
     }
 
-    let mut removed_something = false;
+    let mut cleared: Vec<&str> = Vec::new();
 
     if cred_path.exists() {
         std::fs::remove_file(&cred_path).map_err(|e| RafctlError::ConfigWrite {
             path: cred_path,
             source: e,
         })?;
-        removed_something = true;
+        cleared.push("credential file");
     }
 
-    if credentials::has_credential(&name_lower, CredentialType::OAuthToken)? {
-        credentials::delete_credential(&name_lower, CredentialType::OAuthToken)?;
-        removed_something = true;
+    for (cred_type, label) in [
+        (CredentialType::OAuthToken, "OAuth token (keyring)"),
+        (CredentialType::ApiKey, "API key (keyring)"),
+    ] {
+        match credentials::delete_all_credentials(&name_lower, cred_type) {
+            Ok(0) => {}
+            Ok(_) => cleared.push(label),
+            Err(e) => eprintln!("{} Could not clear {}: {}", "⚠".yellow(), label, e),
+        }
     }
 
-    if credentials::has_credential(&name_lower, CredentialType::ApiKey)? {
-        credentials::delete_credential(&name_lower, CredentialType::ApiKey)?;
-        removed_something = true;
+    #[allow(deprecated)]
+    if profile.api_key.is_some() {
+        let mut updated_profile = profile;
+        updated_profile.api_key = None;
+        save_profile(&updated_profile)?;
+        cleared.push("API key (meta.yaml)");
     }
 
-    if removed_something {
-        println!("{} Logged out of '{}'", "✓".green(), name_lower);
-    } else {
+    if cleared.is_empty() {
         println!(
             "{} Profile '{}' is not authenticated",
             "ℹ".cyan(),
             name_lower
         );
+    } else {
+        println!("{} Logged out of '{}'", "✓".green(), name_lower);
+        for item in &cleared {
+            println!("  • Cleared {}", item);
+        }
     }
 
     Ok(())
 }
 
-pub fn handle_set_key(profile_name: &str, api_key: Option<&str>) -> Result<(), RafctlError> {
+pub fn handle_migrate() -> Result<(), RafctlError> {
+    let mut migrated: Vec<String> = Vec::new();
+    let mut failed = 0;
+
+    for name in list_profiles()? {
+        let mut profile = match load_profile(&name) {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+
+        #[allow(deprecated)]
+        let Some(api_key) = profile.api_key.clone() else {
+            continue;
+        };
+
+        if let Err(e) = credentials::migrate_api_key_to_keyring(&name, &api_key) {
+            eprintln!("{} Failed to migrate '{}': {}", "✗".red(), name, e);
+            failed += 1;
+            continue;
+        }
+
+        #[allow(deprecated)]
+        {
+            profile.api_key = None;
+        }
+        save_profile(&profile)?;
+        println!("{} Migrated API key for '{}'", "✓".green(), name);
+        migrated.push(name);
+    }
+
+    if migrated.is_empty() && failed == 0 {
+        println!("{} No plaintext API keys found in profiles", "ℹ".cyan());
+        return Ok(());
+    }
+
+    println!(
+        "\n{} {} API key(s) moved to secure storage{}",
+        "ℹ".cyan(),
+        migrated.len(),
+        if failed > 0 {
+            format!(", {} failed", failed)
+        } else {
+            String::new()
+        }
+    );
+
+    if !migrated.is_empty() {
+        println!(
+            "{} These keys were stored in plaintext in meta.yaml; consider rotating them if the files were ever shared or backed up",
+            "⚠".yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// Outcome of checking an API key against the Anthropic API.
+#[derive(Debug, PartialEq)]
+enum KeyValidation {
+    Valid,
+    Rejected,
+    /// Network error or unexpected response; the key could not be checked
+    Unknown(String),
+}
+
+fn verify_api_key(key: &str) -> KeyValidation {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build();
+
+    let result = agent
+        .get(ANTHROPIC_MODELS_API)
+        .set("x-api-key", key)
+        .set("anthropic-version", ANTHROPIC_API_VERSION)
+        .set(
+            "User-Agent",
+            &format!("rafctl/{}", env!("CARGO_PKG_VERSION")),
+        )
+        .call();
+
+    match result {
+        Ok(response) => validation_for_status(response.status()),
+        Err(ureq::Error::Status(code, _)) => validation_for_status(code),
+        Err(e) => KeyValidation::Unknown(e.to_string()),
+    }
+}
+
+fn validation_for_status(status: u16) -> KeyValidation {
+    match status {
+        200..=299 => KeyValidation::Valid,
+        401 => KeyValidation::Rejected,
+        code => KeyValidation::Unknown(format!("unexpected HTTP status {}", code)),
+    }
+}
+
+/// Register the Claude Code OAuth session currently in the macOS keychain
+/// under a rafctl profile.
+pub fn handle_import_token(profile_name: &str) -> Result<(), RafctlError> {
     let resolved_name = resolve_profile_alias(profile_name)?;
     let name_lower = resolved_name.to_lowercase();
 
     let profile = load_profile(&name_lower)?;
 
+    if profile.tool != ToolType::Claude || profile.auth_mode != AuthMode::OAuth {
+        return Err(RafctlError::NotOAuthProfile(name_lower));
+    }
+
+    import_claude_token(&name_lower)?;
+
+    println!(
+        "{} Imported current Claude OAuth token into profile '{}'",
+        "✓".green(),
+        name_lower
+    );
+
+    Ok(())
+}
+
+/// Put back the system Claude token saved before the last OAuth launch.
+pub fn handle_restore_system() -> Result<(), RafctlError> {
+    if credentials::restore_claude_system_token()? {
+        println!("{} Restored the system Claude login", "✓".green());
+    } else {
+        println!("{} No saved system Claude login to restore", "ℹ".cyan());
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct TokenExport<'a> {
+    profile: &'a str,
+    token: &'a str,
+}
+
+/// Print a Claude profile's stored OAuth token so scripts can reuse it.
+///
+/// Refuses unless `confirmed`, and keeps the token out of `--json` output
+/// unless `json_token` is also set, since JSON tends to end up in logs.
+pub fn handle_export_token(
+    profile_name: &str,
+    confirmed: bool,
+    json_token: bool,
+    format: OutputFormat,
+) -> Result<(), RafctlError> {
+    if !confirmed {
+        return Err(RafctlError::MissingArgument(
+            "--yes-i-understand-this-is-secret (the token is a live credential)".to_string(),
+        ));
+    }
+    if format == OutputFormat::Json && !json_token {
+        return Err(RafctlError::MissingArgument(
+            "--json-token (required to include the token in --json output)".to_string(),
+        ));
+    }
+
+    let resolved_name = resolve_profile_alias(profile_name)?;
+    let name_lower = resolved_name.to_lowercase();
+    let profile = load_profile(&name_lower)?;
+
+    if profile.tool != ToolType::Claude || profile.auth_mode != AuthMode::OAuth {
+        return Err(RafctlError::NotOAuthProfile(name_lower));
+    }
+
+    let token = credentials::get_credential(&name_lower, CredentialType::OAuthToken)?
+        .ok_or_else(|| RafctlError::NotAuthenticated(name_lower.clone()))?;
+
+    match format {
+        OutputFormat::Json => print_json(&TokenExport {
+            profile: &name_lower,
+            token: &token,
+        })?,
+        _ => println!("{}", token),
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn import_claude_token(profile_name: &str) -> Result<(), RafctlError> {
+    keychain::capture_oauth_from_claude(profile_name)?;
+
+    // `rafctl run` reads OAuth tokens from the cross-platform store; keep it in sync
+    let token = keychain::read_oauth_token(profile_name)?
+        .ok_or_else(|| RafctlError::NotAuthenticated(profile_name.to_string()))?;
+    credentials::store_credential(profile_name, CredentialType::OAuthToken, &token)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn import_claude_token(_profile_name: &str) -> Result<(), RafctlError> {
+    Err(RafctlError::KeychainError(
+        "Importing the Claude OAuth token requires macOS for keychain access".to_string(),
+    ))
+}
+
+/// Read an API key from the terminal without echoing it.
+pub fn prompt_api_key(prompt: &str) -> Result<String, RafctlError> {
+    print!("{}", prompt);
+    let _ = io::stdout().flush();
+    let key = read_password().map_err(|e| RafctlError::ConfigRead {
+        path: std::path::PathBuf::from("stdin"),
+        source: e,
+    })?;
+    Ok(key.trim().to_string())
+}
+
+pub fn handle_set_key(
+    profile_name: &str,
+    api_key: Option<&str>,
+    no_verify: bool,
+) -> Result<(), RafctlError> {
+    let resolved_name = resolve_profile_alias(profile_name)?;
+    let name_lower = resolved_name.to_lowercase();
+
+    let profile = load_profile(&name_lower)?;
+    if !supports_api_keys(&profile) {
+        return Ok(());
+    }
+
+    let Some((key, verified)) = read_api_key(api_key, no_verify)? else {
+        return Ok(());
+    };
+
+    credentials::store_indexed_credential(
+        &name_lower,
+        CredentialType::ApiKey,
+        profile.active_api_key.unwrap_or(0),
+        &key,
+    )?;
+
+    #[allow(deprecated)]
+    if profile.api_key.is_some() {
+        let mut updated_profile = profile;
+        updated_profile.api_key = None;
+        save_profile(&updated_profile)?;
+        println!(
+            "{} Migrated API key from plaintext to secure storage",
+            "ℹ".cyan()
+        );
+    }
+
+    if verified {
+        println!(
+            "{} API key verified and set for profile '{}' (stored securely)",
+            "✓".green(),
+            name_lower
+        );
+    } else {
+        println!(
+            "{} API key set for profile '{}' (stored securely, not verified)",
+            "ℹ".cyan(),
+            name_lower
+        );
+    }
+
+    Ok(())
+}
+
+pub fn handle_add_key(
+    profile_name: &str,
+    api_key: Option<&str>,
+    no_verify: bool,
+) -> Result<(), RafctlError> {
+    let resolved_name = resolve_profile_alias(profile_name)?;
+    let name_lower = resolved_name.to_lowercase();
+
+    let mut profile = load_profile(&name_lower)?;
+    if !supports_api_keys(&profile) {
+        return Ok(());
+    }
+
+    let Some((key, verified)) = read_api_key(api_key, no_verify)? else {
+        return Ok(());
+    };
+
+    let mut count = credentials::count_credentials(&name_lower, CredentialType::ApiKey)?;
+
+    // A plaintext key is the profile's first key; move it into the keyring
+    // before appending so it keeps index 0
+    #[allow(deprecated)]
+    if let Some(legacy) = profile.api_key.take() {
+        if count == 0 {
+            credentials::store_credential(&name_lower, CredentialType::ApiKey, &legacy)?;
+            count = 1;
+        }
+        save_profile(&profile)?;
+        println!(
+            "{} Migrated API key from plaintext to secure storage",
+            "ℹ".cyan()
+        );
+    }
+
+    credentials::store_indexed_credential(&name_lower, CredentialType::ApiKey, count, &key)?;
+
+    println!(
+        "{} Added API key {} of {} to profile '{}' ({})",
+        "✓".green(),
+        count + 1,
+        count + 1,
+        name_lower,
+        if verified { "verified" } else { "not verified" }
+    );
+    if count > 0 {
+        println!(
+            "  {}",
+            format!("Switch keys with: rafctl auth rotate-key {}", name_lower).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+pub fn handle_rotate_key(profile_name: &str) -> Result<(), RafctlError> {
+    let resolved_name = resolve_profile_alias(profile_name)?;
+    let name_lower = resolved_name.to_lowercase();
+
+    let mut profile = load_profile(&name_lower)?;
+    if !supports_api_keys(&profile) {
+        return Ok(());
+    }
+
+    let count = credentials::count_credentials(&name_lower, CredentialType::ApiKey)?;
+    if count == 0 {
+        return Err(RafctlError::NoApiKey(name_lower));
+    }
+    if count == 1 {
+        println!(
+            "{} Profile '{}' has only one API key. Add another with: rafctl auth add-key {}",
+            "ℹ".cyan(),
+            name_lower,
+            name_lower
+        );
+        return Ok(());
+    }
+
+    let next = next_key_index(profile.active_api_key, count);
+    profile.active_api_key = (next > 0).then_some(next);
+    save_profile(&profile)?;
+
+    println!(
+        "{} Profile '{}' now uses API key {} of {}",
+        "✓".green(),
+        name_lower,
+        next + 1,
+        count
+    );
+
+    Ok(())
+}
+
+/// Index after `active`, wrapping around; an out-of-range index restarts at 0
+fn next_key_index(active: Option<usize>, count: usize) -> usize {
+    match active {
+        Some(index) if index < count => (index + 1) % count,
+        _ if count > 1 => 1,
+        _ => 0,
+    }
+}
+
+/// Whether the profile can hold API keys, printing why not otherwise
+fn supports_api_keys(profile: &Profile) -> bool {
     if profile.tool != ToolType::Claude {
         eprintln!(
             "{} API key mode only supported for Claude profiles",
             "✗".red()
         );
-        return Ok(());
+        return false;
     }
 
     if profile.auth_mode != AuthMode::ApiKey {
         eprintln!(
             "{} Profile '{}' is in OAuth mode. Recreate with: rafctl profile add {} --tool claude --auth-mode api-key",
             "✗".red(),
-            name_lower,
-            name_lower
+            profile.name,
+            profile.name
         );
-        return Ok(());
+        return false;
     }
 
+    true
+}
+
+/// Take the key from `--key` or the terminal and check it against the API.
+/// Returns the key and whether it was verified, or `None` if the user backed out.
+fn read_api_key(
+    api_key: Option<&str>,
+    no_verify: bool,
+) -> Result<Option<(String, bool)>, RafctlError> {
     let key = match api_key {
         Some(k) => k.to_string(),
-        None => {
-            print!("Enter API key: ");
-            let _ = io::stdout().flush();
-            read_password().map_err(|e| RafctlError::ConfigRead {
-                path: std::path::PathBuf::from("stdin"),
-                source: e,
-            })?
-        }
+        None => prompt_api_key("Enter API key: ")?,
     };
 
     if key.is_empty() {
         eprintln!("{} API key cannot be empty", "✗".red());
-        return Ok(());
+        return Ok(None);
     }
 
     if !key.starts_with("sk-ant-api") {
@@ -256,24 +793,57 @@ pub fn handle_set_key(profile_name: &str, api_key: Option<&str>) -> Result<(), R
         );
     }
 
-    credentials::store_credential(&name_lower, CredentialType::ApiKey, &key)?;
+    let mut verified = false;
+    if !no_verify {
+        match verify_api_key(&key) {
+            KeyValidation::Valid => verified = true,
+            KeyValidation::Rejected => {
+                eprintln!(
+                    "{} Anthropic API rejected this key (401 Unauthorized)",
+                    "⚠".yellow()
+                );
+                // Without a terminal nobody can confirm, so refuse rather than store it
+                if !io::stdin().is_terminal() || !confirm("Store it anyway? [y/N]")? {
+                    return Err(RafctlError::ApiKeyRejected);
+                }
+            }
+            KeyValidation::Unknown(reason) => {
+                eprintln!(
+                    "{} Could not verify API key ({}), storing without verification",
+                    "⚠".yellow(),
+                    reason
+                );
+            }
+        }
+    }
 
-    #[allow(deprecated)]
-    if profile.api_key.is_some() {
-        let mut updated_profile = profile;
-        updated_profile.api_key = None;
-        save_profile(&updated_profile)?;
-        println!(
-            "{} Migrated API key from plaintext to secure storage",
-            "ℹ".cyan()
-        );
+    Ok(Some((key, verified)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_key_index_wraps() {
+        assert_eq!(next_key_index(None, 3), 1);
+        assert_eq!(next_key_index(Some(1), 3), 2);
+        assert_eq!(next_key_index(Some(2), 3), 0);
     }
 
-    println!(
-        "{} API key set for profile '{}' (stored securely)",
-        "✓".green(),
-        name_lower
-    );
+    #[test]
+    fn test_validation_for_status() {
+        assert_eq!(validation_for_status(200), KeyValidation::Valid);
+        assert_eq!(validation_for_status(401), KeyValidation::Rejected);
+        assert_eq!(
+            validation_for_status(529),
+            KeyValidation::Unknown("unexpected HTTP status 529".to_string())
+        );
+    }
 
-    Ok(())
+    #[test]
+    fn test_next_key_index_out_of_range() {
+        assert_eq!(next_key_index(Some(5), 2), 1);
+        assert_eq!(next_key_index(Some(5), 1), 0);
+    }
 }
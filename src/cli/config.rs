@@ -1,14 +1,30 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use super::output::print_json;
+use super::output::{confirm, print_json};
 use super::OutputFormat;
-use crate::core::config::{get_default_profile, load_global_config, save_global_config};
-use crate::core::profile::{get_config_dir, load_profile, profile_exists, ToolType};
+use crate::core::backup::{create_backup, read_backup_manifest, restore_backup};
+use crate::core::cache::{prune_cache, PruneReason};
+use crate::core::config::{
+    get_default_profile, load_global_config, reset_global_config, save_global_config, GlobalConfig,
+};
+use crate::core::constants::{
+    CACHE_DIR, GLOBAL_CONFIG_FILE, OAUTH_LOCK_FILE, PRICING_FILE, RECOGNIZED_ENV_VARS,
+    SNAPSHOTS_DIR,
+};
+use crate::core::lock::{inspect_lock, oauth_lock_path, LockState};
+use crate::core::profile::{
+    get_config_dir, get_profiles_dir, load_profile, profile_exists, ToolType,
+};
+use crate::core::stats::get_global_stats_path;
+use crate::core::transcript::get_global_transcripts_dir;
 use crate::error::RafctlError;
 
 #[derive(Serialize)]
@@ -22,6 +38,9 @@ pub fn handle_show(format: OutputFormat) -> Result<(), RafctlError> {
     let config = load_global_config()?;
     let config_dir = get_config_dir()?;
 
+    // Opportunistic housekeeping; never worth failing `config show` over
+    let pruned = prune_cache().unwrap_or_default();
+
     let output = ConfigOutput {
         default_profile: config.default_profile.clone(),
         last_used_profile: config.last_used_profile.clone(),
@@ -30,9 +49,9 @@ pub fn handle_show(format: OutputFormat) -> Result<(), RafctlError> {
 
     match format {
         OutputFormat::Json => {
-            print_json(&output);
+            print_json(&output)?;
         }
-        OutputFormat::Plain => {
+        OutputFormat::Plain | OutputFormat::Porcelain => {
             let default = config.default_profile.as_deref().unwrap_or("(not set)");
             let last_used = config.last_used_profile.as_deref().unwrap_or("(none)");
             println!("default_profile={}", default);
@@ -49,6 +68,158 @@ pub fn handle_show(format: OutputFormat) -> Result<(), RafctlError> {
             println!("  Last used profile: {}", last_used);
 
             println!("  Config directory:  {}", config_dir.display());
+            if !pruned.is_empty() {
+                println!(
+                    "{}",
+                    format!("Pruned {} stale cache file(s)", pruned.len()).dimmed()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct PruneCacheOutput {
+    removed: usize,
+    files: Vec<PrunedCacheFile>,
+}
+
+#[derive(Serialize)]
+struct PrunedCacheFile {
+    path: String,
+    reason: &'static str,
+}
+
+pub fn handle_prune_cache(format: OutputFormat) -> Result<(), RafctlError> {
+    let files: Vec<PrunedCacheFile> = prune_cache()?
+        .into_iter()
+        .map(|p| PrunedCacheFile {
+            path: p.path.display().to_string(),
+            reason: match p.reason {
+                PruneReason::Orphaned => "orphaned",
+                PruneReason::Expired => "expired",
+            },
+        })
+        .collect();
+
+    match format {
+        OutputFormat::Json => print_json(&PruneCacheOutput {
+            removed: files.len(),
+            files,
+        })?,
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("REASON\tPATH");
+            for f in &files {
+                println!("{}\t{}", f.reason, f.path);
+            }
+        }
+        OutputFormat::Human => {
+            if files.is_empty() {
+                println!("{} Cache is already clean", "ℹ".cyan());
+                return Ok(());
+            }
+            for f in &files {
+                println!("  {} {}", f.path, format!("({})", f.reason).dimmed());
+            }
+            println!("{} Removed {} cache file(s)", "✓".green(), files.len());
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct PathsOutput {
+    config_dir: String,
+    profiles_dir: String,
+    config_file: String,
+    transcripts_dir: Option<String>,
+    stats_cache: Option<String>,
+    oauth_lock: String,
+    cache_dir: String,
+    pricing_file: String,
+    snapshots_dir: String,
+}
+
+pub fn handle_paths(format: OutputFormat) -> Result<(), RafctlError> {
+    let config_dir = get_config_dir()?;
+    let profiles_dir = get_profiles_dir()?;
+    let transcripts_dir = get_global_transcripts_dir();
+    let stats_cache = get_global_stats_path().ok();
+
+    let entries: Vec<(&str, &str, Option<PathBuf>)> = vec![
+        ("config_dir", "Config directory", Some(config_dir.clone())),
+        (
+            "profiles_dir",
+            "Profiles directory",
+            Some(profiles_dir.clone()),
+        ),
+        (
+            "config_file",
+            "Global config",
+            Some(config_dir.join(GLOBAL_CONFIG_FILE)),
+        ),
+        ("transcripts_dir", "Transcripts", transcripts_dir.clone()),
+        ("stats_cache", "Stats cache", stats_cache.clone()),
+        (
+            "oauth_lock",
+            "OAuth lock",
+            Some(config_dir.join(OAUTH_LOCK_FILE)),
+        ),
+        (
+            "cache_dir",
+            "Cache directory",
+            Some(config_dir.join(CACHE_DIR)),
+        ),
+        (
+            "pricing_file",
+            "Pricing overrides",
+            Some(config_dir.join(PRICING_FILE)),
+        ),
+        (
+            "snapshots_dir",
+            "Config snapshots",
+            Some(config_dir.join(SNAPSHOTS_DIR)),
+        ),
+    ];
+
+    match format {
+        OutputFormat::Json => {
+            print_json(&PathsOutput {
+                config_dir: config_dir.display().to_string(),
+                profiles_dir: profiles_dir.display().to_string(),
+                config_file: config_dir.join(GLOBAL_CONFIG_FILE).display().to_string(),
+                transcripts_dir: transcripts_dir.map(|p| p.display().to_string()),
+                stats_cache: stats_cache.map(|p| p.display().to_string()),
+                oauth_lock: config_dir.join(OAUTH_LOCK_FILE).display().to_string(),
+                cache_dir: config_dir.join(CACHE_DIR).display().to_string(),
+                pricing_file: config_dir.join(PRICING_FILE).display().to_string(),
+                snapshots_dir: config_dir.join(SNAPSHOTS_DIR).display().to_string(),
+            })?;
+        }
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            for (key, _, path) in &entries {
+                let value = path
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default();
+                println!("{}={}", key, value);
+            }
+        }
+        OutputFormat::Human => {
+            println!("{}", "Paths:".bold());
+            for (_, label, path) in &entries {
+                let value = match path {
+                    Some(p) if p.exists() => p.display().to_string(),
+                    Some(p) => format!("{} {}", p.display(), "(not created)".dimmed()),
+                    None => "(unavailable: home directory not found)"
+                        .dimmed()
+                        .to_string(),
+                };
+                println!("  {:<19} {}", format!("{}:", label), value);
+            }
         }
     }
 
@@ -63,7 +234,7 @@ pub fn handle_set_default(profile_name: &str) -> Result<(), RafctlError> {
     }
 
     let mut config = load_global_config()?;
-    config.default_profile = Some(name_lower.clone());
+    config.set_default_profile(&name_lower);
     save_global_config(&config)?;
 
     println!("{} Default profile set to '{}'", "✓".green(), name_lower);
@@ -93,6 +264,378 @@ pub fn handle_path() -> Result<(), RafctlError> {
     Ok(())
 }
 
+pub fn handle_clear_lock(skip_confirm: bool) -> Result<(), RafctlError> {
+    let lock_path = oauth_lock_path()?;
+
+    let holder = match inspect_lock(&lock_path) {
+        LockState::Absent => {
+            println!("{} No OAuth lock file found", "ℹ".cyan());
+            return Ok(());
+        }
+        LockState::Held { holder } => {
+            eprintln!("{} Not removing a lock that is still held", "ℹ".cyan());
+            return Err(RafctlError::OAuthConflict { holder });
+        }
+        LockState::Stale { holder } => holder,
+    };
+
+    if !skip_confirm
+        && io::stdin().is_terminal()
+        && !confirm(&format!(
+            "{} Remove stale OAuth lock (last held by '{}')? [y/N]",
+            "⚠".yellow(),
+            holder.as_deref().unwrap_or("unknown")
+        ))?
+    {
+        println!("{} Cancelled", "ℹ".cyan());
+        return Ok(());
+    }
+
+    std::fs::remove_file(&lock_path).map_err(|e| RafctlError::ConfigWrite {
+        path: lock_path.clone(),
+        source: e,
+    })?;
+
+    println!("{} Removed stale OAuth lock", "✓".green());
+
+    Ok(())
+}
+
+pub fn handle_reset(skip_confirm: bool) -> Result<(), RafctlError> {
+    if !skip_confirm
+        && io::stdin().is_terminal()
+        && !confirm(&format!(
+            "{} Reset global config (default and last-used profile)? Profiles are kept. [y/N]",
+            "⚠".yellow()
+        ))?
+    {
+        println!("{} Cancelled", "ℹ".cyan());
+        return Ok(());
+    }
+
+    reset_global_config()?;
+    println!(
+        "{} Global config reset to defaults (profiles preserved)",
+        "✓".green()
+    );
+
+    Ok(())
+}
+
+pub fn handle_export(output: Option<&Path>) -> Result<(), RafctlError> {
+    let config = load_global_config()?.portable();
+    let target = output
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("stdout"));
+
+    let yaml = serde_yaml::to_string(&config).map_err(|e| RafctlError::ConfigWrite {
+        path: target.clone(),
+        source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+    })?;
+
+    match output {
+        Some(path) => {
+            fs::write(path, yaml).map_err(|e| RafctlError::ConfigWrite {
+                path: target,
+                source: e,
+            })?;
+            println!(
+                "{} Global config exported to {}",
+                "✓".green(),
+                path.display()
+            );
+        }
+        None => print!("{}", yaml),
+    }
+
+    Ok(())
+}
+
+pub fn handle_import(file: &Path) -> Result<(), RafctlError> {
+    let content = fs::read_to_string(file).map_err(|e| RafctlError::ConfigRead {
+        path: file.to_path_buf(),
+        source: e,
+    })?;
+    let imported: GlobalConfig =
+        serde_yaml::from_str(&content).map_err(|e| RafctlError::ConfigRead {
+            path: file.to_path_buf(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        })?;
+    let imported = imported.portable();
+
+    if let Some(ref default) = imported.default_profile {
+        if !profile_exists(default)? {
+            println!(
+                "{} Default profile '{}' does not exist on this machine yet",
+                "⚠".yellow(),
+                default
+            );
+        }
+    }
+
+    let mut config = load_global_config()?;
+    config.merge(imported);
+    save_global_config(&config)?;
+
+    println!(
+        "{} Global config imported from {}",
+        "✓".green(),
+        file.display()
+    );
+
+    Ok(())
+}
+
+pub fn handle_backup(file: &Path) -> Result<(), RafctlError> {
+    let manifest = create_backup(file)?;
+
+    println!(
+        "{} Backed up {} profile(s) to {}",
+        "✓".green(),
+        manifest.profiles.len(),
+        file.display()
+    );
+    if !manifest.needs_reauth.is_empty() {
+        println!(
+            "{} Keyring credentials are not included; after a restore, re-authenticate: {}",
+            "ℹ".cyan(),
+            manifest.needs_reauth.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+pub fn handle_restore(file: &Path, merge: bool, skip_confirm: bool) -> Result<(), RafctlError> {
+    let manifest = read_backup_manifest(file)?;
+
+    // Nobody can answer the prompt, and replacing every profile is too
+    // destructive to assume a yes
+    if !skip_confirm && !merge && !io::stdin().is_terminal() {
+        return Err(RafctlError::MissingArgument(
+            "--yes (required to replace profiles without a terminal)".to_string(),
+        ));
+    }
+
+    if !skip_confirm && io::stdin().is_terminal() {
+        let action = if merge {
+            "Merge it into the current config (existing profiles are kept)?"
+        } else {
+            "Replace all current profiles and global config with it?"
+        };
+        if !confirm(&format!(
+            "{} Backup from {} contains {} profile(s). {} [y/N]",
+            "⚠".yellow(),
+            manifest.created_at.format("%Y-%m-%d %H:%M"),
+            manifest.profiles.len(),
+            action
+        ))? {
+            println!("{} Cancelled", "ℹ".cyan());
+            return Ok(());
+        }
+    }
+
+    let summary = restore_backup(file, merge)?;
+
+    println!(
+        "{} Restored {} profile(s) from {}",
+        "✓".green(),
+        summary.restored.len(),
+        file.display()
+    );
+    if !summary.skipped.is_empty() {
+        println!(
+            "{} Kept existing profile(s): {}",
+            "ℹ".cyan(),
+            summary.skipped.join(", ")
+        );
+    }
+
+    let needs_reauth: Vec<&str> = summary
+        .manifest
+        .needs_reauth
+        .iter()
+        .filter(|name| summary.restored.contains(name))
+        .map(String::as_str)
+        .collect();
+    if !needs_reauth.is_empty() {
+        println!(
+            "{} Re-authenticate (keyring credentials are not backed up): {}",
+            "⚠".yellow(),
+            needs_reauth.join(", ")
+        );
+        println!(
+            "  Use 'rafctl auth login <profile>' or 'rafctl auth set-key <profile>' for API key profiles"
+        );
+    }
+
+    Ok(())
+}
+
+pub fn handle_edit() -> Result<(), RafctlError> {
+    let config_path = get_config_dir()?.join(GLOBAL_CONFIG_FILE);
+    if !config_path.exists() {
+        save_global_config(&load_global_config()?)?;
+    }
+
+    let original = fs::read_to_string(&config_path).map_err(|e| RafctlError::ConfigRead {
+        path: config_path.clone(),
+        source: e,
+    })?;
+
+    open_in_editor(&config_path)?;
+
+    let edited = fs::read_to_string(&config_path).map_err(|e| RafctlError::ConfigRead {
+        path: config_path.clone(),
+        source: e,
+    })?;
+    if edited == original {
+        println!("{} No changes", "ℹ".cyan());
+        return Ok(());
+    }
+
+    match serde_yaml::from_str::<GlobalConfig>(&edited) {
+        Ok(config) => {
+            save_global_config(&config)?;
+            println!("{} Global config updated", "✓".green());
+            Ok(())
+        }
+        Err(e) => {
+            fs::write(&config_path, &original).map_err(|e| RafctlError::ConfigWrite {
+                path: config_path.clone(),
+                source: e,
+            })?;
+            eprintln!(
+                "{} Edited config is not valid, the previous version was kept:",
+                "✗".red()
+            );
+            eprintln!("{}", edited.trim_end().dimmed());
+            Err(RafctlError::ConfigRead {
+                path: config_path,
+                source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+            })
+        }
+    }
+}
+
+/// Run `$EDITOR` (which may carry its own arguments, e.g. `code --wait`) on `path`.
+fn open_in_editor(path: &Path) -> Result<(), RafctlError> {
+    let editor = std::env::var("EDITOR")
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| RafctlError::ProcessSpawn {
+            tool: program.to_string(),
+            message: e.to_string(),
+        })?;
+
+    if !status.success() {
+        return Err(RafctlError::ProcessSpawn {
+            tool: program.to_string(),
+            message: format!("editor exited with {}", status),
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct EnvVarOutput {
+    value: Option<String>,
+    description: &'static str,
+}
+
+/// Mask a secret value, keeping only the last four characters.
+fn mask_secret(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 8 {
+        return "****".to_string();
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("****{}", tail)
+}
+
+pub fn handle_env(format: OutputFormat) -> Result<(), RafctlError> {
+    let vars: Vec<(&str, EnvVarOutput)> =
+        RECOGNIZED_ENV_VARS
+            .iter()
+            .map(|info| {
+                let value = std::env::var(info.name).ok().map(|v| {
+                    if info.secret {
+                        mask_secret(&v)
+                    } else {
+                        v
+                    }
+                });
+                (
+                    info.name,
+                    EnvVarOutput {
+                        value,
+                        description: info.description,
+                    },
+                )
+            })
+            .collect();
+
+    match format {
+        OutputFormat::Json => {
+            let map: serde_json::Map<String, Value> = vars
+                .iter()
+                .map(|(name, output)| {
+                    (
+                        name.to_string(),
+                        serde_json::to_value(output).unwrap_or(Value::Null),
+                    )
+                })
+                .collect();
+            print_json(&map)?;
+        }
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("NAME\tVALUE\tDESCRIPTION");
+            for (name, output) in &vars {
+                println!(
+                    "{}\t{}\t{}",
+                    name,
+                    output.value.as_deref().unwrap_or("-"),
+                    output.description
+                );
+            }
+        }
+        OutputFormat::Human => {
+            println!("{}", "Environment variables:".bold());
+            let width = vars.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+            for (name, output) in &vars {
+                let value = match &output.value {
+                    Some(v) => v.green().to_string(),
+                    None => "(not set)".dimmed().to_string(),
+                };
+                println!("  {:<width$}  {}", name, value, width = width);
+                println!(
+                    "  {:<width$}  {}",
+                    "",
+                    output.description.dimmed(),
+                    width = width
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn handle_hud(
     enable: bool,
     disable: bool,
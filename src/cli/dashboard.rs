@@ -1,5 +1,5 @@
-use std::io;
-use std::time::Duration;
+use std::io::{self, IsTerminal};
+use std::time::{Duration, Instant};
 
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::layout::{Constraint, Layout};
@@ -22,6 +22,23 @@ pub enum DashboardAction {
     None,
     Run(String),
     Login(String),
+    /// Delete a profile (already confirmed); the dashboard reopens afterwards
+    Delete(String),
+    /// Create a profile interactively; the dashboard reopens afterwards
+    Create,
+}
+
+/// How often the dashboard reloads profiles and stats on its own
+const AUTO_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What the profile table is being shown for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Dashboard,
+    /// Viewer only: the run, login, add and delete keys are ignored
+    ReadOnly,
+    /// Choose a profile for `run` / `switch`: Enter picks, nothing else acts
+    Picker,
 }
 
 struct ProfileRow {
@@ -43,53 +60,87 @@ struct App {
     should_quit: bool,
     message: Option<String>,
     pending_action: DashboardAction,
+    mode: Mode,
+    /// Profile awaiting a y/N answer after `d`
+    confirm_delete: Option<String>,
+    /// Set by `R`; `run_app` reloads before the next draw
+    refresh_requested: bool,
 }
 
-impl App {
-    fn new() -> Result<Self, RafctlError> {
-        let profile_names = list_profiles()?;
-        let mut profiles = Vec::new();
-
-        for name in profile_names {
-            if let Ok(profile) = load_profile(&name) {
-                let authenticated = is_authenticated(profile.tool, &name).unwrap_or(false);
-                let last_used = profile
-                    .last_used
-                    .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string());
-
-                let stats = load_profile_stats(&name, profile.tool);
-                let today_activity = stats.recent_activity(1);
-                let today_messages = today_activity.first().map(|a| a.message_count).unwrap_or(0);
-                let tokens_7d = stats.total_tokens(Some(7));
-
-                profiles.push(ProfileRow {
-                    name: profile.name,
-                    tool: profile.tool,
-                    auth_mode: profile.auth_mode,
-                    authenticated,
-                    last_used,
-                    today_messages,
-                    tokens_7d,
-                    #[cfg(target_os = "macos")]
-                    usage: None,
-                });
-            }
+/// Collect the table rows: auth state and today's / 7-day usage per profile.
+fn load_rows() -> Result<Vec<ProfileRow>, RafctlError> {
+    let profile_names = list_profiles()?;
+    let mut profiles = Vec::new();
+
+    for name in profile_names {
+        if let Ok(profile) = load_profile(&name) {
+            let authenticated = is_authenticated(profile.tool, &name).unwrap_or(false);
+            let last_used = profile
+                .last_used
+                .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string());
+
+            let stats = load_profile_stats(&name, profile.tool);
+            let today_activity = stats.recent_activity(1);
+            let today_messages = today_activity.first().map(|a| a.message_count).unwrap_or(0);
+            let tokens_7d = stats.total_tokens(Some(7));
+
+            profiles.push(ProfileRow {
+                name: profile.name,
+                tool: profile.tool,
+                auth_mode: profile.auth_mode,
+                authenticated,
+                last_used,
+                today_messages,
+                tokens_7d,
+                #[cfg(target_os = "macos")]
+                usage: None,
+            });
         }
+    }
+
+    Ok(profiles)
+}
+
+impl App {
+    fn new(mode: Mode) -> Result<Self, RafctlError> {
+        let profiles = load_rows()?;
 
         let mut table_state = TableState::default();
         if !profiles.is_empty() {
             table_state.select(Some(0));
         }
 
+        let message = (profiles.is_empty() && mode == Mode::Dashboard)
+            .then(|| "No profiles yet. Press a to create one".to_string());
+
         Ok(Self {
             profiles,
             table_state,
             should_quit: false,
-            message: None,
+            message,
             pending_action: DashboardAction::None,
+            mode,
+            confirm_delete: None,
+            refresh_requested: false,
         })
     }
 
+    /// Reload the rows in place, keeping the selected profile (or at least
+    /// the selected position) when it is still there.
+    fn refresh(&mut self) -> Result<(), RafctlError> {
+        let selected_name = self.selected_profile().map(|p| p.name.clone());
+        let selected_index = self.table_state.selected();
+
+        self.profiles = load_rows()?;
+
+        let index = selected_name
+            .and_then(|name| self.profiles.iter().position(|p| p.name == name))
+            .or_else(|| selected_index.map(|i| i.min(self.profiles.len().saturating_sub(1))));
+        self.table_state
+            .select(index.filter(|_| !self.profiles.is_empty()));
+        Ok(())
+    }
+
     fn next(&mut self) {
         if self.profiles.is_empty() {
             return;
@@ -130,10 +181,35 @@ impl App {
                 return;
             }
 
+            if let Some(name) = self.confirm_delete.take() {
+                if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                    self.pending_action = DashboardAction::Delete(name);
+                    self.should_quit = true;
+                } else {
+                    self.message = Some("Delete cancelled".to_string());
+                }
+                return;
+            }
+
             match key.code {
                 KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
                 KeyCode::Down | KeyCode::Char('j') => self.next(),
+                KeyCode::Char('R') => self.refresh_requested = true,
                 KeyCode::Up | KeyCode::Char('k') => self.previous(),
+                KeyCode::Enter
+                | KeyCode::Char('r')
+                | KeyCode::Char('l')
+                | KeyCode::Char('d')
+                | KeyCode::Char('a')
+                    if self.mode == Mode::ReadOnly =>
+                {
+                    self.message = Some("Read-only dashboard: actions are disabled".to_string());
+                }
+                KeyCode::Char('r')
+                | KeyCode::Char('l')
+                | KeyCode::Char('d')
+                | KeyCode::Char('a')
+                    if self.mode == Mode::Picker => {}
                 KeyCode::Enter | KeyCode::Char('r') => {
                     if let Some(profile) = self.selected_profile() {
                         self.pending_action = DashboardAction::Run(profile.name.clone());
@@ -146,23 +222,83 @@ impl App {
                         self.should_quit = true;
                     }
                 }
+                KeyCode::Char('d') => {
+                    if let Some(name) = self.selected_profile().map(|p| p.name.clone()) {
+                        self.message = Some(format!(
+                            "Delete profile '{}' and its stored credentials? [y/N]",
+                            name
+                        ));
+                        self.confirm_delete = Some(name);
+                    }
+                }
+                KeyCode::Char('a') => {
+                    self.pending_action = DashboardAction::Create;
+                    self.should_quit = true;
+                }
                 _ => {}
             }
         }
     }
 }
 
-pub fn run_dashboard() -> Result<DashboardAction, RafctlError> {
+/// Show the dashboard until the user quits or picks an action.
+/// `message` is shown in the status line, e.g. the outcome of the last action.
+pub fn run_dashboard(
+    readonly: bool,
+    message: Option<String>,
+) -> Result<DashboardAction, RafctlError> {
+    let mode = if readonly {
+        Mode::ReadOnly
+    } else {
+        Mode::Dashboard
+    };
     let mut terminal = ratatui::init();
-    let result = run_app(&mut terminal);
+    let result = run_app(&mut terminal, mode, message);
     ratatui::restore();
     result
 }
 
-fn run_app(terminal: &mut DefaultTerminal) -> Result<DashboardAction, RafctlError> {
-    let mut app = App::new()?;
+/// Let the user choose a profile from the dashboard table.
+///
+/// Returns `None` without drawing anything when stdin or stdout is not a
+/// terminal or there are no profiles, so scripts keep their old behavior;
+/// also `None` when the user quits without choosing.
+pub fn pick_profile() -> Result<Option<String>, RafctlError> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() || list_profiles()?.is_empty() {
+        return Ok(None);
+    }
+
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, Mode::Picker, None);
+    ratatui::restore();
+
+    match result? {
+        DashboardAction::Run(name) => Ok(Some(name)),
+        _ => Ok(None),
+    }
+}
+
+fn run_app(
+    terminal: &mut DefaultTerminal,
+    mode: Mode,
+    message: Option<String>,
+) -> Result<DashboardAction, RafctlError> {
+    let mut app = App::new(mode)?;
+    if message.is_some() {
+        app.message = message;
+    }
+    let mut last_refresh = Instant::now();
 
     loop {
+        if app.refresh_requested || last_refresh.elapsed() >= AUTO_REFRESH_INTERVAL {
+            app.refresh()?;
+            if app.refresh_requested {
+                app.message = Some("Refreshed".to_string());
+                app.refresh_requested = false;
+            }
+            last_refresh = Instant::now();
+        }
+
         terminal
             .draw(|frame| render(frame, &mut app))
             .map_err(|e| RafctlError::ConfigWrite {
@@ -198,19 +334,24 @@ fn render(frame: &mut Frame, app: &mut App) {
     ])
     .areas(frame.area());
 
-    render_header(frame, header_area);
+    render_header(frame, header_area, app.mode);
     render_table(frame, app, table_area);
-    render_help(frame, help_area);
+    render_help(frame, help_area, app.mode);
     render_message(frame, app, message_area);
 }
 
-fn render_header(frame: &mut Frame, area: ratatui::layout::Rect) {
+fn render_header(frame: &mut Frame, area: ratatui::layout::Rect, mode: Mode) {
+    let title = if mode == Mode::Picker {
+        "select a profile"
+    } else {
+        "dashboard"
+    };
     let header = Paragraph::new(Line::from(vec![
         Span::styled(
             "rafctl ",
             Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD),
         ),
-        Span::raw("dashboard"),
+        Span::raw(title),
     ]))
     .block(Block::bordered().title("AI Coding Agent Profile Manager ☕"));
 
@@ -294,20 +435,43 @@ fn render_table(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
     frame.render_stateful_widget(table, area, &mut app.table_state);
 }
 
-fn render_help(frame: &mut Frame, area: ratatui::layout::Rect) {
-    let help = Paragraph::new(Line::from(vec![
+fn render_help(frame: &mut Frame, area: ratatui::layout::Rect, mode: Mode) {
+    let mut keys = vec![
         Span::styled("↑/k", Style::new().fg(Color::Cyan)),
         Span::raw(" up  "),
         Span::styled("↓/j", Style::new().fg(Color::Cyan)),
         Span::raw(" down  "),
-        Span::styled("Enter/r", Style::new().fg(Color::Cyan)),
-        Span::raw(" run  "),
-        Span::styled("l", Style::new().fg(Color::Cyan)),
-        Span::raw(" login  "),
+    ];
+    match mode {
+        Mode::Dashboard => keys.extend([
+            Span::styled("Enter/r", Style::new().fg(Color::Cyan)),
+            Span::raw(" run  "),
+            Span::styled("l", Style::new().fg(Color::Cyan)),
+            Span::raw(" login  "),
+            Span::styled("a", Style::new().fg(Color::Cyan)),
+            Span::raw(" add  "),
+            Span::styled("d", Style::new().fg(Color::Cyan)),
+            Span::raw(" delete  "),
+        ]),
+        Mode::Picker => keys.extend([
+            Span::styled("Enter", Style::new().fg(Color::Cyan)),
+            Span::raw(" select  "),
+        ]),
+        Mode::ReadOnly => {}
+    }
+    keys.extend([
+        Span::styled("R", Style::new().fg(Color::Cyan)),
+        Span::raw(" refresh  "),
         Span::styled("q/Esc", Style::new().fg(Color::Cyan)),
         Span::raw(" quit"),
-    ]))
-    .block(Block::bordered());
+    ]);
+    if mode == Mode::ReadOnly {
+        keys.push(Span::styled(
+            "  (read-only)",
+            Style::new().fg(Color::DarkGray),
+        ));
+    }
+    let help = Paragraph::new(Line::from(keys)).block(Block::bordered());
 
     frame.render_widget(help, area);
 }
@@ -328,3 +492,79 @@ fn format_tokens(n: u64) -> String {
         n.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    fn app_with(names: &[&str], mode: Mode) -> App {
+        let profiles = names
+            .iter()
+            .map(|name| ProfileRow {
+                name: name.to_string(),
+                tool: ToolType::Codex,
+                auth_mode: AuthMode::OAuth,
+                authenticated: false,
+                last_used: None,
+                today_messages: 0,
+                tokens_7d: 0,
+                #[cfg(target_os = "macos")]
+                usage: None,
+            })
+            .collect();
+        let mut table_state = TableState::default();
+        table_state.select(Some(0));
+        App {
+            profiles,
+            table_state,
+            should_quit: false,
+            message: None,
+            pending_action: DashboardAction::None,
+            mode,
+            confirm_delete: None,
+            refresh_requested: false,
+        }
+    }
+
+    fn press(app: &mut App, c: char) {
+        app.handle_event(Event::Key(KeyEvent::new(
+            KeyCode::Char(c),
+            KeyModifiers::NONE,
+        )));
+    }
+
+    #[test]
+    fn test_delete_needs_confirmation() {
+        let mut app = app_with(&["work"], Mode::Dashboard);
+        press(&mut app, 'd');
+        assert!(!app.should_quit);
+        press(&mut app, 'n');
+        assert!(!app.should_quit);
+        assert_eq!(app.message.as_deref(), Some("Delete cancelled"));
+
+        press(&mut app, 'd');
+        press(&mut app, 'y');
+        assert!(app.should_quit);
+        assert!(matches!(app.pending_action, DashboardAction::Delete(ref n) if n == "work"));
+    }
+
+    #[test]
+    fn test_actions_disabled_outside_dashboard_mode() {
+        let mut readonly = app_with(&["work"], Mode::ReadOnly);
+        press(&mut readonly, 'd');
+        press(&mut readonly, 'a');
+        assert!(!readonly.should_quit);
+        assert!(readonly.confirm_delete.is_none());
+        // Refreshing is not an action, so the viewer keeps it
+        press(&mut readonly, 'R');
+        assert!(readonly.refresh_requested);
+
+        let mut empty = app_with(&[], Mode::Dashboard);
+        empty.table_state.select(None);
+        press(&mut empty, 'd');
+        assert!(empty.confirm_delete.is_none());
+        press(&mut empty, 'a');
+        assert!(matches!(empty.pending_action, DashboardAction::Create));
+    }
+}
@@ -0,0 +1,316 @@
+//! Doctor command - diagnoses common setup problems.
+
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+use serde::Serialize;
+
+use super::output::{error_with_causes, print_json};
+use super::OutputFormat;
+use crate::core::constants::OAUTH_LOCK_FILE;
+use crate::core::credentials::{self, CredentialType};
+use crate::core::lock::{inspect_lock, LockState};
+use crate::core::profile::{
+    get_config_dir, get_profile_meta_path, list_profiles, load_profile, ToolType,
+};
+use crate::error::RafctlError;
+use crate::tools::check_tool_available;
+
+/// Profile name used for the keyring round-trip; never a valid user profile.
+const KEYRING_PROBE_PROFILE: &str = "__rafctl-doctor";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: String,
+    status: CheckStatus,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Check {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn with_hint(mut self, hint: Option<String>) -> Self {
+        self.hint = hint;
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct DoctorOutput {
+    ok: bool,
+    checks: Vec<Check>,
+}
+
+/// Run all checks and print a checklist. Returns `false` if a critical check failed.
+pub fn handle_doctor(format: OutputFormat) -> Result<bool, RafctlError> {
+    let config_dir = get_config_dir()?;
+
+    let mut checks = check_tools();
+    checks.push(check_config_dir_writable(&config_dir));
+    checks.extend(check_profiles());
+    checks.push(check_keyring());
+    checks.push(check_oauth_lock(&config_dir.join(OAUTH_LOCK_FILE)));
+    checks.push(check_hud_binary());
+
+    let ok = checks.iter().all(|c| c.status != CheckStatus::Fail);
+
+    match format {
+        OutputFormat::Json => print_json(&DoctorOutput { ok, checks })?,
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("STATUS\tCHECK\tDETAIL");
+            for check in &checks {
+                let status = match check.status {
+                    CheckStatus::Ok => "ok",
+                    CheckStatus::Warn => "warn",
+                    CheckStatus::Fail => "fail",
+                };
+                println!("{}\t{}\t{}", status, check.name, check.detail);
+            }
+        }
+        OutputFormat::Human => {
+            println!("{}", "rafctl doctor".bold());
+            println!();
+            for check in &checks {
+                let icon = match check.status {
+                    CheckStatus::Ok => "✓".green(),
+                    CheckStatus::Warn => "⚠".yellow(),
+                    CheckStatus::Fail => "✗".red(),
+                };
+                println!("  {} {}: {}", icon, check.name, check.detail);
+                if let Some(hint) = &check.hint {
+                    for line in hint.lines() {
+                        println!("      {}", line.dimmed());
+                    }
+                }
+            }
+            println!();
+            if ok {
+                println!("{} No critical problems found", "✓".green());
+            } else {
+                println!("{} Critical problems found, see above", "✗".red());
+            }
+        }
+    }
+
+    Ok(ok)
+}
+
+/// A missing tool is only critical when a profile depends on it.
+fn check_tools() -> Vec<Check> {
+    let used_tools: Vec<ToolType> = list_profiles()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|name| load_profile(name).ok())
+        .map(|profile| profile.tool)
+        .collect();
+
+    [ToolType::Claude, ToolType::Codex, ToolType::Gemini]
+        .into_iter()
+        .map(|tool| {
+            let name = format!("{} binary", tool.command_name());
+            match check_tool_available(tool) {
+                Ok(()) => Check::new(name, CheckStatus::Ok, "found on PATH"),
+                Err(e) => {
+                    let (status, detail) = if used_tools.contains(&tool) {
+                        (CheckStatus::Fail, "not found (used by a profile)")
+                    } else {
+                        (CheckStatus::Warn, "not found (no profile uses it)")
+                    };
+                    Check::new(name, status, detail).with_hint(e.hint())
+                }
+            }
+        })
+        .collect()
+}
+
+fn check_config_dir_writable(config_dir: &Path) -> Check {
+    let name = "Config directory";
+    let probe = config_dir.join(".doctor-write-test");
+
+    let result = std::fs::create_dir_all(config_dir)
+        .and_then(|_| std::fs::write(&probe, b"ok"))
+        .and_then(|_| std::fs::remove_file(&probe));
+
+    match result {
+        Ok(()) => Check::new(name, CheckStatus::Ok, config_dir.display().to_string()),
+        Err(e) => Check::new(
+            name,
+            CheckStatus::Fail,
+            format!("{} is not writable: {}", config_dir.display(), e),
+        )
+        .with_hint(Some(
+            "Fix the directory permissions or point RAFCTL_CONFIG_DIR elsewhere".to_string(),
+        )),
+    }
+}
+
+/// One failing check per profile whose `meta.yaml` cannot be loaded.
+fn check_profiles() -> Vec<Check> {
+    let name = "Profiles";
+    let profiles = match list_profiles() {
+        Ok(profiles) => profiles,
+        Err(e) => return vec![Check::new(name, CheckStatus::Fail, error_with_causes(&e))],
+    };
+
+    let corrupted: Vec<Check> = profiles
+        .iter()
+        .filter_map(|profile| {
+            let err = load_profile(profile).err()?;
+            let hint = get_profile_meta_path(profile).ok().map(|path| {
+                format!(
+                    "Fix the YAML in {}, or run: rafctl profile remove {}",
+                    path.display(),
+                    profile
+                )
+            });
+            Some(
+                Check::new(
+                    format!("Profile '{}'", profile),
+                    CheckStatus::Fail,
+                    error_with_causes(&err),
+                )
+                .with_hint(hint),
+            )
+        })
+        .collect();
+
+    if corrupted.is_empty() {
+        vec![Check::new(
+            name,
+            CheckStatus::Ok,
+            format!("{} profile(s) readable", profiles.len()),
+        )]
+    } else {
+        corrupted
+    }
+}
+
+fn check_keyring() -> Check {
+    let name = "Keyring";
+    let secret = "rafctl-doctor-probe";
+
+    let round_trip =
+        credentials::store_credential(KEYRING_PROBE_PROFILE, CredentialType::ApiKey, secret)
+            .and_then(|_| {
+                credentials::get_credential(KEYRING_PROBE_PROFILE, CredentialType::ApiKey)
+            });
+    let _ = credentials::delete_credential(KEYRING_PROBE_PROFILE, CredentialType::ApiKey);
+
+    match round_trip {
+        Ok(Some(value)) if value == secret => {
+            Check::new(name, CheckStatus::Ok, "store/read/delete round-trip works")
+        }
+        Ok(_) => Check::new(
+            name,
+            CheckStatus::Fail,
+            "stored value could not be read back",
+        )
+        .with_hint(Some(keyring_hint())),
+        Err(e) => {
+            Check::new(name, CheckStatus::Fail, e.to_string()).with_hint(Some(keyring_hint()))
+        }
+    }
+}
+
+fn keyring_hint() -> String {
+    if cfg!(target_os = "linux") {
+        "Start a secret-service provider (gnome-keyring or KWallet) and unlock it".to_string()
+    } else {
+        "Unlock the system keychain and allow rafctl to access it".to_string()
+    }
+}
+
+fn check_oauth_lock(lock_path: &Path) -> Check {
+    let name = "OAuth lock";
+
+    match inspect_lock(lock_path) {
+        LockState::Absent => Check::new(name, CheckStatus::Ok, "no lock file"),
+        LockState::Stale { .. } => Check::new(
+            name,
+            CheckStatus::Warn,
+            "stale lock file (no instance holds it)",
+        )
+        .with_hint(Some("Remove it with: rafctl config clear-lock".to_string())),
+        LockState::Held { holder } => {
+            let detail = match holder {
+                Some(profile) => {
+                    format!("held by a running OAuth instance (profile '{}')", profile)
+                }
+                None => "held by a running OAuth instance".to_string(),
+            };
+            Check::new(name, CheckStatus::Ok, detail)
+        }
+    }
+}
+
+fn check_hud_binary() -> Check {
+    let name = "rafctl-hud";
+
+    match find_on_path("rafctl-hud") {
+        Some(path) => Check::new(name, CheckStatus::Ok, path.display().to_string()),
+        None => Check::new(name, CheckStatus::Warn, "not found on PATH").with_hint(Some(
+            "Install it with: cargo install --path . (needed for rafctl hud install)".to_string(),
+        )),
+    }
+}
+
+fn find_on_path(binary: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_oauth_lock_states() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join(OAUTH_LOCK_FILE);
+
+        assert_eq!(check_oauth_lock(&lock_path).status, CheckStatus::Ok);
+
+        std::fs::write(&lock_path, "work\n").unwrap();
+        let stale = check_oauth_lock(&lock_path);
+        assert_eq!(stale.status, CheckStatus::Warn);
+        assert!(stale.hint.is_some());
+
+        let _holder = crate::core::lock::acquire_lock(&lock_path, "work")
+            .unwrap()
+            .unwrap();
+        let held = check_oauth_lock(&lock_path);
+        assert_eq!(held.status, CheckStatus::Ok);
+        assert!(held.detail.contains("'work'"));
+    }
+
+    #[test]
+    fn test_check_config_dir_writable() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().join(".rafctl");
+
+        assert_eq!(
+            check_config_dir_writable(&config_dir).status,
+            CheckStatus::Ok
+        );
+        assert!(!config_dir.join(".doctor-write-test").exists());
+    }
+}
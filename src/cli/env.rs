@@ -1,24 +1,65 @@
+use crate::core::config::get_default_profile;
 use crate::core::constants::{
-    ENV_RAFCTL_PROFILE, ENV_RAFCTL_PROFILE_TOOL, ENV_RAFCTL_VERSION, VERSION,
+    ENV_RAFCTL_AUTH_MODE, ENV_RAFCTL_PROFILE, ENV_RAFCTL_PROFILE_DIR, ENV_RAFCTL_PROFILE_TOOL,
+    ENV_RAFCTL_VERSION, VERSION,
 };
-use crate::core::profile::{load_profile, resolve_profile_alias};
+use crate::core::profile::{get_profile_dir, load_profile, resolve_profile_alias};
 use crate::error::RafctlError;
 
-pub fn handle_env(profile_name: &str) -> Result<(), RafctlError> {
-    let resolved_name = resolve_profile_alias(profile_name)?;
+/// Shell dialect for the printed variable assignments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvSyntax {
+    Posix,
+    Fish,
+    PowerShell,
+}
+
+impl EnvSyntax {
+    fn assignment(self, key: &str, value: &str) -> String {
+        match self {
+            EnvSyntax::Posix => format!("export {}=\"{}\"", key, value),
+            EnvSyntax::Fish => format!("set -gx {} \"{}\"", key, value),
+            EnvSyntax::PowerShell => format!("$env:{} = \"{}\"", key, value),
+        }
+    }
+}
+
+pub fn handle_env(profile_name: Option<&str>, syntax: EnvSyntax) -> Result<(), RafctlError> {
+    let resolved_name = match profile_name {
+        Some(name) => resolve_profile_alias(name)?,
+        None => get_default_profile()?.ok_or(RafctlError::NoDefaultProfile)?,
+    };
     let name_lower = resolved_name.to_lowercase();
 
     let profile = load_profile(&name_lower)?;
-    let config_dir = profile.tool.config_dir_for_profile(&name_lower)?;
 
+    if profile.is_isolated() {
+        let config_dir = profile.tool.config_dir_for_profile(&name_lower)?;
+        println!(
+            "{}",
+            syntax.assignment(
+                profile.tool.env_var_name(),
+                &config_dir.display().to_string()
+            )
+        );
+    }
+    println!("{}", syntax.assignment(ENV_RAFCTL_PROFILE, &profile.name));
+    println!(
+        "{}",
+        syntax.assignment(ENV_RAFCTL_PROFILE_TOOL, &profile.tool.to_string())
+    );
+    println!(
+        "{}",
+        syntax.assignment(ENV_RAFCTL_AUTH_MODE, &profile.auth_label())
+    );
     println!(
-        "export {}=\"{}\"",
-        profile.tool.env_var_name(),
-        config_dir.display()
+        "{}",
+        syntax.assignment(
+            ENV_RAFCTL_PROFILE_DIR,
+            &get_profile_dir(&name_lower)?.display().to_string()
+        )
     );
-    println!("export {}=\"{}\"", ENV_RAFCTL_PROFILE, profile.name);
-    println!("export {}=\"{}\"", ENV_RAFCTL_PROFILE_TOOL, profile.tool);
-    println!("export {}=\"{}\"", ENV_RAFCTL_VERSION, VERSION);
+    println!("{}", syntax.assignment(ENV_RAFCTL_VERSION, VERSION));
 
     Ok(())
 }
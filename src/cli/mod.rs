@@ -3,19 +3,29 @@ pub mod auth;
 pub mod config;
 pub mod dashboard;
 pub mod debug;
+pub mod doctor;
 pub mod env;
 pub mod hud;
 pub mod output;
 pub mod profile;
 pub mod quota;
+pub mod replay;
 pub mod run;
+pub mod search;
 pub mod sessions;
+pub mod stats;
 pub mod status;
+pub mod version;
 pub mod watch;
 
 use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{generate, Shell};
-use std::io;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::core::constants::ENV_NO_COLOR;
+use crate::core::profile::list_profiles;
+use crate::core::stats::{Granularity, MergeStrategy};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
 pub enum OutputFormat {
@@ -23,6 +33,38 @@ pub enum OutputFormat {
     Human,
     Json,
     Plain,
+    /// Versioned `key=value` records for scripts; see docs/porcelain.md
+    Porcelain,
+}
+
+/// What `rafctl run` does when an OAuth profile's 5-hour quota is exhausted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QuotaExhaustedAction {
+    /// Launch the profile's configured fallback profile instead
+    SwitchTo,
+    /// Abort without launching the tool
+    Fail,
+}
+
+/// Ordering for `rafctl profile list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum ProfileSort {
+    /// Alphabetical by name
+    #[default]
+    Name,
+    /// Most recently used first; never-used profiles last
+    LastUsed,
+    /// Newest profile first
+    Created,
+    /// Most tokens used (all time, from the stats cache) first
+    Tokens,
+}
+
+/// Document format for `rafctl sessions <id> --export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SessionExportFormat {
+    /// Markdown summary
+    Md,
 }
 
 #[derive(Parser)]
@@ -34,6 +76,31 @@ pub struct Cli {
     #[arg(long, global = true, help = "Plain output (no colors or emoji)")]
     pub plain: bool,
 
+    #[arg(
+        long,
+        global = true,
+        help = "Stable, versioned key=value output for scripts (status, profile list, quota)"
+    )]
+    pub porcelain: bool,
+
+    #[arg(
+        long = "output",
+        id = "report_output",
+        global = true,
+        value_name = "PATH",
+        requires = "json",
+        help = "Write the --json payload to this file instead of stdout"
+    )]
+    pub report_output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Use this config directory (overrides RAFCTL_CONFIG_DIR; default: ~/.rafctl)"
+    )]
+    pub config_dir: Option<PathBuf>,
+
     #[arg(short = 'v', long, global = true, help = "Enable verbose/debug output")]
     pub verbose: bool,
 
@@ -45,7 +112,9 @@ impl Cli {
     pub fn output_format(&self) -> OutputFormat {
         if self.json {
             OutputFormat::Json
-        } else if self.plain || std::env::var("NO_COLOR").is_ok() {
+        } else if self.porcelain {
+            OutputFormat::Porcelain
+        } else if self.plain || std::env::var(ENV_NO_COLOR).is_ok() {
             OutputFormat::Plain
         } else {
             OutputFormat::Human
@@ -67,8 +136,62 @@ pub enum Commands {
     },
     #[command(about = "Run tool with profile")]
     Run {
-        #[arg(help = "Profile name (uses last used if not specified)")]
+        #[arg(
+            help = "Profile name, or 'last' / '-' for the last run profile (uses the default if not specified)"
+        )]
         profile: Option<String>,
+        #[arg(
+            long,
+            value_enum,
+            help = "Check the 5-hour quota before launching (OAuth profiles only)"
+        )]
+        on_quota_exhausted: Option<QuotaExhaustedAction>,
+        #[arg(
+            long,
+            help = "Abort if the profile has already hit its 5-hour limit (OAuth profiles only)"
+        )]
+        pre_check_quota: bool,
+        #[arg(
+            long,
+            value_name = "SECS",
+            help = "Stop the tool if its session transcript has no new events for SECS seconds"
+        )]
+        timeout_idle: Option<u64>,
+        #[arg(
+            long,
+            help = "Launch an OAuth profile even if another OAuth instance is running"
+        )]
+        force: bool,
+        #[arg(long, help = "Print a recap of the session after the tool exits")]
+        post_summary: bool,
+        #[arg(
+            long,
+            help = "Copy the profile's meta.yaml and settings.json to ~/.rafctl/snapshots before launching"
+        )]
+        snapshot_config: bool,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Pass the KEY=VALUE variables from this file to the tool"
+        )]
+        env_file: Option<PathBuf>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Launch the tool (and profile hooks) in this directory"
+        )]
+        cwd: Option<PathBuf>,
+        #[arg(
+            long,
+            help = "Record the session's cost in the profile's cost ledger (Claude only)"
+        )]
+        capture_cost: bool,
+        #[arg(
+            long,
+            conflicts_with = "profile",
+            help = "Choose the profile from an interactive list, even if a default is set"
+        )]
+        pick: bool,
         #[arg(last = true, help = "Arguments to pass to the tool")]
         args: Vec<String>,
     },
@@ -76,11 +199,39 @@ pub enum Commands {
     Status {
         #[arg(help = "Specific profile (shows all if not specified)")]
         profile: Option<String>,
+        #[arg(
+            long,
+            help = "Exit with code 1 if any shown profile is not authenticated"
+        )]
+        check: bool,
+        #[arg(
+            long,
+            conflicts_with = "profile",
+            help = "Only show profiles with this tag"
+        )]
+        tag: Option<String>,
     },
     #[command(about = "Show quota/usage limits")]
     Quota {
         #[arg(help = "Specific profile (shows all if not specified)")]
         profile: Option<String>,
+        #[arg(long, help = "Exit with code 2 if any quota fetch failed")]
+        exit_on_error: bool,
+        #[arg(
+            long,
+            conflicts_with = "exit_on_error",
+            help = "Keep refreshing the quota view until Ctrl+C"
+        )]
+        watch: bool,
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value = "60",
+            requires = "watch",
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Seconds between refreshes with --watch"
+        )]
+        interval: u64,
     },
     #[command(about = "Configuration management")]
     Config {
@@ -93,11 +244,22 @@ pub enum Commands {
         shell: Shell,
     },
     #[command(about = "Interactive TUI dashboard")]
-    Dashboard,
+    Dashboard {
+        #[arg(long, help = "View only: disable the run, login, add and delete keys")]
+        readonly: bool,
+    },
     #[command(about = "Switch to a profile (set as default and show status)")]
     Switch {
-        #[arg(help = "Profile name to switch to")]
-        profile: String,
+        #[arg(
+            help = "Profile name to switch to ('-' for the previous default; choose interactively if not specified)"
+        )]
+        profile: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "profile",
+            help = "Switch back to the previous default profile"
+        )]
+        previous: bool,
     },
     #[command(about = "Show usage analytics from local stats")]
     Analytics {
@@ -107,15 +269,125 @@ pub enum Commands {
         days: usize,
         #[arg(long, help = "Show all profiles")]
         all: bool,
+        #[arg(
+            long,
+            value_name = "PROFILE",
+            num_args = 2..,
+            conflicts_with_all = ["profile", "all", "cost", "by_session"],
+            help = "Compare two or more profiles side by side"
+        )]
+        compare: Vec<String>,
+        #[arg(
+            long,
+            requires = "all",
+            help = "With --all, only profiles with this tag"
+        )]
+        tag: Option<String>,
         #[arg(long, help = "Show estimated costs")]
         cost: bool,
+        #[arg(long, help = "Include days with no activity in the daily table")]
+        zero_fill: bool,
+        #[arg(
+            long,
+            value_name = "PERIOD",
+            default_value_t = Granularity::Day,
+            help = "Group the activity table by day, week (ISO) or month"
+        )]
+        group_by: Granularity,
+        #[arg(long, help = "Show token usage per session instead of per day")]
+        by_session: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["all", "cost", "by_session"],
+            help = "Show the daily tool error rate from session transcripts"
+        )]
+        errors: bool,
+        #[arg(long, help = "Exit with status 1 if no usage data was found")]
+        fail_if_empty: bool,
+        #[arg(
+            long,
+            value_name = "SUBSTRING",
+            conflicts_with_all = ["all", "by_session"],
+            help = "Only count models whose id contains this text (e.g. opus)"
+        )]
+        model: Option<String>,
+        #[arg(
+            long,
+            conflicts_with_all = ["all", "by_session"],
+            help = "Merge dated model versions (claude-sonnet-4-5-20250929) into one row per model"
+        )]
+        aggregate_models: bool,
+        #[arg(
+            long,
+            help = "Print the model pricing used for --cost (including pricing.toml overrides)"
+        )]
+        show_pricing: bool,
     },
     #[command(about = "View past Claude Code sessions")]
     Sessions {
+        #[command(subcommand)]
+        action: Option<SessionsAction>,
         #[arg(help = "Session ID to show details (lists recent if not specified)")]
         session_id: Option<String>,
         #[arg(long, help = "Show only today's sessions")]
         today: bool,
+        #[arg(
+            long,
+            value_name = "YYYY-MM-DD",
+            help = "Only sessions started on or after this date"
+        )]
+        since: Option<String>,
+        #[arg(
+            long,
+            value_name = "YYYY-MM-DD",
+            help = "Only sessions started on or before this date"
+        )]
+        until: Option<String>,
+        #[arg(long, default_value = "10", help = "Number of sessions to show")]
+        limit: usize,
+        #[arg(long, help = "Step through the session interactively")]
+        replay: bool,
+        #[arg(
+            long,
+            help = "Hide agent (Task) call counts from the session list; tool counts never include them"
+        )]
+        no_agents: bool,
+        #[arg(
+            long,
+            value_name = "TOOL",
+            help = "List only sessions that called this tool (e.g. Bash), with a count column"
+        )]
+        used_tool: Option<String>,
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            requires = "session_id",
+            conflicts_with = "replay",
+            help = "Export the session instead of showing it"
+        )]
+        export: Option<SessionExportFormat>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            requires = "export",
+            help = "Write the export to a file instead of stdout"
+        )]
+        out: Option<PathBuf>,
+        #[arg(
+            long,
+            value_name = "CMD",
+            requires = "session_id",
+            conflicts_with_all = ["replay", "out"],
+            help = "Pipe the exported session (Markdown unless --export says otherwise) to CMD's stdin"
+        )]
+        summary_cmd: Option<String>,
+    },
+    #[command(about = "Find past sessions by tool call targets (commands, paths, patterns)")]
+    Search {
+        #[arg(help = "Text to look for (case-insensitive)")]
+        query: String,
+        #[arg(long, help = "Only match calls of this tool (e.g. Bash, Edit, Grep)")]
+        tool: Option<String>,
         #[arg(long, default_value = "10", help = "Number of sessions to show")]
         limit: usize,
     },
@@ -123,6 +395,10 @@ pub enum Commands {
     Watch {
         #[arg(help = "Profile name (uses most recent session if not specified)")]
         profile: Option<String>,
+        #[arg(long, help = "Session id (or unique part of it) to watch")]
+        session: Option<String>,
+        #[arg(long, help = "Also show the first line of each assistant text block")]
+        show_text: bool,
     },
     #[command(about = "Manage HUD statusline plugin")]
     Hud {
@@ -131,21 +407,106 @@ pub enum Commands {
     },
     #[command(about = "Export environment variables for a profile")]
     Env {
-        #[arg(help = "Profile name to export environment for")]
+        #[arg(help = "Profile name to export environment for (uses default if not specified)")]
+        profile: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "powershell",
+            help = "Print fish syntax (set -gx)"
+        )]
+        fish: bool,
+        #[arg(long, help = "Print PowerShell syntax ($env:NAME = ...)")]
+        powershell: bool,
+    },
+    #[command(about = "Diagnose common setup problems")]
+    Doctor,
+    #[command(about = "Show version and build information (target, git commit)")]
+    Version,
+    #[command(about = "Export or import a profile's usage stats")]
+    Stats {
+        #[command(subcommand)]
+        action: StatsAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StatsAction {
+    #[command(about = "Write a profile's stats-cache.json as pretty JSON")]
+    Export {
+        #[arg(long, help = "Profile to export")]
         profile: String,
+        #[arg(long, value_name = "PATH", help = "Write to a file instead of stdout")]
+        out: Option<PathBuf>,
+    },
+    #[command(about = "Merge a stats-cache.json from another machine into a profile's stats")]
+    Import {
+        #[arg(help = "stats-cache.json to import")]
+        file: PathBuf,
+        #[arg(long, help = "Profile to import into")]
+        profile: String,
+        #[arg(
+            long,
+            default_value_t = MergeStrategy::Sum,
+            help = "How to combine days both caches have: sum, or max (re-importing is harmless)"
+        )]
+        strategy: MergeStrategy,
     },
 }
 
 #[derive(Subcommand)]
 pub enum ConfigAction {
     #[command(about = "Show current configuration")]
-    Show,
+    Show {
+        #[arg(long, help = "List every file and directory rafctl reads or writes")]
+        paths: bool,
+    },
     #[command(about = "Set default profile")]
     SetDefault { profile: String },
     #[command(about = "Clear default profile")]
     ClearDefault,
     #[command(about = "Show configuration file path")]
     Path,
+    #[command(about = "Remove a stale OAuth lock file left behind by a killed instance")]
+    ClearLock {
+        #[arg(long, short = 'y', help = "Skip confirmation prompt")]
+        yes: bool,
+    },
+    #[command(about = "Remove cache files of deleted profiles and expired cache entries")]
+    PruneCache,
+    #[command(about = "Reset global config to defaults (keeps profiles and credentials)")]
+    Reset {
+        #[arg(long, short = 'y', help = "Skip confirmation prompt")]
+        yes: bool,
+    },
+    #[command(
+        about = "List recognized environment variables and their current values",
+        visible_alias = "list-env"
+    )]
+    Env,
+    #[command(about = "Open config.yaml in $EDITOR and validate it on save")]
+    Edit,
+    #[command(about = "Export the portable global settings as YAML")]
+    Export {
+        #[arg(
+            short = 'o',
+            long = "out",
+            value_name = "PATH",
+            help = "Write to this file instead of stdout"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Merge global settings from a file written by 'config export'")]
+    Import { file: PathBuf },
+    #[command(about = "Archive the whole rafctl config dir (profiles, settings) to a tar file")]
+    Backup { file: PathBuf },
+    #[command(about = "Restore a tar file written by 'config backup'")]
+    Restore {
+        file: PathBuf,
+        #[arg(long, help = "Keep existing profiles and only add the missing ones")]
+        merge: bool,
+        #[arg(long, short = 'y', help = "Skip confirmation prompt")]
+        yes: bool,
+    },
     #[command(about = "Configure HUD statusline integration")]
     Hud {
         #[arg(long, help = "Enable HUD for profile")]
@@ -162,13 +523,46 @@ pub enum ProfileAction {
     #[command(about = "Add a new profile")]
     Add {
         name: String,
-        #[arg(long, help = "Tool type: claude or codex")]
-        tool: String,
+        #[arg(
+            long,
+            help = "Tool type: claude, codex, or gemini (prompts interactively if omitted)"
+        )]
+        tool: Option<String>,
         #[arg(long, help = "Auth mode for Claude: oauth (default) or api-key")]
         auth_mode: Option<String>,
+        #[arg(long, help = "Profile to switch to when this one's quota is exhausted")]
+        fallback: Option<String>,
+        #[arg(
+            long,
+            value_name = "MODE",
+            help = "Config dir used by run: isolated (default, own dir) or shared (tool's default dir, credentials still swapped)"
+        )]
+        claude_config_dir_mode: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "claude_config_dir_mode",
+            help = "Shorthand for --claude-config-dir-mode shared"
+        )]
+        no_dir: bool,
+        #[arg(
+            long,
+            help = "Prompt for the API key without echoing it (implies --auth-mode api-key)"
+        )]
+        prompt_key: bool,
     },
     #[command(about = "List all profiles")]
-    List,
+    List {
+        #[arg(long, help = "Only show profiles that are currently authenticated")]
+        authenticated_only: bool,
+        #[arg(long, help = "Only show profiles with this tag")]
+        tag: Option<String>,
+        #[arg(long, value_enum, default_value_t = ProfileSort::Name, help = "Sort order")]
+        sort: ProfileSort,
+        #[arg(long, help = "Reverse the sort order")]
+        reverse: bool,
+        #[arg(long, help = "Show at most this many profiles")]
+        limit: Option<usize>,
+    },
     #[command(about = "Remove a profile")]
     Remove {
         name: String,
@@ -178,7 +572,82 @@ pub enum ProfileAction {
         dry_run: bool,
     },
     #[command(about = "Show profile details")]
-    Show { name: String },
+    Show {
+        name: String,
+        #[arg(
+            long,
+            help = "Show which credentials are stored in the keyring and on disk (never their values)"
+        )]
+        secrets: bool,
+    },
+    #[command(about = "Show recent launches of a profile")]
+    History {
+        name: String,
+        #[arg(long, default_value = "20", help = "Number of launches to show")]
+        limit: usize,
+    },
+    #[command(about = "Export a profile to a portable JSON bundle")]
+    Export {
+        name: String,
+        #[arg(
+            long = "out",
+            short = 'o',
+            value_name = "PATH",
+            help = "Output file (default: <name>.rafctl-profile.json)"
+        )]
+        output: Option<PathBuf>,
+        #[arg(long, help = "Include OAuth token and API key from the keyring")]
+        include_secrets: bool,
+    },
+    #[command(about = "Set shell commands to run before/after 'rafctl run' launches the tool")]
+    SetHook {
+        profile: String,
+        #[arg(
+            long,
+            value_name = "CMD",
+            help = "Pre-run command (empty string removes it)"
+        )]
+        pre: Option<String>,
+        #[arg(
+            long,
+            value_name = "CMD",
+            help = "Post-run command (empty string removes it)"
+        )]
+        post: Option<String>,
+        #[arg(long, conflicts_with_all = ["pre", "post"], help = "Remove both hooks")]
+        clear: bool,
+    },
+    #[command(
+        about = "Fold one profile's sessions, stats and history into another, then delete it"
+    )]
+    Merge {
+        #[arg(help = "Profile to merge and delete")]
+        from: String,
+        #[arg(help = "Profile that receives the data")]
+        into: String,
+        #[arg(short = 'y', long, help = "Skip confirmation prompt")]
+        yes: bool,
+    },
+    #[command(about = "Add a tag to a profile (or remove it with --remove)")]
+    Tag {
+        profile: String,
+        tag: String,
+        #[arg(long, help = "Remove the tag instead of adding it")]
+        remove: bool,
+    },
+    #[command(about = "Check whether a name is allowed for 'profile add' (exit 1 if not)")]
+    ValidateName { name: String },
+    #[command(about = "Import a profile from a bundle created by 'profile export'")]
+    Import {
+        file: PathBuf,
+        #[arg(long, help = "Overwrite an existing profile with the same name")]
+        force: bool,
+        #[arg(
+            long,
+            help = "Keep the bundle's pre/post-run hook commands (dropped by default)"
+        )]
+        with_hooks: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -201,6 +670,67 @@ pub enum AuthAction {
         profile: String,
         #[arg(long, help = "API key (prompts if not provided)")]
         key: Option<String>,
+        #[arg(long, help = "Skip checking the key against the Anthropic API")]
+        no_verify: bool,
+    },
+    #[command(about = "Store an additional API key for a profile")]
+    AddKey {
+        profile: String,
+        #[arg(long, help = "API key (prompts if not provided)")]
+        key: Option<String>,
+        #[arg(long, help = "Skip checking the key against the Anthropic API")]
+        no_verify: bool,
+    },
+    #[command(about = "Switch a profile to its next stored API key")]
+    RotateKey { profile: String },
+    #[command(about = "Move plaintext API keys from profile metadata into the keyring")]
+    Migrate,
+    #[command(
+        about = "Import the current Claude Code OAuth login into a profile (macOS)",
+        visible_alias = "capture"
+    )]
+    ImportToken { profile: String },
+    #[command(
+        about = "Put back the system Claude login that an OAuth launch swapped out",
+        long_about = "Put back the system Claude login that an OAuth launch swapped out.\n\n\
+                      Before launching an OAuth profile, rafctl saves the token in Claude \
+                      Code's system keychain entry and restores it when the tool exits. If \
+                      rafctl was killed in between, the system entry still holds the \
+                      profile's token; run this to restore the saved one."
+    )]
+    RestoreSystem,
+    #[command(
+        about = "Print a profile's stored OAuth token for scripts (secret)",
+        long_about = "Print a profile's stored OAuth token to stdout so scripts can call the \
+                      Anthropic API directly.\n\n\
+                      The token grants full access to the account until it expires. Anything \
+                      that captures stdout (shell history, CI logs, terminal scrollback) will \
+                      hold a live credential, so pipe it straight into the consumer and never \
+                      echo it. The token is only printed when --yes-i-understand-this-is-secret \
+                      is passed, and --json output additionally requires --json-token."
+    )]
+    Export {
+        profile: String,
+        #[arg(
+            long = "yes-i-understand-this-is-secret",
+            help = "Confirm that the token will be written to stdout"
+        )]
+        confirmed: bool,
+        #[arg(long, help = "Allow the token to appear in --json output")]
+        json_token: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SessionsAction {
+    #[command(about = "Move a project's global sessions into a profile's transcripts dir")]
+    Migrate {
+        #[arg(long, help = "Project directory whose sessions should be moved")]
+        project: PathBuf,
+        #[arg(long, help = "Profile to move the sessions into")]
+        to: String,
+        #[arg(long, short = 'y', help = "Skip confirmation prompt")]
+        yes: bool,
     },
 }
 
@@ -225,5 +755,179 @@ pub enum HudAction {
 
 pub fn generate_completions(shell: Shell) {
     let mut cmd = Cli::command();
-    generate(shell, &mut cmd, "rafctl", &mut io::stdout());
+    let mut buf = Vec::new();
+    generate(shell, &mut cmd, "rafctl", &mut buf);
+    let script = String::from_utf8_lossy(&buf).into_owned();
+
+    let mut commands = Vec::new();
+    collect_profile_arg_commands(&cmd, "rafctl", &mut commands);
+
+    let script = match shell {
+        Shell::Bash => bash_completions(script, &commands),
+        Shell::Zsh => zsh_completions(script, &commands),
+        Shell::Fish => fish_completions(script, &commands),
+        Shell::PowerShell => powershell_completions(script, &commands),
+        _ => script,
+    };
+    let _ = io::stdout().write_all(script.as_bytes());
+}
+
+/// Helper invoked by completion scripts as `rafctl __complete <target>`.
+/// It is handled before clap parsing so it never shows up in the generated
+/// scripts themselves.
+pub const COMPLETE_COMMAND: &str = "__complete";
+
+/// Print candidates for `rafctl __complete <target>`, one per line.
+/// Errors and unknown targets print nothing so a broken config never breaks
+/// the shell prompt.
+pub fn print_completion_candidates(target: Option<&str>) {
+    if target == Some("profiles") {
+        for name in list_profiles().unwrap_or_default() {
+            println!("{}", name);
+        }
+    }
+}
+
+/// The static clap script, re-registered behind a wrapper that offers profile
+/// names for the first positional of the commands in `commands`.
+fn bash_completions(script: String, commands: &[String]) -> String {
+    let patterns = commands
+        .iter()
+        .map(|c| format!("\"{}\"", c))
+        .collect::<Vec<_>>()
+        .join("|");
+
+    let hook = format!(
+        r#"
+_rafctl_profiles() {{
+    _rafctl "$@"
+
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" cmd_path="rafctl" i
+    [[ "$cur" == -* ]] && return 0
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        [[ "${{COMP_WORDS[i]}}" == -* ]] || cmd_path="$cmd_path;${{COMP_WORDS[i]}}"
+    done
+    case "$cmd_path" in
+        {patterns})
+            COMPREPLY=( $(compgen -W "$(rafctl {COMPLETE_COMMAND} profiles 2>/dev/null)" -- "$cur") )
+            ;;
+    esac
+}}
+"#
+    );
+
+    let script = script.replace("complete -F _rafctl ", "complete -F _rafctl_profiles ");
+    match script.rfind(BASH_REGISTER_LINE) {
+        Some(pos) => format!("{}{}\n{}", &script[..pos], hook, &script[pos..]),
+        None => script,
+    }
+}
+
+/// Line in clap's bash script that starts registering the completion function
+const BASH_REGISTER_LINE: &str = "if [[ \"${BASH_VERSINFO[0]}\"";
+
+/// The static clap script with its entry point renamed, so a `_rafctl` that
+/// first offers profile names also runs when the file is autoloaded from fpath.
+fn zsh_completions(script: String, commands: &[String]) -> String {
+    let keys = commands
+        .iter()
+        .map(|c| format!("'{}'", c))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let hook = format!(
+        r#"_rafctl() {{
+    local -a profile_commands=({keys})
+    local cmd_path="rafctl" word
+    if [[ "$PREFIX" != -* ]]; then
+        for word in "${{(@)words[2,CURRENT-1]}}"; do
+            [[ "$word" == -* ]] || cmd_path="$cmd_path;$word"
+        done
+        if (( ${{profile_commands[(Ie)$cmd_path]}} )); then
+            compadd -- ${{(f)"$(rafctl {COMPLETE_COMMAND} profiles 2>/dev/null)"}}
+            return
+        fi
+    fi
+    _rafctl_clap "$@"
+}}
+
+"#
+    );
+
+    let script = script.replacen("\n_rafctl() {\n", "\n_rafctl_clap() {\n", 1);
+    match script.rfind(ZSH_REGISTER_LINE) {
+        Some(pos) => format!("{}{}{}", &script[..pos], hook, &script[pos..]),
+        None => script,
+    }
+}
+
+/// Line in clap's zsh script that runs or registers the completion function
+const ZSH_REGISTER_LINE: &str = "if [ \"$funcstack[1]\" = \"_rafctl\" ]; then";
+
+/// The static clap script plus a rule completing profile names.
+fn fish_completions(script: String, commands: &[String]) -> String {
+    let keys = commands
+        .iter()
+        .map(|c| format!("'{}'", c))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        r#"{script}
+function __fish_rafctl_wants_profile
+    set -l cmd_path rafctl
+    for word in (commandline -opc)[2..-1]
+        string match -q -- '-*' $word; or set cmd_path "$cmd_path;$word"
+    end
+    contains -- $cmd_path {keys}
+end
+complete -c rafctl -n __fish_rafctl_wants_profile -f -a "(rafctl {COMPLETE_COMMAND} profiles 2>/dev/null)"
+"#
+    )
+}
+
+/// The static clap script plus profile-name hints from `rafctl __complete profiles`.
+fn powershell_completions(script: String, commands: &[String]) -> String {
+    let keys = commands
+        .iter()
+        .map(|k| format!("'{}'", k))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let hook = format!(
+        r#"    if ($command -in @({keys})) {{
+        $completions += @(rafctl {COMPLETE_COMMAND} profiles 2>$null | ForEach-Object {{
+            [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, 'Profile')
+        }})
+    }}
+
+"#
+    );
+
+    match script.find(POWERSHELL_FILTER_LINE) {
+        Some(pos) => format!("{}{}{}", &script[..pos], hook, &script[pos..]),
+        None => script,
+    }
+}
+
+/// Line in clap's PowerShell script that filters the collected completions
+const POWERSHELL_FILTER_LINE: &str = "    $completions.Where{";
+
+/// Subcommand paths (in clap's `rafctl;auth;login` form) whose first
+/// positional argument names an existing profile.
+fn collect_profile_arg_commands(cmd: &clap::Command, path: &str, out: &mut Vec<String>) {
+    let takes_profile =
+        cmd.get_positionals()
+            .next()
+            .is_some_and(|arg| match arg.get_id().as_str() {
+                "profile" => true,
+                "name" => !matches!(cmd.get_name(), "add" | "validate-name"),
+                _ => false,
+            });
+    if takes_profile {
+        out.push(path.to_string());
+    }
+
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        collect_profile_arg_commands(sub, &format!("{};{}", path, sub.get_name()), out);
+    }
 }
@@ -1,40 +1,217 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use colored::Colorize;
 use serde::Serialize;
 
 use super::OutputFormat;
+use crate::core::backup::create_private;
+use crate::error::RafctlError;
 
-pub fn print_success(message: &str, format: OutputFormat) {
+pub fn print_success(message: &str, format: OutputFormat) -> Result<(), RafctlError> {
     match format {
         OutputFormat::Human => println!("\u{2713} {}", message),
-        OutputFormat::Plain => println!("OK: {}", message),
-        OutputFormat::Json => print_json(&SuccessMessage { ok: true, message }),
+        OutputFormat::Plain | OutputFormat::Porcelain => println!("OK: {}", message),
+        OutputFormat::Json => print_json(&SuccessMessage { ok: true, message })?,
     }
+    Ok(())
 }
 
-pub fn print_error(message: &str, format: OutputFormat) {
+pub fn print_error(message: &str, format: OutputFormat) -> Result<(), RafctlError> {
     match format {
         OutputFormat::Human => eprintln!("\u{2717} {}", message),
-        OutputFormat::Plain => eprintln!("ERROR: {}", message),
+        OutputFormat::Plain | OutputFormat::Porcelain => eprintln!("ERROR: {}", message),
         OutputFormat::Json => print_json(&ErrorMessage {
             ok: false,
             error: message,
-        }),
+        })?,
     }
+    Ok(())
 }
 
 pub fn print_info(message: &str, format: OutputFormat) {
     match format {
         OutputFormat::Human => println!("\u{2139} {}", message),
-        OutputFormat::Plain => println!("INFO: {}", message),
+        OutputFormat::Plain | OutputFormat::Porcelain => println!("INFO: {}", message),
         OutputFormat::Json => {}
     }
 }
 
-pub fn print_json<T: Serialize>(data: &T) {
-    if let Ok(json) = serde_json::to_string_pretty(data) {
-        println!("{}", json);
+/// An error followed by its `source()` chain, e.g. the YAML parse error
+/// behind a `ConfigRead`.
+pub fn error_with_causes(err: &dyn Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Warn on stderr about a profile that exists on disk but cannot be loaded,
+/// so list-style commands can skip it without hiding the problem.
+pub fn warn_corrupted_profile(name: &str, err: &dyn Error, format: OutputFormat) {
+    let message = format!("Skipping profile '{}': {}", name, error_with_causes(err));
+    match format {
+        OutputFormat::Human => {
+            eprintln!("{} {}", "⚠".yellow(), message);
+            eprintln!(
+                "{}",
+                "  Fix or delete its meta.yaml; rafctl doctor lists every corrupted profile"
+                    .dimmed()
+            );
+        }
+        _ => eprintln!("WARN: {}", message),
+    }
+}
+
+/// Ask a yes/no question on the terminal; anything but y/yes is a no.
+pub fn confirm(prompt: &str) -> Result<bool, RafctlError> {
+    print!("{} ", prompt);
+    let _ = io::stdout().flush();
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| RafctlError::ConfigRead {
+            path: PathBuf::from("stdin"),
+            source: e,
+        })?;
+
+    let answer = input.trim().to_lowercase();
+    Ok(answer == "y" || answer == "yes")
+}
+
+/// File that `--output` sends JSON payloads to instead of stdout
+static OUTPUT_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Everything written to the `--output` file so far, so a command that prints
+/// several payloads leaves all of them, as stdout would
+static OUTPUT_WRITTEN: Mutex<String> = Mutex::new(String::new());
+
+/// Send every later `print_json` payload to `path` (global `--output`)
+pub fn set_output_file(path: PathBuf) {
+    let _ = OUTPUT_FILE.set(path);
+}
+
+pub fn print_json<T: Serialize>(data: &T) -> Result<(), RafctlError> {
+    let Ok(json) = serde_json::to_string_pretty(data) else {
+        return Ok(());
+    };
+    match OUTPUT_FILE.get() {
+        Some(path) => {
+            let mut written = OUTPUT_WRITTEN.lock().unwrap_or_else(|e| e.into_inner());
+            written.push_str(&json);
+            written.push('\n');
+            write_atomically(path, &written).map_err(|e| RafctlError::ConfigWrite {
+                path: path.clone(),
+                source: e,
+            })
+        }
+        None => {
+            println!("{}", json);
+            Ok(())
+        }
+    }
+}
+
+/// Write via a temp file in the same directory and rename it into place, so
+/// readers never see a half-written report. The file is private to the user,
+/// since reports such as `auth export --json-token` can carry secrets.
+fn write_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+    static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| std::io::Error::other("not a file path"))?;
+    let tmp = path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = create_private(&tmp)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .and_then(|_| std::fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Print one compact JSON object per line and flush, for streaming consumers.
+pub fn print_json_line<T: Serialize>(data: &T) {
+    if let Ok(json) = serde_json::to_string(data) {
+        let mut stdout = std::io::stdout().lock();
+        let _ = writeln!(stdout, "{}", json);
+        let _ = stdout.flush();
     }
 }
 
+/// Version of the `--porcelain` line protocol. Bumped only on breaking
+/// changes; adding keys or record kinds is not one.
+pub const PORCELAIN_VERSION: u32 = 1;
+
+/// Print the `--porcelain` header line; it always comes first.
+pub fn print_porcelain_header() {
+    println!("# rafctl-porcelain v{}", PORCELAIN_VERSION);
+}
+
+/// One `--porcelain` record: the record kind followed by tab-separated
+/// `key=value` fields. Unset values are left out rather than printed empty.
+pub struct PorcelainRecord {
+    line: String,
+}
+
+impl PorcelainRecord {
+    pub fn new(kind: &str) -> Self {
+        PorcelainRecord {
+            line: kind.to_string(),
+        }
+    }
+
+    pub fn field(mut self, key: &str, value: impl Display) -> Self {
+        self.line.push('\t');
+        self.line.push_str(key);
+        self.line.push('=');
+        self.line.push_str(&escape_porcelain(&value.to_string()));
+        self
+    }
+
+    pub fn opt_field(self, key: &str, value: Option<impl Display>) -> Self {
+        match value {
+            Some(value) => self.field(key, value),
+            None => self,
+        }
+    }
+
+    pub fn print(self) {
+        println!("{}", self.line);
+    }
+}
+
+/// Escape backslashes, tabs and newlines so every record stays on one line.
+fn escape_porcelain(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 #[derive(Serialize)]
 struct SuccessMessage<'a> {
     ok: bool,
@@ -46,3 +223,41 @@ struct ErrorMessage<'a> {
     ok: bool,
     error: &'a str,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_with_causes_includes_source() {
+        let err = crate::error::RafctlError::ConfigRead {
+            path: "/tmp/meta.yaml".into(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, "bad indentation"),
+        };
+        assert_eq!(
+            error_with_causes(&err),
+            "Failed to read config '/tmp/meta.yaml': bad indentation"
+        );
+    }
+
+    #[test]
+    fn test_write_atomically_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        std::fs::write(&path, "old").unwrap();
+
+        write_atomically(&path, "{}\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}\n");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_porcelain_record_escapes_values() {
+        let record = PorcelainRecord::new("profile")
+            .field("name", "work")
+            .opt_field("last_used", None::<&str>)
+            .field("note", "a\tb\\c\nd");
+        assert_eq!(record.line, "profile\tname=work\tnote=a\\tb\\\\c\\nd");
+    }
+}
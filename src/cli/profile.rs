@@ -1,16 +1,33 @@
-use std::io::{self, Write};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
 use colored::Colorize;
 use serde::Serialize;
 
-use super::output::print_json;
-use super::OutputFormat;
-use crate::core::credentials;
+use super::auth::prompt_api_key;
+use super::debug::is_verbose;
+use super::output::{confirm, print_json, print_porcelain_header, PorcelainRecord};
+use super::{OutputFormat, ProfileSort};
+use crate::core::backup::create_private;
+use crate::core::bundle::{build_bundle, restore_bundle, ProfileBundle};
+use crate::core::config::{load_global_config, save_global_config};
+use crate::core::credentials::{self, CredentialType};
+use crate::core::history::{load_history, merge_history, HistoryEntry};
 use crate::core::profile::{
-    delete_profile, list_profiles, load_profile, profile_exists, resolve_profile_alias,
-    save_profile, validate_profile_name, AuthMode, Profile, ToolType,
+    delete_profile, get_profile_dir, list_profiles, list_profiles_with_tag, load_profile,
+    profile_exists, resolve_profile_alias, save_profile, validate_profile_name, validate_tag,
+    AuthMode, ConfigMode, Profile, ToolType,
+};
+use crate::core::stats::{
+    get_profile_stats_path, load_profile_stats, load_stats_cache, merge_cost_ledger,
+    save_stats_cache,
+};
+use crate::core::transcript::{
+    count_conflicting_transcripts, move_all_transcripts, transcripts_dir_for,
 };
 use crate::error::RafctlError;
+use crate::tools::is_authenticated;
 
 #[derive(Serialize)]
 struct ProfileInfo {
@@ -18,8 +35,70 @@ struct ProfileInfo {
     tool: String,
     auth_mode: Option<String>,
     api_key_configured: Option<bool>,
+    config_mode: String,
     created_at: String,
     last_used: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pre_run: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    post_run: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secrets: Option<Vec<SecretStatus>>,
+    config_dir: PathBuf,
+    credential_path: PathBuf,
+}
+
+/// Where one kind of credential lives and whether it is there (`profile show --secrets`).
+#[derive(Serialize)]
+struct SecretStatus {
+    credential: &'static str,
+    store: &'static str,
+    /// `None` when the store could not be queried (e.g. keyring locked)
+    present: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+/// Check every place a profile's credentials can live. Values are never read
+/// into the output, only their presence.
+fn secret_statuses(name: &str, profile: &Profile) -> Vec<SecretStatus> {
+    let keyring = |credential, cred_type| match credentials::has_credential(name, cred_type) {
+        Ok(present) => SecretStatus {
+            credential,
+            store: "keyring",
+            present: Some(present),
+            detail: None,
+        },
+        Err(e) => SecretStatus {
+            credential,
+            store: "keyring",
+            present: None,
+            detail: Some(e.to_string()),
+        },
+    };
+
+    #[allow(deprecated)]
+    let legacy_key = profile.api_key.is_some();
+    let credential_path = profile.tool.credential_path(name).ok();
+
+    vec![
+        keyring("oauth-token", CredentialType::OAuthToken),
+        keyring("api-key", CredentialType::ApiKey),
+        SecretStatus {
+            credential: "api-key",
+            store: "meta.yaml",
+            present: Some(legacy_key),
+            detail: legacy_key.then(|| "plaintext; move it with: rafctl auth migrate".to_string()),
+        },
+        SecretStatus {
+            credential: "tool-login",
+            store: "file",
+            present: Some(is_authenticated(profile.tool, name).unwrap_or(false)),
+            detail: credential_path.map(|p| p.display().to_string()),
+        },
+    ]
 }
 
 #[derive(Serialize)]
@@ -27,7 +106,15 @@ struct ProfileListOutput {
     profiles: Vec<ProfileInfo>,
 }
 
-pub fn handle_add(name: &str, tool: &str, auth_mode: Option<&str>) -> Result<(), RafctlError> {
+pub fn handle_add(
+    name: &str,
+    tool: Option<&str>,
+    auth_mode: Option<&str>,
+    fallback: Option<&str>,
+    config_mode: Option<&str>,
+    no_dir: bool,
+    prompt_key: bool,
+) -> Result<(), RafctlError> {
     validate_profile_name(name)?;
 
     let name_lower = name.to_lowercase();
@@ -36,24 +123,83 @@ pub fn handle_add(name: &str, tool: &str, auth_mode: Option<&str>) -> Result<(),
         return Err(RafctlError::ProfileAlreadyExists(name_lower));
     }
 
-    let tool_type: ToolType = tool
-        .parse()
-        .map_err(|e: String| RafctlError::InvalidProfileName(e))?;
+    let auth_flag = match auth_mode {
+        Some(mode) => Some(
+            mode.parse::<AuthMode>()
+                .map_err(RafctlError::InvalidProfileName)?,
+        ),
+        None if prompt_key => Some(AuthMode::ApiKey),
+        None => None,
+    };
+
+    if prompt_key {
+        if auth_flag != Some(AuthMode::ApiKey) {
+            return Err(RafctlError::MissingArgument(
+                "--auth-mode api-key (required by --prompt-key)".to_string(),
+            ));
+        }
+        if !io::stdin().is_terminal() {
+            return Err(RafctlError::MissingArgument(
+                "an interactive terminal (required by --prompt-key)".to_string(),
+            ));
+        }
+    }
 
-    let auth = match auth_mode {
+    let config_mode = match config_mode {
         Some(mode) => mode
-            .parse::<AuthMode>()
+            .parse::<ConfigMode>()
             .map_err(RafctlError::InvalidProfileName)?,
-        None => AuthMode::default(),
+        None if no_dir => ConfigMode::Shared,
+        None => ConfigMode::Isolated,
+    };
+
+    let (tool_type, auth, api_key) = match tool {
+        Some(t) => {
+            let tool_type: ToolType = t
+                .parse()
+                .map_err(|e: String| RafctlError::InvalidProfileName(e))?;
+            if prompt_key && tool_type != ToolType::Claude {
+                return Err(RafctlError::MissingArgument(
+                    "--tool claude (required by --prompt-key)".to_string(),
+                ));
+            }
+            let api_key = if prompt_key {
+                prompt_optional_api_key()?
+            } else {
+                None
+            };
+            (tool_type, auth_flag.unwrap_or_default(), api_key)
+        }
+        None if io::stdin().is_terminal() => run_add_wizard(auth_flag)?,
+        None => {
+            return Err(RafctlError::MissingArgument(
+                "--tool <claude|codex|gemini>".to_string(),
+            ))
+        }
     };
 
-    if tool_type == ToolType::Codex && auth == AuthMode::ApiKey {
-        eprintln!("{} Codex only supports OAuth authentication", "⚠".yellow());
+    if tool_type != ToolType::Claude && auth == AuthMode::ApiKey {
+        eprintln!(
+            "{} {} only supports OAuth authentication",
+            "⚠".yellow(),
+            tool_type
+        );
     }
 
-    let profile = Profile::new_with_auth(name_lower.clone(), tool_type, auth);
+    let fallback_profile = match fallback {
+        Some(f) => Some(resolve_profile_alias(f)?.to_lowercase()),
+        None => None,
+    };
+
+    let mut profile = Profile::new_with_auth(name_lower.clone(), tool_type, auth);
+    profile.fallback_profile = fallback_profile;
+    profile.config_mode = config_mode;
     save_profile(&profile)?;
 
+    if let Some(key) = &api_key {
+        credentials::store_credential(&name_lower, CredentialType::ApiKey, key)?;
+    }
+
     let mode_info = if tool_type == ToolType::Claude {
         format!(" ({})", auth)
     } else {
@@ -68,7 +214,17 @@ pub fn handle_add(name: &str, tool: &str, auth_mode: Option<&str>) -> Result<(),
         mode_info
     );
 
-    if auth == AuthMode::ApiKey {
+    if config_mode == ConfigMode::Shared {
+        println!(
+            "{} Shared config dir: {} will use its default config directory",
+            "ℹ".cyan(),
+            tool_type
+        );
+    }
+
+    if api_key.is_some() {
+        println!("{} API key stored securely", "✓".green());
+    } else if auth == AuthMode::ApiKey {
         println!(
             "{} Set API key with: rafctl auth set-key {}",
             "ℹ".cyan(),
@@ -79,26 +235,165 @@ pub fn handle_add(name: &str, tool: &str, auth_mode: Option<&str>) -> Result<(),
     Ok(())
 }
 
-pub fn handle_list(format: OutputFormat) -> Result<(), RafctlError> {
-    let profiles = list_profiles()?;
+/// Ask for a profile name, then create it through the `profile add` wizard.
+/// Used by the dashboard's add key; returns the new profile's name.
+pub fn handle_add_interactive() -> Result<String, RafctlError> {
+    print!("{} ", "Profile name:".bold());
+    let _ = io::stdout().flush();
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| RafctlError::ConfigRead {
+            path: std::path::PathBuf::from("stdin"),
+            source: e,
+        })?;
+
+    let name = input.trim();
+    handle_add(name, None, None, None, None, false, false)?;
+    Ok(name.to_lowercase())
+}
+
+/// Interactively ask for tool, auth mode and (optionally) an API key.
+/// Only used when `--tool` is omitted and stdin is a terminal.
+fn run_add_wizard(
+    auth_flag: Option<AuthMode>,
+) -> Result<(ToolType, AuthMode, Option<String>), RafctlError> {
+    let tools = [ToolType::Claude, ToolType::Codex, ToolType::Gemini];
+    let tool_labels: Vec<String> = tools.iter().map(|t| t.to_string()).collect();
+    let tool_type = tools[prompt_choice("Select tool", &tool_labels)?];
+
+    if tool_type != ToolType::Claude {
+        return Ok((tool_type, auth_flag.unwrap_or_default(), None));
+    }
+
+    let auth = match auth_flag {
+        Some(mode) => mode,
+        None => {
+            let modes = [AuthMode::OAuth, AuthMode::ApiKey];
+            let mode_labels: Vec<String> = modes.iter().map(|m| m.to_string()).collect();
+            modes[prompt_choice("Select auth mode", &mode_labels)?]
+        }
+    };
+
+    if auth != AuthMode::ApiKey {
+        return Ok((tool_type, auth, None));
+    }
+
+    Ok((tool_type, auth, prompt_optional_api_key()?))
+}
+
+fn prompt_optional_api_key() -> Result<Option<String>, RafctlError> {
+    let key = prompt_api_key("Enter API key (leave empty to set later): ")?;
+    Ok((!key.is_empty()).then_some(key))
+}
+
+/// Print a numbered menu and return the selected index (first option is the default)
+fn prompt_choice(title: &str, options: &[String]) -> Result<usize, RafctlError> {
+    println!("{}", format!("{}:", title).bold());
+    for (i, option) in options.iter().enumerate() {
+        println!("  {}) {}", i + 1, option);
+    }
+
+    loop {
+        print!("Choice [1]: ");
+        let _ = io::stdout().flush();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .map_err(|e| RafctlError::ConfigRead {
+                path: std::path::PathBuf::from("stdin"),
+                source: e,
+            })?;
+
+        match parse_choice(&input, options) {
+            Some(index) => return Ok(index),
+            None => eprintln!(
+                "{} Enter a number between 1 and {}",
+                "✗".red(),
+                options.len()
+            ),
+        }
+    }
+}
+
+/// Parse a menu answer: empty selects the first option, otherwise a 1-based
+/// number or the option's name.
+fn parse_choice(input: &str, options: &[String]) -> Option<usize> {
+    let answer = input.trim().to_lowercase();
+    if answer.is_empty() {
+        return Some(0);
+    }
+    if let Ok(n) = answer.parse::<usize>() {
+        return (1..=options.len()).contains(&n).then(|| n - 1);
+    }
+    options.iter().position(|o| o.to_lowercase() == answer)
+}
+
+pub fn handle_list(
+    authenticated_only: bool,
+    tag: Option<&str>,
+    sort: ProfileSort,
+    reverse: bool,
+    limit: Option<usize>,
+    format: OutputFormat,
+) -> Result<(), RafctlError> {
+    let mut profiles = match tag {
+        Some(tag) => list_profiles_with_tag(tag)?,
+        None => list_profiles()?,
+    };
+
+    if authenticated_only {
+        profiles.retain(|name| {
+            load_profile(name)
+                .and_then(|p| is_authenticated(p.tool, name))
+                .unwrap_or(false)
+        });
+
+        if profiles.is_empty() {
+            match format {
+                OutputFormat::Json => print_json(&ProfileListOutput { profiles: vec![] })?,
+                OutputFormat::Porcelain => print_list_porcelain(&[]),
+                _ => println!("No authenticated profiles found."),
+            }
+            return Ok(());
+        }
+    }
 
     if profiles.is_empty() {
         match format {
-            OutputFormat::Json => print_json(&ProfileListOutput { profiles: vec![] }),
+            OutputFormat::Json => print_json(&ProfileListOutput { profiles: vec![] })?,
+            OutputFormat::Porcelain => print_list_porcelain(&[]),
             OutputFormat::Plain => println!("No profiles found."),
             OutputFormat::Human => {
                 println!(
-                    "No profiles found. Create one with: rafctl profile add <name> --tool <claude|codex>"
+                    "No profiles found. Create one with: rafctl profile add <name> --tool <claude|codex|gemini>"
                 );
             }
         }
         return Ok(());
     }
 
+    let mut loaded: Vec<(String, Option<Profile>)> = profiles
+        .into_iter()
+        .map(|name| {
+            let profile = load_profile(&name).ok();
+            (name, profile)
+        })
+        .collect();
+    sort_profiles(&mut loaded, sort);
+    if reverse {
+        loaded.reverse();
+    }
+    if let Some(limit) = limit {
+        loaded.truncate(limit);
+    }
+
     let mut profile_list: Vec<ProfileInfo> = Vec::new();
 
-    for name in &profiles {
-        if let Ok(profile) = load_profile(name) {
+    for (name, profile) in &loaded {
+        if let Some(profile) = profile {
             profile_list.push(ProfileInfo {
                 name: profile.name.clone(),
                 tool: profile.tool.to_string(),
@@ -115,10 +410,17 @@ pub fn handle_list(format: OutputFormat) -> Result<(), RafctlError> {
                 } else {
                     None
                 },
+                config_mode: profile.config_mode.to_string(),
                 created_at: profile.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
                 last_used: profile
                     .last_used
                     .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+                pre_run: profile.pre_run.clone(),
+                post_run: profile.post_run.clone(),
+                tags: profile.tags.clone(),
+                secrets: None,
+                config_dir: get_profile_dir(name)?,
+                credential_path: profile.tool.credential_path(name)?,
             });
         }
     }
@@ -127,8 +429,9 @@ pub fn handle_list(format: OutputFormat) -> Result<(), RafctlError> {
         OutputFormat::Json => {
             print_json(&ProfileListOutput {
                 profiles: profile_list,
-            });
+            })?;
         }
+        OutputFormat::Porcelain => print_list_porcelain(&profile_list),
         OutputFormat::Plain => {
             println!("NAME\tTOOL\tAUTH_MODE\tLAST_USED");
             for p in &profile_list {
@@ -139,9 +442,9 @@ pub fn handle_list(format: OutputFormat) -> Result<(), RafctlError> {
         }
         OutputFormat::Human => {
             println!("{}", "Profiles:".bold());
-            for name in profiles {
-                match load_profile(&name) {
-                    Ok(profile) => {
+            for (name, profile) in &loaded {
+                match profile {
+                    Some(profile) => {
                         let last_used = profile
                             .last_used
                             .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
@@ -158,7 +461,7 @@ pub fn handle_list(format: OutputFormat) -> Result<(), RafctlError> {
                             last_used.dimmed()
                         );
                     }
-                    Err(_) => {
+                    None => {
                         println!("  {} {} (corrupted)", "•".red(), name);
                     }
                 }
@@ -169,7 +472,44 @@ pub fn handle_list(format: OutputFormat) -> Result<(), RafctlError> {
     Ok(())
 }
 
-pub fn handle_show(name: &str, format: OutputFormat) -> Result<(), RafctlError> {
+fn print_list_porcelain(profiles: &[ProfileInfo]) {
+    print_porcelain_header();
+    for p in profiles {
+        PorcelainRecord::new("profile")
+            .field("name", &p.name)
+            .field("tool", &p.tool)
+            .opt_field("auth_mode", p.auth_mode.as_ref())
+            .opt_field("api_key_configured", p.api_key_configured)
+            .field("config_mode", &p.config_mode)
+            .field("created_at", &p.created_at)
+            .opt_field("last_used", p.last_used.as_ref())
+            .opt_field("tags", (!p.tags.is_empty()).then(|| p.tags.join(",")))
+            .print();
+    }
+}
+
+/// Orders `profile list` entries; corrupted profiles always sort last.
+fn sort_profiles(loaded: &mut [(String, Option<Profile>)], sort: ProfileSort) {
+    use std::cmp::Reverse;
+
+    match sort {
+        ProfileSort::Name => loaded.sort_by(|a, b| a.0.cmp(&b.0)),
+        ProfileSort::LastUsed => {
+            loaded.sort_by_key(|(_, p)| Reverse(p.as_ref().and_then(|p| p.last_used)))
+        }
+        ProfileSort::Created => {
+            loaded.sort_by_key(|(_, p)| Reverse(p.as_ref().map(|p| p.created_at)))
+        }
+        ProfileSort::Tokens => loaded.sort_by_cached_key(|(name, p)| {
+            Reverse(
+                p.as_ref()
+                    .map(|p| load_profile_stats(name, p.tool).total_tokens(None)),
+            )
+        }),
+    }
+}
+
+pub fn handle_show(name: &str, secrets: bool, format: OutputFormat) -> Result<(), RafctlError> {
     let resolved_name = resolve_profile_alias(name)?;
     let name_lower = resolved_name.to_lowercase();
     let profile = load_profile(&name_lower)?;
@@ -193,17 +533,24 @@ pub fn handle_show(name: &str, format: OutputFormat) -> Result<(), RafctlError>
         } else {
             None
         },
+        config_mode: profile.config_mode.to_string(),
         created_at: profile.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
         last_used: profile
             .last_used
             .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+        pre_run: profile.pre_run.clone(),
+        post_run: profile.post_run.clone(),
+        tags: profile.tags.clone(),
+        secrets: secrets.then(|| secret_statuses(&name_lower, &profile)),
+        config_dir: get_profile_dir(&name_lower)?,
+        credential_path: profile.tool.credential_path(&name_lower)?,
     };
 
     match format {
         OutputFormat::Json => {
-            print_json(&info);
+            print_json(&info)?;
         }
-        OutputFormat::Plain => {
+        OutputFormat::Plain | OutputFormat::Porcelain => {
             println!("Profile: {}", profile.name);
             println!("Tool: {}", profile.tool);
             if profile.tool == ToolType::Claude {
@@ -218,6 +565,7 @@ pub fn handle_show(name: &str, format: OutputFormat) -> Result<(), RafctlError>
                     );
                 }
             }
+            println!("Config dir mode: {}", profile.config_mode);
             println!(
                 "Created: {}",
                 profile.created_at.format("%Y-%m-%d %H:%M:%S")
@@ -229,6 +577,27 @@ pub fn handle_show(name: &str, format: OutputFormat) -> Result<(), RafctlError>
                     .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
                     .unwrap_or_else(|| "never".to_string())
             );
+            if let Some(cmd) = &profile.pre_run {
+                println!("Pre-run hook: {}", cmd);
+            }
+            if let Some(cmd) = &profile.post_run {
+                println!("Post-run hook: {}", cmd);
+            }
+            if !profile.tags.is_empty() {
+                println!("Tags: {}", profile.tags.join(", "));
+            }
+            if is_verbose() {
+                println!("Profile dir: {}", info.config_dir.display());
+                println!("Credential file: {}", info.credential_path.display());
+            }
+            for s in info.secrets.iter().flatten() {
+                let present = match s.present {
+                    Some(true) => "present",
+                    Some(false) => "absent",
+                    None => "unknown",
+                };
+                println!("Secret: {}\t{}\t{}", s.credential, s.store, present);
+            }
         }
         OutputFormat::Human => {
             println!("{}", format!("Profile: {}", profile.name).bold());
@@ -247,6 +616,9 @@ pub fn handle_show(name: &str, format: OutputFormat) -> Result<(), RafctlError>
                     println!("  API key:    {}", key_status);
                 }
             }
+            if !profile.is_isolated() {
+                println!("  Config dir: {}", "shared".yellow());
+            }
             println!(
                 "  Created:    {}",
                 profile.created_at.format("%Y-%m-%d %H:%M:%S")
@@ -258,6 +630,37 @@ pub fn handle_show(name: &str, format: OutputFormat) -> Result<(), RafctlError>
                     .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
                     .unwrap_or_else(|| "never".to_string())
             );
+            if let Some(cmd) = &profile.pre_run {
+                println!("  Pre-run:    {}", cmd.dimmed());
+            }
+            if let Some(cmd) = &profile.post_run {
+                println!("  Post-run:   {}", cmd.dimmed());
+            }
+            if !profile.tags.is_empty() {
+                println!("  Tags:       {}", profile.tags.join(", ").cyan());
+            }
+            if is_verbose() {
+                println!("  Directory:  {}", info.config_dir.display());
+                println!("  Cred file:  {}", info.credential_path.display());
+            }
+            if let Some(secrets) = &info.secrets {
+                println!("  Secrets:");
+                for s in secrets {
+                    let present = match s.present {
+                        Some(true) => "✓ present".green(),
+                        Some(false) => "✗ absent".red(),
+                        None => "? unknown".yellow(),
+                    };
+                    let detail = s.detail.as_deref().unwrap_or_default();
+                    println!(
+                        "    {:<12} {:<10} {}  {}",
+                        s.credential,
+                        s.store,
+                        present,
+                        detail.dimmed()
+                    );
+                }
+            }
         }
     }
 
@@ -275,32 +678,585 @@ pub fn handle_remove(name: &str, skip_confirm: bool, dry_run: bool) -> Result<()
         return Ok(());
     }
 
-    if !skip_confirm {
-        print!(
-            "{} Are you sure you want to remove profile '{}'? [y/N] ",
+    // Without a terminal there is nobody to answer, so behave like --yes
+    if !skip_confirm && io::stdin().is_terminal() {
+        // A profile with a corrupted meta.yaml must still be removable
+        match load_profile(&name_lower) {
+            Ok(profile) => {
+                let last_used = profile
+                    .last_used
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "never".to_string());
+                println!(
+                    "{} Profile '{}' ({}, last used: {})",
+                    "ℹ".cyan(),
+                    name_lower,
+                    profile.tool,
+                    last_used
+                );
+            }
+            Err(_) => println!(
+                "{} Profile '{}' (metadata unreadable)",
+                "ℹ".cyan(),
+                name_lower
+            ),
+        }
+        if !confirm(&format!(
+            "{} Are you sure you want to remove profile '{}'? [y/N]",
             "⚠".yellow(),
             name_lower
+        ))? {
+            println!("{} Cancelled", "ℹ".cyan());
+            return Ok(());
+        }
+    }
+
+    delete_profile_credentials(&name_lower);
+    delete_profile(&name_lower)?;
+
+    let mut config = load_global_config()?;
+    let was_default = config.default_profile.as_deref() == Some(name_lower.as_str());
+    if config.forget_profile(&name_lower) {
+        save_global_config(&config)?;
+    }
+
+    println!("{} Profile '{}' removed", "✓".green(), name_lower);
+    if was_default {
+        println!(
+            "{} It was the default profile; set a new one with: rafctl config set-default <profile>",
+            "ℹ".cyan()
         );
-        let _ = io::stdout().flush();
+    }
 
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .map_err(|e| RafctlError::ConfigRead {
-                path: std::path::PathBuf::from("stdin"),
-                source: e,
-            })?;
+    Ok(())
+}
+
+/// Remove a profile's keyring entries, warning (not failing) on keyring errors
+fn delete_profile_credentials(name: &str) {
+    for (cred_type, label) in [
+        (CredentialType::OAuthToken, "OAuth token"),
+        (CredentialType::ApiKey, "API key"),
+    ] {
+        if let Err(e) = credentials::delete_all_credentials(name, cred_type) {
+            eprintln!(
+                "{} Could not delete {} from keyring: {}",
+                "⚠".yellow(),
+                label,
+                e
+            );
+        }
+    }
+}
+
+pub fn handle_set_hook(
+    name: &str,
+    pre: Option<&str>,
+    post: Option<&str>,
+    clear: bool,
+) -> Result<(), RafctlError> {
+    if pre.is_none() && post.is_none() && !clear {
+        return Err(RafctlError::MissingArgument(
+            "--pre, --post or --clear".to_string(),
+        ));
+    }
 
-        let answer = input.trim().to_lowercase();
-        if answer != "y" && answer != "yes" {
+    let resolved_name = resolve_profile_alias(name)?;
+    let name_lower = resolved_name.to_lowercase();
+    let mut profile = load_profile(&name_lower)?;
+
+    // An empty command removes the hook
+    let hook = |cmd: &str| Some(cmd.trim().to_string()).filter(|c| !c.is_empty());
+    if clear {
+        profile.pre_run = None;
+        profile.post_run = None;
+    }
+    if let Some(cmd) = pre {
+        profile.pre_run = hook(cmd);
+    }
+    if let Some(cmd) = post {
+        profile.post_run = hook(cmd);
+    }
+    save_profile(&profile)?;
+
+    println!("{} Hooks updated for '{}'", "✓".green(), name_lower);
+    for (label, cmd) in [
+        ("pre_run", &profile.pre_run),
+        ("post_run", &profile.post_run),
+    ] {
+        println!("  • {}: {}", label, cmd.as_deref().unwrap_or("(none)"));
+    }
+
+    Ok(())
+}
+
+/// Add `tag` to a profile, or remove it with `remove`.
+pub fn handle_tag(name: &str, tag: &str, remove: bool) -> Result<(), RafctlError> {
+    let resolved_name = resolve_profile_alias(name)?;
+    let name_lower = resolved_name.to_lowercase();
+    let mut profile = load_profile(&name_lower)?;
+    let tag = tag.to_lowercase();
+
+    if remove {
+        if !profile.has_tag(&tag) {
+            println!(
+                "{} Profile '{}' has no tag '{}'",
+                "ℹ".cyan(),
+                name_lower,
+                tag
+            );
+            return Ok(());
+        }
+        profile.tags.retain(|t| !t.eq_ignore_ascii_case(&tag));
+        save_profile(&profile)?;
+        println!(
+            "{} Removed tag '{}' from '{}'",
+            "✓".green(),
+            tag,
+            name_lower
+        );
+        return Ok(());
+    }
+
+    validate_tag(&tag)?;
+    if profile.has_tag(&tag) {
+        println!(
+            "{} Profile '{}' is already tagged '{}'",
+            "ℹ".cyan(),
+            name_lower,
+            tag
+        );
+        return Ok(());
+    }
+    profile.tags.push(tag.clone());
+    profile.tags.sort();
+    save_profile(&profile)?;
+    println!("{} Tagged '{}' with '{}'", "✓".green(), name_lower, tag);
+
+    Ok(())
+}
+
+pub fn handle_merge(from: &str, into: &str, skip_confirm: bool) -> Result<(), RafctlError> {
+    let from_name = resolve_profile_alias(from)?.to_lowercase();
+    let into_name = resolve_profile_alias(into)?.to_lowercase();
+    let cannot_merge = |reason: String| RafctlError::CannotMerge {
+        from: from_name.clone(),
+        into: into_name.clone(),
+        reason,
+    };
+
+    if from_name == into_name {
+        return Err(cannot_merge(
+            "a profile cannot be merged into itself".to_string(),
+        ));
+    }
+
+    let from_profile = load_profile(&from_name)?;
+    let mut into_profile = load_profile(&into_name)?;
+    if from_profile.tool != into_profile.tool {
+        return Err(cannot_merge(format!(
+            "tools differ ({} vs {})",
+            from_profile.tool, into_profile.tool
+        )));
+    }
+
+    // Deleting the source afterwards would lose transcripts that cannot move
+    if from_profile.tool == ToolType::Claude {
+        let conflicts = count_conflicting_transcripts(
+            &transcripts_dir_for(&from_profile)?,
+            &transcripts_dir_for(&into_profile)?,
+        );
+        if conflicts > 0 {
+            return Err(cannot_merge(format!(
+                "{} transcript file(s) exist in both profiles",
+                conflicts
+            )));
+        }
+    }
+
+    // Nobody can answer the prompt, and deleting the source profile is too
+    // destructive to assume a yes
+    if !skip_confirm && !io::stdin().is_terminal() {
+        return Err(RafctlError::MissingArgument(
+            "--yes (required to merge profiles without a terminal)".to_string(),
+        ));
+    }
+    if !skip_confirm {
+        println!(
+            "{} Sessions, stats and history of '{}' will move to '{}'",
+            "ℹ".cyan(),
+            from_name,
+            into_name
+        );
+        let question = format!(
+            "{} Delete profile '{}' and its credentials afterwards? [y/N]",
+            "⚠".yellow(),
+            from_name
+        );
+        if !confirm(&question)? {
             println!("{} Cancelled", "ℹ".cyan());
             return Ok(());
         }
     }
 
-    delete_profile(&name_lower)?;
+    let mut merged: Vec<String> = Vec::new();
+    let mut left_behind = 0;
 
-    println!("{} Profile '{}' removed", "✓".green(), name_lower);
+    if from_profile.tool == ToolType::Claude {
+        let source = transcripts_dir_for(&from_profile)?;
+        let destination = transcripts_dir_for(&into_profile)?;
+        if source != destination {
+            let (moved, skipped) = move_all_transcripts(&source, &destination).map_err(|e| {
+                RafctlError::ConfigWrite {
+                    path: destination.clone(),
+                    source: e,
+                }
+            })?;
+            left_behind = skipped;
+            if moved > 0 {
+                merged.push(format!("Moved {} transcript file(s)", moved));
+            }
+        }
+    }
+
+    let from_stats = get_profile_stats_path(&from_name, from_profile.tool)?;
+    if from_stats.exists() {
+        let into_stats = get_profile_stats_path(&into_name, into_profile.tool)?;
+        let mut stats = load_stats_cache(&into_stats);
+        stats.merge(&load_stats_cache(&from_stats));
+        save_stats_cache(&into_stats, &stats)?;
+        merged.push("Merged usage stats".to_string());
+    }
+
+    let launches = merge_history(&from_name, &into_name)?;
+    if launches > 0 {
+        merged.push(format!("Merged {} launch history entries", launches));
+    }
+
+    let costs = merge_cost_ledger(&from_name, &into_name)?;
+    if costs > 0 {
+        merged.push(format!("Merged {} cost ledger entries", costs));
+    }
+
+    into_profile.last_used = into_profile.last_used.max(from_profile.last_used);
+    for tag in &from_profile.tags {
+        if !into_profile.has_tag(tag) {
+            into_profile.tags.push(tag.clone());
+        }
+    }
+    into_profile.tags.sort();
+    if into_profile.fallback_profile.as_deref() == Some(from_name.as_str()) {
+        into_profile.fallback_profile = None;
+    }
+    save_profile(&into_profile)?;
+
+    let retargeted = retarget_profile_references(&from_name, &into_name)?;
+    if retargeted > 0 {
+        merged.push(format!("Updated {} config reference(s)", retargeted));
+    }
+
+    // A transcript that appeared in both profiles after the check above stays
+    // with the source, so keep the source rather than delete it
+    if left_behind == 0 {
+        delete_profile_credentials(&from_name);
+        delete_profile(&from_name)?;
+    }
+
+    println!(
+        "{} Merged '{}' into '{}'",
+        "✓".green(),
+        from_name,
+        into_name
+    );
+    for item in &merged {
+        println!("  • {}", item);
+    }
+    if left_behind == 0 {
+        println!("  • Removed profile '{}'", from_name);
+    } else {
+        eprintln!(
+            "{} Kept profile '{}': {} transcript file(s) already existed in '{}' and were not moved",
+            "⚠".yellow(),
+            from_name,
+            left_behind,
+            into_name
+        );
+    }
 
     Ok(())
 }
+
+/// Point the default/last-used/previous default profile and other profiles'
+/// fallbacks at `into` instead of `from`. Returns how many references changed.
+fn retarget_profile_references(from: &str, into: &str) -> Result<usize, RafctlError> {
+    let mut changed = 0;
+
+    let mut config = load_global_config()?;
+    for slot in [
+        &mut config.default_profile,
+        &mut config.last_used_profile,
+        &mut config.previous_default_profile,
+    ] {
+        if slot.as_deref() == Some(from) {
+            *slot = Some(into.to_string());
+            changed += 1;
+        }
+    }
+    // `switch -` back to the profile that is already the default does nothing
+    if config.previous_default_profile.is_some()
+        && config.previous_default_profile == config.default_profile
+    {
+        config.previous_default_profile = None;
+    }
+    if changed > 0 {
+        save_global_config(&config)?;
+    }
+
+    for name in list_profiles()? {
+        if name == from || name == into {
+            continue;
+        }
+        let Ok(mut profile) = load_profile(&name) else {
+            continue;
+        };
+        if profile.fallback_profile.as_deref() == Some(from) {
+            profile.fallback_profile = Some(into.to_string());
+            save_profile(&profile)?;
+            changed += 1;
+        }
+    }
+
+    Ok(changed)
+}
+
+#[derive(Serialize)]
+struct HistoryOutput {
+    profile: String,
+    entries: Vec<HistoryEntry>,
+}
+
+pub fn handle_history(name: &str, limit: usize, format: OutputFormat) -> Result<(), RafctlError> {
+    let resolved_name = resolve_profile_alias(name)?;
+    let name_lower = resolved_name.to_lowercase();
+    load_profile(&name_lower)?;
+
+    let entries = load_history(&name_lower, limit)?;
+
+    match format {
+        OutputFormat::Json => {
+            print_json(&HistoryOutput {
+                profile: name_lower,
+                entries,
+            })?;
+        }
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("STARTED\tEXIT\tARGS\tSNAPSHOT");
+            for entry in &entries {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    entry.started_at.format("%Y-%m-%d %H:%M:%S"),
+                    entry.exit_code,
+                    entry.args.join(" "),
+                    entry
+                        .snapshot
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|| "-".to_string())
+                );
+            }
+        }
+        OutputFormat::Human => {
+            if entries.is_empty() {
+                println!(
+                    "{} No launches recorded for '{}' yet",
+                    "ℹ".cyan(),
+                    name_lower
+                );
+                return Ok(());
+            }
+
+            println!("{}", format!("History: {}", name_lower).bold());
+            for entry in &entries {
+                let exit = if entry.exit_code == 0 {
+                    "exit 0".green()
+                } else {
+                    format!("exit {}", entry.exit_code).red()
+                };
+                let args = if entry.args.is_empty() {
+                    String::new()
+                } else {
+                    format!("  {}", entry.args.join(" "))
+                };
+                println!(
+                    "  {}  {}{}",
+                    entry.started_at.format("%Y-%m-%d %H:%M:%S"),
+                    exit,
+                    args.dimmed()
+                );
+                if let Some(snapshot) = &entry.snapshot {
+                    println!(
+                        "{}",
+                        format!("                       snapshot: {}", snapshot.display()).dimmed()
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ExportOutput {
+    profile: String,
+    file: String,
+    includes_secrets: bool,
+}
+
+pub fn handle_export(
+    name: &str,
+    output: Option<&Path>,
+    include_secrets: bool,
+    format: OutputFormat,
+) -> Result<(), RafctlError> {
+    let resolved_name = resolve_profile_alias(name)?;
+    let name_lower = resolved_name.to_lowercase();
+
+    let bundle = build_bundle(&name_lower, include_secrets)?;
+    let output_path = output
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(format!("{}.rafctl-profile.json", name_lower)));
+
+    let json = serde_json::to_string_pretty(&bundle).map_err(|e| RafctlError::ConfigWrite {
+        path: output_path.clone(),
+        source: std::io::Error::other(e),
+    })?;
+
+    // A bundle with secrets is never readable by others, not even briefly
+    let written = if bundle.secrets.is_some() {
+        create_private(&output_path).and_then(|mut file| file.write_all(json.as_bytes()))
+    } else {
+        fs::write(&output_path, json)
+    };
+    written.map_err(|e| RafctlError::ConfigWrite {
+        path: output_path.clone(),
+        source: e,
+    })?;
+
+    if format == OutputFormat::Json {
+        print_json(&ExportOutput {
+            profile: name_lower,
+            file: output_path.display().to_string(),
+            includes_secrets: bundle.secrets.is_some(),
+        })?;
+        return Ok(());
+    }
+
+    println!(
+        "{} Profile '{}' exported to {}",
+        "✓".green(),
+        name_lower,
+        output_path.display()
+    );
+
+    if bundle.secrets.is_some() {
+        println!(
+            "{} Bundle contains plaintext credentials — store it securely",
+            "⚠".yellow()
+        );
+    } else if include_secrets {
+        println!("{} No credentials found in keyring", "ℹ".cyan());
+    }
+
+    Ok(())
+}
+
+pub fn handle_import(file: &Path, force: bool, with_hooks: bool) -> Result<(), RafctlError> {
+    let content = fs::read_to_string(file).map_err(|e| RafctlError::ConfigRead {
+        path: file.to_path_buf(),
+        source: e,
+    })?;
+
+    let bundle: ProfileBundle =
+        serde_json::from_str(&content).map_err(|e| RafctlError::ConfigRead {
+            path: file.to_path_buf(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        })?;
+
+    let has_secrets = bundle.secrets.is_some();
+    let hooks: Vec<(&str, String)> = [
+        ("pre_run", bundle.profile.pre_run.clone()),
+        ("post_run", bundle.profile.post_run.clone()),
+    ]
+    .into_iter()
+    .filter_map(|(hook, cmd)| Some((hook, cmd?)))
+    .collect();
+    let profile = restore_bundle(bundle, force, with_hooks)?;
+
+    println!(
+        "{} Profile '{}' imported ({})",
+        "✓".green(),
+        profile.name,
+        profile.tool
+    );
+    if has_secrets {
+        println!("{} Credentials restored to secure storage", "ℹ".cyan());
+    }
+    for (hook, cmd) in &hooks {
+        if with_hooks {
+            println!("{} Installed {} hook: {}", "⚠".yellow(), hook, cmd);
+        } else {
+            println!("{} Skipped {} hook: {}", "ℹ".cyan(), hook, cmd);
+        }
+    }
+    if !hooks.is_empty() && !with_hooks {
+        println!(
+            "{}",
+            "Hooks run on every 'rafctl run'; re-import with --with-hooks to keep them".dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct NameValidationOutput {
+    name: String,
+    valid: bool,
+    reason: Option<String>,
+}
+
+/// Check a name against the `profile add` rules without creating anything.
+/// Returns whether the name is valid.
+pub fn handle_validate_name(name: &str, format: OutputFormat) -> Result<bool, RafctlError> {
+    let reason = validate_profile_name(name).err().map(|e| e.to_string());
+
+    match format {
+        OutputFormat::Json => print_json(&NameValidationOutput {
+            name: name.to_string(),
+            valid: reason.is_none(),
+            reason: reason.clone(),
+        })?,
+        _ => match &reason {
+            None => println!("{} '{}' is a valid profile name", "✓".green(), name),
+            Some(reason) => eprintln!("{} {}", "✗".red(), reason),
+        },
+    }
+
+    Ok(reason.is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_choice() {
+        let options = vec!["claude".to_string(), "codex".to_string()];
+        assert_eq!(parse_choice("\n", &options), Some(0));
+        assert_eq!(parse_choice("2", &options), Some(1));
+        assert_eq!(parse_choice(" Codex ", &options), Some(1));
+        assert_eq!(parse_choice("0", &options), None);
+        assert_eq!(parse_choice("3", &options), None);
+        assert_eq!(parse_choice("gemini", &options), None);
+    }
+}
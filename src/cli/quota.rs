@@ -1,12 +1,21 @@
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 
-#[cfg(target_os = "macos")]
-use std::time::Duration;
-
-use super::output::print_json;
+use super::output::{print_json, print_porcelain_header, PorcelainRecord};
 use super::OutputFormat;
-use crate::core::profile::{list_profiles, load_profile, profile_exists, AuthMode, ToolType};
+use crate::core::constants::{CACHE_DIR, QUOTA_CACHE_PREFIX, QUOTA_CACHE_TTL_SECS};
+use crate::core::profile::{
+    get_config_dir, list_profiles, load_profile, profile_exists, AuthMode, ToolType,
+};
 use crate::error::RafctlError;
 
 #[cfg(target_os = "macos")]
@@ -17,6 +26,15 @@ const ANTHROPIC_USAGE_API: &str = "https://api.anthropic.com/api/oauth/usage";
 #[cfg(target_os = "macos")]
 const API_TIMEOUT_SECS: u64 = 30;
 
+/// Usage API attempts, including the first
+const USAGE_API_ATTEMPTS: u32 = 3;
+/// Wait before the first retry; doubled for each further one
+const USAGE_API_RETRY_BASE: Duration = Duration::from_millis(500);
+/// Upper bound on any wait, including a server-sent `Retry-After`
+const USAGE_API_MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// Profiles fetched at once by `quota --all`
+const USAGE_FETCH_CONCURRENCY: usize = 4;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageWindow {
     pub utilization: f64,
@@ -27,6 +45,30 @@ pub struct UsageWindow {
 pub struct UsageLimits {
     pub five_hour: Option<UsageWindow>,
     pub seven_day: Option<UsageWindow>,
+    /// Opus-specific windows, only reported for some plans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub five_hour_opus: Option<UsageWindow>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seven_day_opus: Option<UsageWindow>,
+}
+
+impl UsageLimits {
+    /// Opus windows present in the response, with their display labels
+    fn opus_windows(&self) -> impl Iterator<Item = (&'static str, &UsageWindow)> {
+        [
+            ("Opus 5h", self.five_hour_opus.as_ref()),
+            ("Opus 7d", self.seven_day_opus.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(label, window)| window.map(|w| (label, w)))
+    }
+}
+
+/// Last successful usage response for a profile, kept under `~/.rafctl/cache`
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedUsage {
+    fetched_at: DateTime<Utc>,
+    usage: UsageLimits,
 }
 
 #[derive(Debug, Serialize)]
@@ -43,14 +85,15 @@ struct AllQuotaOutput {
     profiles: Vec<QuotaOutput>,
 }
 
-pub fn handle_quota(profile_name: Option<&str>, format: OutputFormat) -> Result<(), RafctlError> {
+/// Print quota usage. Returns whether every attempted quota fetch succeeded.
+pub fn handle_quota(profile_name: Option<&str>, format: OutputFormat) -> Result<bool, RafctlError> {
     match profile_name {
         Some(name) => show_single_quota(name, format),
         None => show_all_quota(format),
     }
 }
 
-fn show_single_quota(profile_name: &str, format: OutputFormat) -> Result<(), RafctlError> {
+fn show_single_quota(profile_name: &str, format: OutputFormat) -> Result<bool, RafctlError> {
     let name_lower = profile_name.to_lowercase();
 
     if !profile_exists(&name_lower)? {
@@ -68,7 +111,16 @@ fn show_single_quota(profile_name: &str, format: OutputFormat) -> Result<(), Raf
                     auth_mode: profile.auth_mode.to_string(),
                     usage: None,
                     error: Some("Quota monitoring only available for Claude profiles".to_string()),
-                });
+                })?;
+            }
+            OutputFormat::Porcelain => {
+                print_quota_porcelain(&[QuotaOutput {
+                    profile: name_lower.clone(),
+                    tool: profile.tool.to_string(),
+                    auth_mode: profile.auth_mode.to_string(),
+                    usage: None,
+                    error: Some("Quota monitoring only available for Claude profiles".to_string()),
+                }]);
             }
             _ => {
                 eprintln!(
@@ -77,7 +129,7 @@ fn show_single_quota(profile_name: &str, format: OutputFormat) -> Result<(), Raf
                 );
             }
         }
-        return Ok(());
+        return Ok(true);
     }
 
     if profile.auth_mode != AuthMode::OAuth {
@@ -89,7 +141,16 @@ fn show_single_quota(profile_name: &str, format: OutputFormat) -> Result<(), Raf
                     auth_mode: profile.auth_mode.to_string(),
                     usage: None,
                     error: Some("Quota monitoring only available for OAuth mode".to_string()),
-                });
+                })?;
+            }
+            OutputFormat::Porcelain => {
+                print_quota_porcelain(&[QuotaOutput {
+                    profile: name_lower.clone(),
+                    tool: profile.tool.to_string(),
+                    auth_mode: profile.auth_mode.to_string(),
+                    usage: None,
+                    error: Some("Quota monitoring only available for OAuth mode".to_string()),
+                }]);
             }
             _ => {
                 eprintln!(
@@ -98,10 +159,11 @@ fn show_single_quota(profile_name: &str, format: OutputFormat) -> Result<(), Raf
                 );
             }
         }
-        return Ok(());
+        return Ok(true);
     }
 
-    let usage = fetch_usage_for_profile(&name_lower);
+    let usage = fetch_and_cache_usage(&name_lower);
+    let fetched = usage.is_ok();
 
     match format {
         OutputFormat::Json => {
@@ -116,7 +178,20 @@ fn show_single_quota(profile_name: &str, format: OutputFormat) -> Result<(), Raf
                 usage: usage_data,
                 error: error_msg,
             };
-            print_json(&output);
+            print_json(&output)?;
+        }
+        OutputFormat::Porcelain => {
+            let (usage_data, error_msg) = match &usage {
+                Ok(u) => (Some(u.clone()), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            print_quota_porcelain(&[QuotaOutput {
+                profile: name_lower.clone(),
+                tool: profile.tool.to_string(),
+                auth_mode: profile.auth_mode.to_string(),
+                usage: usage_data,
+                error: error_msg,
+            }]);
         }
         OutputFormat::Plain => {
             print_usage_plain(&name_lower, &usage);
@@ -126,50 +201,56 @@ fn show_single_quota(profile_name: &str, format: OutputFormat) -> Result<(), Raf
         }
     }
 
-    Ok(())
+    Ok(fetched)
 }
 
-fn show_all_quota(format: OutputFormat) -> Result<(), RafctlError> {
+fn show_all_quota(format: OutputFormat) -> Result<bool, RafctlError> {
     let profiles = list_profiles()?;
 
     if profiles.is_empty() {
         match format {
             OutputFormat::Json => {
-                print_json(&AllQuotaOutput { profiles: vec![] });
+                print_json(&AllQuotaOutput { profiles: vec![] })?;
             }
+            OutputFormat::Porcelain => print_quota_porcelain(&[]),
             _ => {
                 println!("No profiles found.");
             }
         }
-        return Ok(());
+        return Ok(true);
     }
 
-    let mut outputs: Vec<QuotaOutput> = Vec::new();
-
-    for name in &profiles {
-        if let Ok(profile) = load_profile(name) {
-            if profile.tool == ToolType::Claude && profile.auth_mode == AuthMode::OAuth {
-                let usage = fetch_usage_for_profile(name);
-                let (usage_data, error_msg) = match usage {
-                    Ok(u) => (Some(u), None),
-                    Err(e) => (None, Some(e.to_string())),
-                };
-                outputs.push(QuotaOutput {
-                    profile: name.clone(),
-                    tool: profile.tool.to_string(),
-                    auth_mode: profile.auth_mode.to_string(),
-                    usage: usage_data,
-                    error: error_msg,
-                });
+    let oauth_profiles: Vec<(&String, _)> = profiles
+        .iter()
+        .filter_map(|name| load_profile(name).ok().map(|profile| (name, profile)))
+        .filter(|(_, p)| p.tool == ToolType::Claude && p.auth_mode == AuthMode::OAuth)
+        .collect();
+
+    // Each fetch can wait on the network for a while, so run a few at once
+    let outputs: Vec<QuotaOutput> = parallel_map(
+        &oauth_profiles,
+        USAGE_FETCH_CONCURRENCY,
+        |(name, profile)| {
+            let (usage_data, error_msg) = match fetch_and_cache_usage(name) {
+                Ok(u) => (Some(u), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            QuotaOutput {
+                profile: (*name).clone(),
+                tool: profile.tool.to_string(),
+                auth_mode: profile.auth_mode.to_string(),
+                usage: usage_data,
+                error: error_msg,
             }
-        }
-    }
+        },
+    );
 
     if outputs.is_empty() {
         match format {
             OutputFormat::Json => {
-                print_json(&AllQuotaOutput { profiles: vec![] });
+                print_json(&AllQuotaOutput { profiles: vec![] })?;
             }
+            OutputFormat::Porcelain => print_quota_porcelain(&[]),
             _ => {
                 println!(
                     "{} No Claude OAuth profiles found for quota monitoring",
@@ -177,44 +258,45 @@ fn show_all_quota(format: OutputFormat) -> Result<(), RafctlError> {
                 );
             }
         }
-        return Ok(());
+        return Ok(true);
     }
 
+    let all_fetched = outputs.iter().all(|o| o.error.is_none());
+
     match format {
         OutputFormat::Json => {
-            print_json(&AllQuotaOutput { profiles: outputs });
+            print_json(&AllQuotaOutput { profiles: outputs })?;
         }
+        OutputFormat::Porcelain => print_quota_porcelain(&outputs),
         OutputFormat::Plain => {
-            println!("PROFILE\t5H_USAGE\t5H_RESET\t7D_USAGE\t7D_RESET");
+            println!(
+                "PROFILE\t5H_USAGE\t5H_RESET\t7D_USAGE\t7D_RESET\tOPUS_5H_USAGE\tOPUS_7D_USAGE"
+            );
             for o in &outputs {
                 if let Some(usage) = &o.usage {
-                    let five_h = usage
-                        .five_hour
-                        .as_ref()
-                        .map(|w| format!("{:.1}%", w.utilization))
-                        .unwrap_or_else(|| "-".to_string());
-                    let five_h_reset = usage
-                        .five_hour
-                        .as_ref()
-                        .and_then(|w| w.resets_at.clone())
-                        .unwrap_or_else(|| "-".to_string());
-                    let seven_d = usage
-                        .seven_day
-                        .as_ref()
-                        .map(|w| format!("{:.1}%", w.utilization))
-                        .unwrap_or_else(|| "-".to_string());
-                    let seven_d_reset = usage
-                        .seven_day
-                        .as_ref()
-                        .and_then(|w| w.resets_at.clone())
-                        .unwrap_or_else(|| "-".to_string());
+                    let pct = |w: &Option<UsageWindow>| {
+                        w.as_ref()
+                            .map(|w| format!("{:.1}%", w.utilization))
+                            .unwrap_or_else(|| "-".to_string())
+                    };
+                    let reset = |w: &Option<UsageWindow>| {
+                        w.as_ref()
+                            .and_then(|w| w.resets_at.clone())
+                            .unwrap_or_else(|| "-".to_string())
+                    };
                     println!(
-                        "{}\t{}\t{}\t{}\t{}",
-                        o.profile, five_h, five_h_reset, seven_d, seven_d_reset
+                        "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                        o.profile,
+                        pct(&usage.five_hour),
+                        reset(&usage.five_hour),
+                        pct(&usage.seven_day),
+                        reset(&usage.seven_day),
+                        pct(&usage.five_hour_opus),
+                        pct(&usage.seven_day_opus)
                     );
                 } else {
                     println!(
-                        "{}\t{}\t-\t-\t-",
+                        "{}\t{}\t-\t-\t-\t-\t-",
                         o.profile,
                         o.error.as_deref().unwrap_or("error")
                     );
@@ -239,7 +321,274 @@ fn show_all_quota(format: OutputFormat) -> Result<(), RafctlError> {
         }
     }
 
-    Ok(())
+    Ok(all_fetched)
+}
+
+fn print_quota_porcelain(outputs: &[QuotaOutput]) {
+    print_porcelain_header();
+    for o in outputs {
+        let window = |w: Option<&UsageWindow>| w.map(|w| format!("{:.1}", w.utilization));
+        let resets = |w: Option<&UsageWindow>| w.and_then(|w| w.resets_at.clone());
+        let usage = o.usage.as_ref();
+        PorcelainRecord::new("quota")
+            .field("profile", &o.profile)
+            .field("tool", &o.tool)
+            .field("auth_mode", &o.auth_mode)
+            .opt_field(
+                "five_hour",
+                window(usage.and_then(|u| u.five_hour.as_ref())),
+            )
+            .opt_field(
+                "five_hour_resets_at",
+                resets(usage.and_then(|u| u.five_hour.as_ref())),
+            )
+            .opt_field(
+                "seven_day",
+                window(usage.and_then(|u| u.seven_day.as_ref())),
+            )
+            .opt_field(
+                "seven_day_resets_at",
+                resets(usage.and_then(|u| u.seven_day.as_ref())),
+            )
+            .opt_field(
+                "five_hour_opus",
+                window(usage.and_then(|u| u.five_hour_opus.as_ref())),
+            )
+            .opt_field(
+                "seven_day_opus",
+                window(usage.and_then(|u| u.seven_day_opus.as_ref())),
+            )
+            .opt_field("error", o.error.as_ref())
+            .print();
+    }
+}
+
+/// Return usage for a profile, using the cached response if it is still fresh
+pub fn cached_usage_for_profile(profile_name: &str) -> Result<UsageLimits, RafctlError> {
+    usage_within(profile_name, QUOTA_CACHE_TTL_SECS)
+}
+
+/// Cached usage if it is younger than `max_age_secs`, otherwise a fresh fetch
+fn usage_within(profile_name: &str, max_age_secs: i64) -> Result<UsageLimits, RafctlError> {
+    if let Some(cached) = load_cached_usage(profile_name) {
+        let age = Utc::now() - cached.fetched_at;
+        if age.num_seconds() < max_age_secs {
+            return Ok(cached.usage);
+        }
+    }
+
+    fetch_and_cache_usage(profile_name)
+}
+
+/// Redraw quota usage every `interval` until interrupted. A failed refresh
+/// keeps showing the last good value (from the quota cache), marked stale.
+pub fn handle_quota_watch(
+    profile_name: Option<&str>,
+    interval: Duration,
+    format: OutputFormat,
+) -> Result<(), RafctlError> {
+    let profiles = match profile_name {
+        Some(name) => {
+            let name_lower = name.to_lowercase();
+            if !profile_exists(&name_lower)? {
+                return Err(RafctlError::ProfileNotFound(name_lower));
+            }
+            vec![name_lower]
+        }
+        None => list_profiles()?,
+    };
+    let profiles: Vec<String> = profiles
+        .into_iter()
+        .filter(|name| {
+            load_profile(name)
+                .is_ok_and(|p| p.tool == ToolType::Claude && p.auth_mode == AuthMode::OAuth)
+        })
+        .collect();
+
+    if profiles.is_empty() {
+        println!(
+            "{} No Claude OAuth profiles found for quota monitoring",
+            "ℹ".cyan()
+        );
+        return Ok(());
+    }
+
+    // Redraw in place only for a human watching a terminal; otherwise append
+    let redraw = format == OutputFormat::Human && io::stdout().is_terminal();
+    let max_age = interval.as_secs().max(1) as i64;
+
+    loop {
+        if redraw {
+            print!("\x1b[H\x1b[2J");
+        }
+
+        match format {
+            OutputFormat::Json | OutputFormat::Porcelain => {
+                let outputs: Vec<QuotaOutput> = profiles
+                    .iter()
+                    .map(|name| {
+                        let (usage, error) = match usage_within(name, max_age) {
+                            Ok(u) => (Some(u), None),
+                            Err(e) => (
+                                load_cached_usage(name).map(|c| c.usage),
+                                Some(e.to_string()),
+                            ),
+                        };
+                        QuotaOutput {
+                            profile: name.clone(),
+                            tool: ToolType::Claude.to_string(),
+                            auth_mode: AuthMode::OAuth.to_string(),
+                            usage,
+                            error,
+                        }
+                    })
+                    .collect();
+                if format == OutputFormat::Json {
+                    print_json(&AllQuotaOutput { profiles: outputs })?;
+                } else {
+                    print_quota_porcelain(&outputs);
+                }
+            }
+            OutputFormat::Plain => {
+                for name in &profiles {
+                    match usage_within(name, max_age) {
+                        Ok(u) => print_usage_plain(name, &Ok(u)),
+                        Err(e) => match load_cached_usage(name) {
+                            Some(cached) => {
+                                print_usage_plain(name, &Ok(cached.usage));
+                                println!("{}: stale={}", name, staleness(cached.fetched_at));
+                            }
+                            None => print_usage_plain(name, &Err(e)),
+                        },
+                    }
+                }
+            }
+            OutputFormat::Human => {
+                println!(
+                    "{} {}",
+                    "Quota Usage".bold(),
+                    format!(
+                        "(every {}s, updated {}, Ctrl+C to stop)",
+                        interval.as_secs(),
+                        chrono::Local::now().format("%H:%M:%S")
+                    )
+                    .dimmed()
+                );
+                for name in &profiles {
+                    match usage_within(name, max_age) {
+                        Ok(u) => print_usage_human_data(name, &u),
+                        Err(e) => match load_cached_usage(name) {
+                            Some(cached) => {
+                                print_usage_human_data(name, &cached.usage);
+                                println!(
+                                    "    {} {} ({})",
+                                    "⚠".yellow(),
+                                    format!(
+                                        "stale, last updated {} ago",
+                                        staleness(cached.fetched_at)
+                                    )
+                                    .yellow(),
+                                    e.to_string().dimmed()
+                                );
+                            }
+                            None => print_usage_human(name, &Err(e)),
+                        },
+                    }
+                }
+            }
+        }
+        let _ = io::stdout().flush();
+
+        std::thread::sleep(interval);
+    }
+}
+
+/// Age of a cached value, e.g. `45s`, `12m` or `3h`
+fn staleness(fetched_at: DateTime<Utc>) -> String {
+    format_age((Utc::now() - fetched_at).num_seconds())
+}
+
+fn format_age(secs: i64) -> String {
+    let secs = secs.max(0);
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h", secs / 3600)
+    }
+}
+
+/// Apply `f` to every item on at most `workers` threads, keeping input order.
+fn parallel_map<T, R, F>(items: &[T], workers: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = f(item);
+                results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn fetch_and_cache_usage(profile_name: &str) -> Result<UsageLimits, RafctlError> {
+    let usage = fetch_usage_for_profile(profile_name)?;
+    if let Err(e) = save_cached_usage(profile_name, &usage) {
+        crate::cli::debug::debug_labeled("quota cache", &e.to_string());
+    }
+    Ok(usage)
+}
+
+fn quota_cache_path(profile_name: &str) -> Result<PathBuf, RafctlError> {
+    Ok(get_config_dir()?
+        .join(CACHE_DIR)
+        .join(format!("{}{}.json", QUOTA_CACHE_PREFIX, profile_name)))
+}
+
+fn load_cached_usage(profile_name: &str) -> Option<CachedUsage> {
+    let path = quota_cache_path(profile_name).ok()?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_cached_usage(profile_name: &str, usage: &UsageLimits) -> Result<(), RafctlError> {
+    let path = quota_cache_path(profile_name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| RafctlError::ConfigWrite {
+            path: parent.to_path_buf(),
+            source: e,
+        })?;
+    }
+
+    let cached = CachedUsage {
+        fetched_at: Utc::now(),
+        usage: usage.clone(),
+    };
+    let content = serde_json::to_string(&cached).map_err(|e| RafctlError::ConfigWrite {
+        path: path.clone(),
+        source: std::io::Error::other(e),
+    })?;
+
+    fs::write(&path, content).map_err(|e| RafctlError::ConfigWrite { path, source: e })
 }
 
 #[cfg(target_os = "macos")]
@@ -247,7 +596,7 @@ fn fetch_usage_for_profile(profile_name: &str) -> Result<UsageLimits, RafctlErro
     let token = keychain::read_oauth_token(profile_name)?
         .ok_or_else(|| RafctlError::NotAuthenticated(profile_name.to_string()))?;
 
-    fetch_usage_from_api(&token)
+    fetch_usage_from_api(profile_name, &token)
 }
 
 #[cfg(not(target_os = "macos"))]
@@ -258,23 +607,62 @@ fn fetch_usage_for_profile(_profile_name: &str) -> Result<UsageLimits, RafctlErr
 }
 
 #[cfg(target_os = "macos")]
-fn fetch_usage_from_api(token: &str) -> Result<UsageLimits, RafctlError> {
+fn fetch_usage_from_api(profile_name: &str, token: &str) -> Result<UsageLimits, RafctlError> {
     let agent = ureq::AgentBuilder::new()
         .timeout(Duration::from_secs(API_TIMEOUT_SECS))
         .build();
 
-    let response = agent
-        .get(ANTHROPIC_USAGE_API)
-        .set("Accept", "application/json")
-        .set("Content-Type", "application/json")
-        .set(
-            "User-Agent",
-            &format!("rafctl/{}", env!("CARGO_PKG_VERSION")),
-        )
-        .set("Authorization", &format!("Bearer {}", token))
-        .set("anthropic-beta", "oauth-2025-04-20")
-        .call()
-        .map_err(|e| RafctlError::KeychainError(format!("API request failed: {}", e)))?;
+    let mut attempt = 1;
+    let response = loop {
+        let result = agent
+            .get(ANTHROPIC_USAGE_API)
+            .set("Accept", "application/json")
+            .set("Content-Type", "application/json")
+            .set(
+                "User-Agent",
+                &format!("rafctl/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .set("Authorization", &format!("Bearer {}", token))
+            .set("anthropic-beta", "oauth-2025-04-20")
+            .call();
+
+        let err = match result {
+            Ok(response) => break response,
+            Err(e) => e,
+        };
+        let (status, retry_after) = match &err {
+            ureq::Error::Status(code, response) => (Some(*code), response.header("Retry-After")),
+            ureq::Error::Transport(_) => (None, None),
+        };
+
+        match status {
+            Some(401) => return Err(RafctlError::NotAuthenticated(profile_name.to_string())),
+            Some(403) => {
+                return Err(RafctlError::KeychainError(format!(
+                    "API rejected the token: {}",
+                    err
+                )))
+            }
+            _ => {}
+        }
+
+        match retry_delay(attempt, status, retry_after) {
+            Some(delay) => {
+                crate::cli::debug::debug(&format!(
+                    "usage API attempt {} failed ({}), retrying in {:?}",
+                    attempt, err, delay
+                ));
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            None => {
+                return Err(RafctlError::KeychainError(format!(
+                    "API request failed: {}",
+                    err
+                )))
+            }
+        }
+    };
 
     let usage: UsageLimits = response
         .into_json()
@@ -283,6 +671,27 @@ fn fetch_usage_from_api(token: &str) -> Result<UsageLimits, RafctlError> {
     Ok(usage)
 }
 
+/// How long to wait before retrying a failed usage API call, or `None` when
+/// giving up. Only connection errors (`status` is `None`), 429 and 5xx are
+/// retried; a numeric `Retry-After` replaces the exponential backoff.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn retry_delay(attempt: u32, status: Option<u16>, retry_after: Option<&str>) -> Option<Duration> {
+    if attempt >= USAGE_API_ATTEMPTS {
+        return None;
+    }
+    if let Some(code) = status {
+        if code != 429 && !(500..600).contains(&code) {
+            return None;
+        }
+    }
+
+    let delay = retry_after
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(USAGE_API_RETRY_BASE * 2u32.pow(attempt - 1));
+    Some(delay.min(USAGE_API_MAX_RETRY_DELAY))
+}
+
 fn print_usage_human(profile_name: &str, usage: &Result<UsageLimits, RafctlError>) {
     println!("  {} {}", "•".cyan(), profile_name.white().bold());
 
@@ -301,49 +710,55 @@ fn print_usage_human_data(profile_name: &str, usage: &UsageLimits) {
 
 fn print_usage_data(u: &UsageLimits) {
     if let Some(five_h) = &u.five_hour {
-        let bar = usage_bar(five_h.utilization);
-        let reset = five_h
-            .resets_at
-            .as_ref()
-            .map(|r| format_reset_time(r))
-            .unwrap_or_default();
-        println!(
-            "    5-hour:  {} {:.1}% {}",
-            bar,
-            five_h.utilization,
-            reset.dimmed()
-        );
+        print_window("5-hour:", five_h);
     }
     if let Some(seven_d) = &u.seven_day {
-        let bar = usage_bar(seven_d.utilization);
-        let reset = seven_d
-            .resets_at
-            .as_ref()
-            .map(|r| format_reset_time(r))
-            .unwrap_or_default();
-        println!(
-            "    7-day:   {} {:.1}% {}",
-            bar,
-            seven_d.utilization,
-            reset.dimmed()
-        );
+        print_window("7-day:", seven_d);
+    }
+    for (label, window) in u.opus_windows() {
+        print_window(&format!("{}:", label), window);
     }
 }
 
+fn print_window(label: &str, window: &UsageWindow) {
+    let reset = window
+        .resets_at
+        .as_ref()
+        .map(|r| format_reset_time(r))
+        .unwrap_or_default();
+    println!(
+        "    {:<8} {} {:.1}% {}",
+        label,
+        usage_bar(window.utilization),
+        window.utilization,
+        reset.dimmed()
+    );
+}
+
+/// Utilization formatted for plain output, `-` when the window is absent
+fn utilization(window: &Option<UsageWindow>) -> String {
+    window
+        .as_ref()
+        .map(|w| format!("{:.1}", w.utilization))
+        .unwrap_or_else(|| "-".to_string())
+}
+
 fn print_usage_plain(profile_name: &str, usage: &Result<UsageLimits, RafctlError>) {
     match usage {
         Ok(u) => {
-            let five_h = u
-                .five_hour
-                .as_ref()
-                .map(|w| format!("{:.1}", w.utilization))
-                .unwrap_or_else(|| "-".to_string());
-            let seven_d = u
-                .seven_day
-                .as_ref()
-                .map(|w| format!("{:.1}", w.utilization))
-                .unwrap_or_else(|| "-".to_string());
-            println!("{}: 5h={}% 7d={}%", profile_name, five_h, seven_d);
+            let mut line = format!(
+                "{}: 5h={}% 7d={}%",
+                profile_name,
+                utilization(&u.five_hour),
+                utilization(&u.seven_day)
+            );
+            if u.five_hour_opus.is_some() {
+                line.push_str(&format!(" opus_5h={}%", utilization(&u.five_hour_opus)));
+            }
+            if u.seven_day_opus.is_some() {
+                line.push_str(&format!(" opus_7d={}%", utilization(&u.seven_day_opus)));
+            }
+            println!("{}", line);
         }
         Err(e) => {
             println!("{}: error={}", profile_name, e);
@@ -366,7 +781,7 @@ fn usage_bar(percentage: f64) -> String {
     }
 }
 
-fn format_reset_time(iso_time: &str) -> String {
+pub fn format_reset_time(iso_time: &str) -> String {
     if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(iso_time) {
         let now = chrono::Utc::now();
         let duration = dt.signed_duration_since(now);
@@ -387,6 +802,47 @@ fn format_reset_time(iso_time: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parallel_map_keeps_order_and_bounds_workers() {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let items: Vec<u64> = (0..12).collect();
+
+        let results = parallel_map(&items, 3, |&n| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            // Later items finish first, so order must come from the index
+            thread::sleep(Duration::from_millis(12 - n));
+            running.fetch_sub(1, Ordering::SeqCst);
+            n * 10
+        });
+
+        assert_eq!(results, items.iter().map(|n| n * 10).collect::<Vec<_>>());
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert!(parallel_map(&Vec::<u64>::new(), 3, |&n| n).is_empty());
+    }
+
+    #[test]
+    fn test_retry_delay_only_for_transient_failures() {
+        // Connection errors, rate limits and server errors back off exponentially
+        assert_eq!(retry_delay(1, None, None), Some(Duration::from_millis(500)));
+        assert_eq!(
+            retry_delay(2, Some(503), None),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            retry_delay(1, Some(429), Some("7")),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            retry_delay(1, Some(429), Some("3600")),
+            Some(USAGE_API_MAX_RETRY_DELAY)
+        );
+        // Other client errors fail at once, and the last attempt never retries
+        assert_eq!(retry_delay(1, Some(400), None), None);
+        assert_eq!(retry_delay(USAGE_API_ATTEMPTS, None, None), None);
+    }
+
     #[test]
     fn test_usage_bar_low() {
         let bar = usage_bar(25.0);
@@ -399,6 +855,47 @@ mod tests {
         assert!(bar.contains("████████"));
     }
 
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(-5), "0s");
+        assert_eq!(format_age(45), "45s");
+        assert_eq!(format_age(600), "10m");
+        assert_eq!(format_age(7300), "2h");
+    }
+
+    #[test]
+    fn test_cached_usage_roundtrip() {
+        let json = r#"{
+            "fetched_at": "2026-01-06T10:00:00Z",
+            "usage": {
+                "five_hour": {"utilization": 42.5, "resets_at": null},
+                "seven_day": null
+            }
+        }"#;
+        let cached: CachedUsage = serde_json::from_str(json).unwrap();
+        assert_eq!(cached.usage.five_hour.as_ref().unwrap().utilization, 42.5);
+        assert!(cached.usage.seven_day.is_none());
+    }
+
+    #[test]
+    fn test_usage_limits_opus_windows() {
+        let json = r#"{
+            "five_hour": {"utilization": 10.0, "resets_at": null},
+            "seven_day": {"utilization": 20.0, "resets_at": null},
+            "seven_day_opus": {"utilization": 55.0, "resets_at": "2026-01-10T03:00:00Z"}
+        }"#;
+        let usage: UsageLimits = serde_json::from_str(json).unwrap();
+        let labels: Vec<_> = usage.opus_windows().map(|(label, _)| label).collect();
+
+        assert_eq!(labels, vec!["Opus 7d"]);
+        assert_eq!(utilization(&usage.seven_day_opus), "55.0");
+        assert_eq!(utilization(&usage.five_hour_opus), "-");
+
+        let serialized = serde_json::to_value(&usage).unwrap();
+        assert!(serialized.get("five_hour_opus").is_none());
+        assert_eq!(serialized["seven_day_opus"]["utilization"], 55.0);
+    }
+
     #[test]
     fn test_format_reset_time_invalid() {
         let result = format_reset_time("invalid");
@@ -0,0 +1,210 @@
+//! Step-through TUI for replaying a past session event by event.
+
+use std::io;
+use std::time::Duration;
+
+use chrono::Local;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Gauge, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::core::transcript::{SessionEvent, SessionEventKind};
+use crate::error::RafctlError;
+
+struct ReplayApp {
+    session_id: String,
+    events: Vec<SessionEvent>,
+    position: usize,
+    scroll: u16,
+    should_quit: bool,
+}
+
+impl ReplayApp {
+    fn new(session_id: &str, events: Vec<SessionEvent>) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            events,
+            position: 0,
+            scroll: 0,
+            should_quit: false,
+        }
+    }
+
+    fn go_to(&mut self, position: usize) {
+        let last = self.events.len().saturating_sub(1);
+        self.position = position.min(last);
+        self.scroll = 0;
+    }
+
+    fn next(&mut self) {
+        self.go_to(self.position + 1);
+    }
+
+    fn previous(&mut self) {
+        self.go_to(self.position.saturating_sub(1));
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        if let Event::Key(key) = event {
+            if key.kind != KeyEventKind::Press {
+                return;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+                KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => self.next(),
+                KeyCode::Left | KeyCode::Char('h') => self.previous(),
+                KeyCode::Home | KeyCode::Char('g') => self.go_to(0),
+                KeyCode::End | KeyCode::Char('G') => self.go_to(usize::MAX),
+                KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+                KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+}
+
+pub fn run_replay(session_id: &str, events: Vec<SessionEvent>) -> Result<(), RafctlError> {
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, ReplayApp::new(session_id, events));
+    ratatui::restore();
+    result
+}
+
+fn run_app(terminal: &mut DefaultTerminal, mut app: ReplayApp) -> Result<(), RafctlError> {
+    loop {
+        terminal
+            .draw(|frame| render(frame, &app))
+            .map_err(|e| RafctlError::ConfigWrite {
+                path: std::path::PathBuf::from("terminal"),
+                source: io::Error::other(e),
+            })?;
+
+        if event::poll(Duration::from_millis(100)).map_err(|e| RafctlError::ConfigRead {
+            path: std::path::PathBuf::from("events"),
+            source: io::Error::other(e),
+        })? {
+            let event = event::read().map_err(|e| RafctlError::ConfigRead {
+                path: std::path::PathBuf::from("events"),
+                source: io::Error::other(e),
+            })?;
+            app.handle_event(event);
+        }
+
+        if app.should_quit {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn render(frame: &mut Frame, app: &ReplayApp) {
+    let [header_area, progress_area, event_area, help_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(1),
+        Constraint::Fill(1),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+
+    render_header(frame, app, header_area);
+    render_progress(frame, app, progress_area);
+    render_event(frame, app, event_area);
+    render_help(frame, help_area);
+}
+
+fn render_header(frame: &mut Frame, app: &ReplayApp, area: ratatui::layout::Rect) {
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(
+            "rafctl ",
+            Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("replay  "),
+        Span::styled(app.session_id.clone(), Style::new().fg(Color::DarkGray)),
+    ]))
+    .block(Block::bordered().title("Session Replay"));
+
+    frame.render_widget(header, area);
+}
+
+fn render_progress(frame: &mut Frame, app: &ReplayApp, area: ratatui::layout::Rect) {
+    let total = app.events.len().max(1);
+    let step = app.position + 1;
+    let gauge = Gauge::default()
+        .gauge_style(Style::new().fg(Color::Cyan))
+        .ratio(step as f64 / total as f64)
+        .label(format!("{} / {}", step, total));
+
+    frame.render_widget(gauge, area);
+}
+
+fn render_event(frame: &mut Frame, app: &ReplayApp, area: ratatui::layout::Rect) {
+    let Some(event) = app.events.get(app.position) else {
+        return;
+    };
+
+    let (label, color) = event_label(event);
+    let time = event
+        .timestamp
+        .map(|t| t.with_timezone(&Local).format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| "--:--:--".to_string());
+
+    let title = Line::from(vec![
+        Span::styled(
+            format!(" {} ", label),
+            Style::new().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!("{} ", time), Style::new().fg(Color::DarkGray)),
+    ]);
+
+    let body = if event.content.is_empty() {
+        "(empty)".to_string()
+    } else {
+        event.content.clone()
+    };
+
+    let paragraph = Paragraph::new(body)
+        .block(Block::bordered().title(title))
+        .wrap(Wrap { trim: false })
+        .scroll((app.scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+fn event_label(event: &SessionEvent) -> (String, Color) {
+    match &event.kind {
+        SessionEventKind::UserMessage => ("User".to_string(), Color::Green),
+        SessionEventKind::AssistantMessage => ("Assistant".to_string(), Color::Cyan),
+        SessionEventKind::ToolCall(name) => (format!("Tool call: {}", name), Color::Yellow),
+        SessionEventKind::ToolResult(name) => {
+            let name = name.as_deref().unwrap_or("unknown");
+            if event.is_error {
+                (format!("Tool error: {}", name), Color::Red)
+            } else {
+                (format!("Tool result: {}", name), Color::Magenta)
+            }
+        }
+    }
+}
+
+fn render_help(frame: &mut Frame, area: ratatui::layout::Rect) {
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("←/h", Style::new().fg(Color::Cyan)),
+        Span::raw(" prev  "),
+        Span::styled("→/l/Space", Style::new().fg(Color::Cyan)),
+        Span::raw(" next  "),
+        Span::styled("g/G", Style::new().fg(Color::Cyan)),
+        Span::raw(" first/last  "),
+        Span::styled("↑/↓", Style::new().fg(Color::Cyan)),
+        Span::raw(" scroll  "),
+        Span::styled("q/Esc", Style::new().fg(Color::Cyan)),
+        Span::raw(" quit"),
+    ]))
+    .block(Block::bordered());
+
+    frame.render_widget(help, area);
+}
@@ -1,27 +1,77 @@
 use std::collections::HashMap;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
 
+use crate::cli::dashboard::pick_profile;
 use crate::cli::debug;
-use crate::core::config::{get_default_profile, set_last_used_profile};
+use crate::cli::quota::{cached_usage_for_profile, format_reset_time, UsageWindow};
+use crate::cli::sessions::print_session_detail;
+use crate::cli::OutputFormat;
+use crate::cli::QuotaExhaustedAction;
+use crate::core::config::{get_default_profile, get_last_used_profile, set_last_used_profile};
 use crate::core::constants::{
-    ENV_ANTHROPIC_API_KEY, ENV_RAFCTL_PROFILE, ENV_RAFCTL_PROFILE_TOOL, ENV_RAFCTL_VERSION, VERSION,
+    ENV_ANTHROPIC_API_KEY, ENV_RAFCTL_AUTH_MODE, ENV_RAFCTL_CONFIG_DIR, ENV_RAFCTL_EXIT_CODE,
+    ENV_RAFCTL_PROFILE, ENV_RAFCTL_PROFILE_DIR, ENV_RAFCTL_PROFILE_TOOL, ENV_RAFCTL_VERSION,
+    IDLE_TIMEOUT_EXIT_CODE, QUOTA_EXHAUSTED_THRESHOLD, QUOTA_RATE_LIMIT_THRESHOLD, VERSION,
 };
 use crate::core::credentials::{self, CredentialType};
+use crate::core::env_file::load_env_file;
+use crate::core::history::{append_history, HistoryEntry};
 #[cfg(target_os = "macos")]
-use crate::core::profile::get_config_dir;
+use crate::core::lock::{acquire_lock, lock_holder, OAuthLock};
+use crate::core::lock::{inspect_lock, oauth_lock_path, LockState};
+use crate::core::pricing::{load_pricing, PricingTable};
 use crate::core::profile::{
-    list_profiles, load_profile, profile_exists, resolve_profile_alias, save_profile, AuthMode,
-    Profile, ToolType,
+    config_dir_override, get_profile_dir, list_profiles, load_profile, profile_exists,
+    resolve_profile_alias, save_profile, AuthMode, ConfigMode, Profile, ToolType,
+};
+use crate::core::snapshot::snapshot_config;
+use crate::core::stats::{append_cost_entry, CostEntry};
+use crate::core::transcript::{
+    newest_session, newest_session_since, parse_transcript, transcripts_dir_for,
 };
 use crate::error::RafctlError;
 use crate::tools::{check_tool_available, is_authenticated};
 
-pub fn handle_run(profile_name: Option<&str>, args: &[String]) -> Result<i32, RafctlError> {
-    let name = resolve_profile_name(profile_name)?;
+/// How often `--timeout-idle` checks the transcript for growth
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Optional behaviors for `rafctl run`
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Pre-launch quota check for OAuth profiles (skipped when `None`)
+    pub on_quota_exhausted: Option<QuotaExhaustedAction>,
+    /// Abort before launching if an OAuth profile is at its 5-hour limit
+    pub pre_check_quota: bool,
+    /// Stop the tool once its session transcript has not grown for this long
+    pub timeout_idle: Option<Duration>,
+    /// Launch an OAuth profile even if another OAuth instance holds the lock
+    pub force: bool,
+    /// Print a recap of the session transcript written during the run
+    pub post_summary: bool,
+    /// Copy the profile's config into `~/.rafctl/snapshots` before launching
+    pub snapshot_config: bool,
+    /// `KEY=VALUE` file whose variables are passed to the tool
+    pub env_file: Option<PathBuf>,
+    /// Directory the tool and hooks are launched in (defaults to the current one)
+    pub cwd: Option<PathBuf>,
+    /// Append the finished session's cost to the profile's `costs.jsonl`
+    pub capture_cost: bool,
+    /// Choose the profile interactively instead of using the default
+    pub pick: bool,
+}
+
+pub fn handle_run(
+    profile_name: Option<&str>,
+    args: &[String],
+    options: &RunOptions,
+) -> Result<i32, RafctlError> {
+    let name = resolve_profile_name(profile_name, options.pick)?;
     let name_lower = name.to_lowercase();
 
     debug::debug_labeled("profile", &name_lower);
@@ -34,35 +84,230 @@ pub fn handle_run(profile_name: Option<&str>, args: &[String]) -> Result<i32, Ra
     debug::debug_labeled("tool", &profile.tool.to_string());
     debug::debug_labeled("auth_mode", &profile.auth_mode.to_string());
 
+    // Activity is read from Claude Code's session transcripts; other tools
+    // never write there and would be stopped however busy they are
+    if options.timeout_idle.is_some() && profile.tool != ToolType::Claude {
+        return Err(RafctlError::UnsupportedForTool {
+            option: "--timeout-idle",
+            supported: "claude",
+            profile: profile.name.clone(),
+            tool: profile.tool.to_string(),
+        });
+    }
+
+    if let Some(action) = options.on_quota_exhausted {
+        if let Some(utilization) = exhausted_utilization(&profile) {
+            let fallback = match (action, &profile.fallback_profile) {
+                (QuotaExhaustedAction::SwitchTo, Some(fallback)) => fallback.clone(),
+                (QuotaExhaustedAction::SwitchTo, None) => {
+                    eprintln!(
+                        "{} No fallback profile configured for '{}'",
+                        "⚠".yellow(),
+                        profile.name
+                    );
+                    return Err(RafctlError::QuotaExhausted {
+                        profile: profile.name.clone(),
+                        utilization,
+                    });
+                }
+                (QuotaExhaustedAction::Fail, _) => {
+                    return Err(RafctlError::QuotaExhausted {
+                        profile: profile.name.clone(),
+                        utilization,
+                    });
+                }
+            };
+
+            eprintln!(
+                "{} Profile '{}' is at {:.0}% of its 5-hour quota, switching to '{}'",
+                "→".cyan(),
+                profile.name,
+                utilization,
+                fallback
+            );
+            // Don't chain failovers: the fallback launches without the quota check
+            let fallback_options = RunOptions {
+                on_quota_exhausted: None,
+                ..options.clone()
+            };
+            return handle_run(Some(&fallback), args, &fallback_options);
+        }
+    }
+
+    if options.pre_check_quota {
+        check_rate_limit(&profile)?;
+    }
+
+    check_parallel_allowed(&profile, options)?;
+
+    if let Some(dir) = &options.cwd {
+        check_working_dir(dir)?;
+    }
+
     check_tool_available(profile.tool)?;
 
     set_terminal_title(&profile.name, profile.tool.command_name());
 
+    if let Some(hook) = &profile.pre_run {
+        let code = run_hook(&profile, "pre_run", hook, &[], options)?;
+        if code != Some(0) {
+            return Err(RafctlError::HookFailed {
+                hook: "pre_run",
+                code,
+            });
+        }
+    }
+
+    let started_at = Utc::now();
+    let snapshot = if options.snapshot_config {
+        let dir = snapshot_config(&profile, started_at)?;
+        println!("{} Config snapshot: {}", "ℹ".cyan(), dir.display());
+        Some(dir)
+    } else {
+        None
+    };
+
     let exit_code = match (&profile.tool, &profile.auth_mode) {
         (ToolType::Claude, AuthMode::ApiKey) => {
             debug::debug("launching with API key mode");
-            launch_with_api_key(&profile, args)?
+            launch_with_api_key(&profile, args, options)?
         }
         (ToolType::Claude, AuthMode::OAuth) => {
             debug::debug("launching with OAuth mode");
-            launch_with_oauth(&profile, args)?
+            launch_with_oauth(&profile, args, options)?
         }
-        (ToolType::Codex, _) => {
+        (ToolType::Codex, _) | (ToolType::Gemini, _) => {
             debug::debug("launching with default mode");
-            launch_default(&profile, args)?
+            launch_default(&profile, args, options)?
         }
     };
 
-    update_profile_usage(&mut profile, &name_lower);
+    if let Some(hook) = &profile.post_run {
+        let exit_env = [(ENV_RAFCTL_EXIT_CODE, exit_code.to_string())];
+        match run_hook(&profile, "post_run", hook, &exit_env, options) {
+            Ok(Some(0)) => {}
+            Ok(code) => eprintln!(
+                "{} {}",
+                "⚠".yellow(),
+                RafctlError::HookFailed {
+                    hook: "post_run",
+                    code
+                }
+            ),
+            Err(e) => eprintln!("{} {}", "⚠".yellow(), e),
+        }
+    }
+
+    if options.post_summary {
+        print_post_summary(&profile, started_at);
+    }
+
+    if options.capture_cost {
+        capture_session_cost(&profile, started_at);
+    }
+
+    update_profile_usage(
+        &mut profile,
+        &name_lower,
+        &HistoryEntry {
+            started_at,
+            args: args.to_vec(),
+            exit_code,
+            snapshot,
+        },
+    );
 
     Ok(exit_code)
 }
 
-fn update_profile_usage(profile: &mut Profile, name_lower: &str) {
+/// Profiles that cannot run in parallel (OAuth Claude) refuse to start while
+/// another profile holds the OAuth lock; more instances of the profile that
+/// holds it may join. Parallel-capable profiles never touch the lock, so they
+/// are not affected by a running OAuth instance.
+fn check_parallel_allowed(profile: &Profile, options: &RunOptions) -> Result<(), RafctlError> {
+    if profile.supports_parallel() {
+        return Ok(());
+    }
+
+    match inspect_lock(&oauth_lock_path()?) {
+        LockState::Held { holder } if holder.as_deref() == Some(profile.name.as_str()) => Ok(()),
+        LockState::Held { holder } if options.force => {
+            eprintln!(
+                "{} Ignoring OAuth lock held by profile '{}' (--force); its session may lose its token",
+                "⚠".yellow(),
+                holder.as_deref().unwrap_or("unknown")
+            );
+            Ok(())
+        }
+        LockState::Held { holder } => Err(RafctlError::OAuthConflict { holder }),
+        LockState::Absent | LockState::Stale { .. } => Ok(()),
+    }
+}
+
+/// Current 5-hour usage window for OAuth Claude profiles.
+/// Quota lookups that fail (offline, no token, non-macOS) never block a launch.
+fn five_hour_window(profile: &Profile) -> Option<UsageWindow> {
+    if profile.tool != ToolType::Claude || profile.auth_mode != AuthMode::OAuth {
+        return None;
+    }
+
+    let usage = match cached_usage_for_profile(&profile.name) {
+        Ok(u) => u,
+        Err(e) => {
+            debug::debug_labeled("quota check skipped", &e.to_string());
+            return None;
+        }
+    };
+
+    let window = usage.five_hour?;
+    debug::debug_labeled("5h utilization", &format!("{:.1}%", window.utilization));
+    Some(window)
+}
+
+/// Return the 5-hour utilization if it is at or above the exhaustion threshold.
+fn exhausted_utilization(profile: &Profile) -> Option<f64> {
+    let utilization = five_hour_window(profile)?.utilization;
+    (utilization >= QUOTA_EXHAUSTED_THRESHOLD).then_some(utilization)
+}
+
+/// Refuse to launch a profile that has already hit its 5-hour limit.
+fn check_rate_limit(profile: &Profile) -> Result<(), RafctlError> {
+    let Some(window) = five_hour_window(profile) else {
+        return Ok(());
+    };
+    if window.utilization < QUOTA_RATE_LIMIT_THRESHOLD {
+        return Ok(());
+    }
+
+    let resets = window
+        .resets_at
+        .as_deref()
+        .map(format_reset_time)
+        .unwrap_or_default();
+    eprintln!(
+        "{} Profile '{}' has reached its 5-hour limit {}",
+        "✗".red(),
+        profile.name,
+        resets
+    );
+    if let Some(fallback) = &profile.fallback_profile {
+        eprintln!("{}", format!("Try: rafctl run {}", fallback).dimmed());
+    }
+
+    Err(RafctlError::QuotaExhausted {
+        profile: profile.name.clone(),
+        utilization: window.utilization,
+    })
+}
+
+fn update_profile_usage(profile: &mut Profile, name_lower: &str, launch: &HistoryEntry) {
     profile.last_used = Some(Utc::now());
     if let Err(e) = save_profile(profile) {
         eprintln!("{} Failed to update profile: {}", "⚠".yellow(), e);
     }
+    if let Err(e) = append_history(name_lower, launch) {
+        eprintln!("{} Failed to record launch history: {}", "⚠".yellow(), e);
+    }
     if let Err(e) = set_last_used_profile(name_lower) {
         eprintln!("{} Failed to update last used: {}", "⚠".yellow(), e);
     }
@@ -75,31 +320,116 @@ fn build_rafctl_env(profile: &Profile) -> HashMap<String, String> {
         ENV_RAFCTL_PROFILE_TOOL.to_string(),
         profile.tool.to_string(),
     );
+    env.insert(ENV_RAFCTL_AUTH_MODE.to_string(), profile.auth_label());
+    // RAFCTL_CONFIG_DIR is rafctl's own root below, so the profile's dir gets its own name
+    if let Ok(dir) = get_profile_dir(&profile.name) {
+        env.insert(
+            ENV_RAFCTL_PROFILE_DIR.to_string(),
+            dir.to_string_lossy().into_owned(),
+        );
+    }
     env.insert(ENV_RAFCTL_VERSION.to_string(), VERSION.to_string());
+    // Hooks and the HUD run rafctl code too; keep them on the same config dir
+    if let Some(dir) = config_dir_override() {
+        env.insert(
+            ENV_RAFCTL_CONFIG_DIR.to_string(),
+            dir.to_string_lossy().into_owned(),
+        );
+    }
     env
 }
 
+/// Run a profile hook through the shell in `--cwd` (or the current directory),
+/// with the same `RAFCTL_*` variables the tool gets. Returns the hook's exit
+/// code (`None` if it was killed by a signal).
+fn run_hook(
+    profile: &Profile,
+    name: &'static str,
+    command: &str,
+    extra_env: &[(&str, String)],
+    options: &RunOptions,
+) -> Result<Option<i32>, RafctlError> {
+    debug::debug_labeled(name, command);
+
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    cmd.envs(build_rafctl_env(profile))
+        .envs(extra_env.iter().map(|(k, v)| (*k, v)))
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    if let Some(dir) = &options.cwd {
+        cmd.current_dir(dir);
+    }
+
+    let status = execute_command(&mut cmd, name)?;
+    Ok(status.code())
+}
+
+/// `--cwd` must name an existing directory; checked before any hook runs.
+fn check_working_dir(dir: &Path) -> Result<(), RafctlError> {
+    match std::fs::metadata(dir) {
+        Ok(meta) if meta.is_dir() => Ok(()),
+        Ok(_) => Err(RafctlError::ConfigRead {
+            path: dir.to_path_buf(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidInput, "not a directory"),
+        }),
+        Err(e) => Err(RafctlError::ConfigRead {
+            path: dir.to_path_buf(),
+            source: e,
+        }),
+    }
+}
+
 fn spawn_tool(
     profile: &Profile,
     args: &[String],
     extra_env: HashMap<String, String>,
+    options: &RunOptions,
 ) -> Result<i32, RafctlError> {
-    let config_dir = profile.tool.config_dir_for_profile(&profile.name)?;
-
-    debug::debug_path("config_dir", &config_dir);
-
     let mut cmd = Command::new(profile.tool.command_name());
 
-    cmd.env(profile.tool.env_var_name(), &config_dir)
-        .stdin(Stdio::inherit())
+    match profile.config_mode {
+        ConfigMode::Isolated => {
+            let config_dir = profile.tool.config_dir_for_profile(&profile.name)?;
+            debug::debug_path("config_dir", &config_dir);
+            cmd.env(profile.tool.env_var_name(), &config_dir);
+        }
+        ConfigMode::Shared => {
+            debug::debug("shared config dir mode, using the tool's own config dir");
+        }
+    }
+
+    cmd.stdin(Stdio::inherit())
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit());
 
+    if let Some(dir) = &options.cwd {
+        debug::debug_path("cwd", dir);
+        cmd.current_dir(dir);
+    }
+
     for (key, value) in build_rafctl_env(profile) {
         debug::debug_env(&key, &value);
         cmd.env(key, value);
     }
 
+    // Env file vars override rafctl's own vars but not the credentials below
+    if let Some(path) = &options.env_file {
+        debug::debug_path("env file", path);
+        for (key, value) in load_env_file(path)? {
+            debug::debug_env(&key, "***");
+            cmd.env(key, value);
+        }
+    }
+
     for (key, value) in extra_env {
         debug::debug_env(
             &key,
@@ -120,10 +450,151 @@ fn spawn_tool(
         cmd.arg(arg);
     }
 
+    if let Some(idle) = options.timeout_idle {
+        let transcripts_dir = transcripts_dir_for(profile)?;
+        debug::debug_path("idle watch", &transcripts_dir);
+        return run_with_idle_timeout(
+            &mut cmd,
+            profile.tool.command_name(),
+            &transcripts_dir,
+            idle,
+        );
+    }
+
     let status = execute_command(&mut cmd, profile.tool.command_name())?;
     Ok(status.code().unwrap_or(1))
 }
 
+/// Recap of the session this run wrote, found as the newest transcript
+/// modified after launch. Never fails the run.
+fn print_post_summary(profile: &Profile, started_at: DateTime<Utc>) {
+    if profile.tool != ToolType::Claude {
+        return;
+    }
+
+    let session = transcripts_dir_for(profile)
+        .ok()
+        .and_then(|dir| newest_session_since(&dir, started_at));
+
+    let Some(detail) = session.as_deref().and_then(parse_transcript) else {
+        println!(
+            "{} No session transcript was written during this run",
+            "ℹ".cyan()
+        );
+        return;
+    };
+
+    let pricing = load_pricing().unwrap_or_else(|e| {
+        debug::debug_labeled("pricing", &e.to_string());
+        PricingTable::builtin()
+    });
+    // Human output never writes to the --output file
+    let _ = print_session_detail(&detail, &pricing, OutputFormat::Human);
+}
+
+/// Record the cost of the session this run wrote in the profile's cost
+/// ledger. Never fails the run.
+fn capture_session_cost(profile: &Profile, started_at: DateTime<Utc>) {
+    if profile.tool != ToolType::Claude {
+        return;
+    }
+
+    let session = transcripts_dir_for(profile)
+        .ok()
+        .and_then(|dir| newest_session_since(&dir, started_at));
+    let Some(detail) = session.as_deref().and_then(parse_transcript) else {
+        println!(
+            "{} No session transcript was written during this run, no cost recorded",
+            "ℹ".cyan()
+        );
+        return;
+    };
+
+    let summary = detail.summary;
+    if !summary.has_usage {
+        println!(
+            "{} Session transcript has no token usage, no cost recorded",
+            "ℹ".cyan()
+        );
+        return;
+    }
+
+    let pricing = load_pricing().unwrap_or_else(|e| {
+        debug::debug_labeled("pricing", &e.to_string());
+        PricingTable::builtin()
+    });
+    let model = summary.model.unwrap_or_default();
+    let model_pricing = pricing.lookup(&model);
+    let entry = CostEntry {
+        recorded_at: Utc::now(),
+        session_id: summary.session_id,
+        model,
+        input_tokens: summary.input_tokens,
+        output_tokens: summary.output_tokens,
+        input_cost: model_pricing.estimate_cost(summary.input_tokens, 0),
+        output_cost: model_pricing.estimate_cost(0, summary.output_tokens),
+    };
+
+    match append_cost_entry(&profile.name, &entry) {
+        Ok(()) => println!(
+            "{} Session cost recorded: ${:.2}",
+            "ℹ".cyan(),
+            entry.total_cost()
+        ),
+        Err(e) => eprintln!("{} Failed to record session cost: {}", "⚠".yellow(), e),
+    }
+}
+
+/// Size of the newest transcript, used to detect whether the session is still active.
+fn transcript_snapshot(transcripts_dir: &Path) -> Option<(PathBuf, u64)> {
+    let path = newest_session(transcripts_dir)?;
+    let len = std::fs::metadata(&path).ok()?.len();
+    Some((path, len))
+}
+
+fn run_with_idle_timeout(
+    cmd: &mut Command,
+    tool_name: &str,
+    transcripts_dir: &Path,
+    idle: Duration,
+) -> Result<i32, RafctlError> {
+    let mut child = cmd.spawn().map_err(|e| RafctlError::ProcessSpawn {
+        tool: tool_name.to_string(),
+        message: e.to_string(),
+    })?;
+
+    let mut snapshot = transcript_snapshot(transcripts_dir);
+    let mut last_activity = Instant::now();
+
+    loop {
+        let status = child.try_wait().map_err(|e| RafctlError::ProcessSpawn {
+            tool: tool_name.to_string(),
+            message: e.to_string(),
+        })?;
+        if let Some(status) = status {
+            return Ok(status.code().unwrap_or(1));
+        }
+
+        let current = transcript_snapshot(transcripts_dir);
+        if current != snapshot {
+            snapshot = current;
+            last_activity = Instant::now();
+        } else if last_activity.elapsed() >= idle {
+            eprintln!(
+                "\n{} No session activity for {}s, stopping {}",
+                "⚠".yellow(),
+                idle.as_secs(),
+                tool_name
+            );
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(IDLE_TIMEOUT_EXIT_CODE);
+        }
+
+        std::thread::sleep(IDLE_POLL_INTERVAL);
+    }
+}
+
 fn execute_command(cmd: &mut Command, tool_name: &str) -> Result<ExitStatus, RafctlError> {
     cmd.status().map_err(|e| RafctlError::ProcessSpawn {
         tool: tool_name.to_string(),
@@ -131,61 +602,82 @@ fn execute_command(cmd: &mut Command, tool_name: &str) -> Result<ExitStatus, Raf
     })
 }
 
-fn launch_with_api_key(profile: &Profile, args: &[String]) -> Result<i32, RafctlError> {
+fn launch_with_api_key(
+    profile: &Profile,
+    args: &[String],
+    options: &RunOptions,
+) -> Result<i32, RafctlError> {
     #[allow(deprecated)]
     let api_key = if let Some(ref key) = profile.api_key {
         key.clone()
     } else {
-        credentials::get_credential(&profile.name, CredentialType::ApiKey)?
-            .ok_or_else(|| RafctlError::NoApiKey(profile.name.clone()))?
+        credentials::get_indexed_credential(
+            &profile.name,
+            CredentialType::ApiKey,
+            profile.active_api_key.unwrap_or(0),
+        )?
+        .ok_or_else(|| RafctlError::NoApiKey(profile.name.clone()))?
     };
 
     let mut extra_env = HashMap::new();
     extra_env.insert(ENV_ANTHROPIC_API_KEY.to_string(), api_key);
 
-    spawn_tool(profile, args, extra_env)
+    spawn_tool(profile, args, extra_env, options)
 }
 
 #[cfg(target_os = "macos")]
-fn launch_with_oauth(profile: &Profile, args: &[String]) -> Result<i32, RafctlError> {
-    use fs2::FileExt;
-    use std::fs::OpenOptions;
-
-    let config_dir = get_config_dir()?;
-    std::fs::create_dir_all(&config_dir).map_err(|e| RafctlError::ConfigWrite {
-        path: config_dir.clone(),
-        source: e,
-    })?;
-    let lock_path = config_dir.join("oauth.lock");
-
-    let lock_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&lock_path)
-        .map_err(|e| RafctlError::ConfigWrite {
-            path: lock_path.clone(),
-            source: e,
-        })?;
+fn launch_with_oauth(
+    profile: &Profile,
+    args: &[String],
+    options: &RunOptions,
+) -> Result<i32, RafctlError> {
+    let lock_path = oauth_lock_path()?;
+    let lock = match acquire_lock(&lock_path, &profile.name)? {
+        Some(lock) => Some(lock),
+        // Lost a race with another launch since the check in `handle_run`
+        None if !options.force => {
+            return Err(RafctlError::OAuthConflict {
+                holder: lock_holder(&lock_path),
+            });
+        }
+        None => None,
+    };
 
-    if lock_file.try_lock_exclusive().is_err() {
-        return Err(RafctlError::OAuthConflict);
-    }
+    // Another instance of this profile already put its token in place, and
+    // may have refreshed it since; leave it alone
+    if !lock.as_ref().is_some_and(|l| l.joined) {
+        let token = credentials::get_credential(&profile.name, CredentialType::OAuthToken)?
+            .ok_or_else(|| RafctlError::NotAuthenticated(profile.name.clone()))?;
 
-    use std::io::Write;
-    let mut lock_file = lock_file;
-    let _ = writeln!(lock_file, "{}", profile.name);
+        // Keep the user's own login so a plain `claude` does not inherit this profile
+        credentials::backup_claude_system_token()?;
+        credentials::write_claude_system_token(&token)?;
+    }
 
-    let token = credentials::get_credential(&profile.name, CredentialType::OAuthToken)?
-        .ok_or_else(|| RafctlError::NotAuthenticated(profile.name.clone()))?;
+    let result = launch_default(profile, args, options);
 
-    credentials::write_claude_system_token(&token)?;
+    // The last instance of the profile to exit puts the user's login back
+    if !lock.as_ref().is_none_or(OAuthLock::is_last_holder) {
+        return result;
+    }
+    if let Err(e) = credentials::restore_claude_system_token() {
+        eprintln!(
+            "{} Could not restore the system Claude token: {}",
+            "⚠".yellow(),
+            e
+        );
+        eprintln!("{}", "Retry with: rafctl auth restore-system".dimmed());
+    }
 
-    launch_default(profile, args)
+    result
 }
 
 #[cfg(not(target_os = "macos"))]
-fn launch_with_oauth(profile: &Profile, _args: &[String]) -> Result<i32, RafctlError> {
+fn launch_with_oauth(
+    profile: &Profile,
+    _args: &[String],
+    _options: &RunOptions,
+) -> Result<i32, RafctlError> {
     eprintln!(
         "{} OAuth mode requires macOS for keychain support",
         "✗".red()
@@ -200,8 +692,13 @@ fn launch_with_oauth(profile: &Profile, _args: &[String]) -> Result<i32, RafctlE
     ))
 }
 
-fn launch_default(profile: &Profile, args: &[String]) -> Result<i32, RafctlError> {
-    if !is_authenticated(profile.tool, &profile.name)? {
+fn launch_default(
+    profile: &Profile,
+    args: &[String],
+    options: &RunOptions,
+) -> Result<i32, RafctlError> {
+    // Shared profiles authenticate in the tool's own config dir, outside rafctl
+    if profile.is_isolated() && !is_authenticated(profile.tool, &profile.name)? {
         eprintln!(
             "{} Profile '{}' is not authenticated",
             "✗".red(),
@@ -214,7 +711,7 @@ fn launch_default(profile: &Profile, args: &[String]) -> Result<i32, RafctlError
         return Err(RafctlError::NotAuthenticated(profile.name.clone()));
     }
 
-    spawn_tool(profile, args, HashMap::new())
+    spawn_tool(profile, args, HashMap::new(), options)
 }
 
 fn set_terminal_title(profile_name: &str, tool_name: &str) {
@@ -227,19 +724,38 @@ fn set_terminal_title(profile_name: &str, tool_name: &str) {
     let _ = std::io::stdout().flush();
 }
 
-fn resolve_profile_name(profile_name: Option<&str>) -> Result<String, RafctlError> {
+/// Pseudo-profile names for "whatever ran last"; a real profile called
+/// `last` still wins
+const LAST_PROFILE_NAMES: [&str; 2] = ["-", "last"];
+
+fn resolve_profile_name(profile_name: Option<&str>, pick: bool) -> Result<String, RafctlError> {
     if let Some(name) = profile_name {
+        let name_lower = name.to_lowercase();
+        if LAST_PROFILE_NAMES.contains(&name_lower.as_str()) && !profile_exists(&name_lower)? {
+            return get_last_used_profile()?.ok_or(RafctlError::NoLastUsedProfile);
+        }
         return resolve_profile_alias(name);
     }
 
-    if let Some(default) = get_default_profile()? {
+    if pick {
+        if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+            return Err(RafctlError::MissingArgument(
+                "an interactive terminal (required by --pick)".to_string(),
+            ));
+        }
+    } else if let Some(default) = get_default_profile()? {
         return Ok(default);
     }
 
+    // Only offered on a terminal; scripts still get NoDefaultProfile below
+    if let Some(name) = pick_profile()? {
+        return Ok(name);
+    }
+
     let profiles = list_profiles()?;
     if profiles.is_empty() {
         eprintln!(
-            "{} No profiles found. Create one with: rafctl profile add <name> --tool <claude|codex>",
+            "{} No profiles found. Create one with: rafctl profile add <name> --tool <claude|codex|gemini>",
             "✗".red()
         );
     } else {
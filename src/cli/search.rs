@@ -0,0 +1,232 @@
+//! Search command handler - finds past sessions by tool call targets
+
+use std::path::PathBuf;
+
+use chrono::Local;
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL_CONDENSED, Cell, Color, Table};
+use serde::Serialize;
+
+use super::output::print_json;
+use super::sessions::shorten_session_id;
+use super::OutputFormat;
+use crate::core::transcript::{get_global_transcripts_dir, list_sessions, parse_transcript};
+use crate::error::RafctlError;
+
+/// Characters of context kept around a match in the snippet column
+const SNIPPET_WIDTH: usize = 60;
+
+#[derive(Debug, Serialize)]
+struct SearchOutput {
+    query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool: Option<String>,
+    results: Vec<SearchResult>,
+    total: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchResult {
+    session_id: String,
+    started_at: Option<String>,
+    cwd: Option<String>,
+    matches: usize,
+    /// Tool of the first matching call
+    tool: String,
+    snippet: String,
+}
+
+pub fn handle_search(
+    query: &str,
+    tool: Option<&str>,
+    limit: usize,
+    format: OutputFormat,
+) -> Result<(), RafctlError> {
+    let transcripts_dir = get_global_transcripts_dir().ok_or_else(|| RafctlError::ConfigRead {
+        path: PathBuf::from("~/.claude/projects"),
+        source: std::io::Error::new(std::io::ErrorKind::NotFound, "Home directory not found"),
+    })?;
+
+    let needle = query.to_lowercase();
+    let mut found = Vec::new();
+
+    if let Ok(projects) = std::fs::read_dir(&transcripts_dir) {
+        for project in projects.flatten() {
+            let project_path = project.path();
+            if !project_path.is_dir() {
+                continue;
+            }
+            for file in list_sessions(&project_path) {
+                let Some(detail) = parse_transcript(&file) else {
+                    continue;
+                };
+
+                let matching: Vec<_> = detail
+                    .tool_calls
+                    .iter()
+                    .filter(|call| tool.is_none_or(|t| call.name.eq_ignore_ascii_case(t)))
+                    .filter_map(|call| {
+                        let target = call.target.as_deref()?;
+                        target
+                            .to_lowercase()
+                            .contains(&needle)
+                            .then_some((call.name.as_str(), target))
+                    })
+                    .collect();
+
+                if let Some((first_tool, first_target)) = matching.first() {
+                    found.push((
+                        detail.summary.started_at,
+                        SearchResult {
+                            session_id: detail.summary.session_id.clone(),
+                            started_at: detail.summary.started_at.map(|dt| {
+                                dt.with_timezone(&Local)
+                                    .format("%Y-%m-%d %H:%M")
+                                    .to_string()
+                            }),
+                            cwd: detail.summary.cwd.clone(),
+                            matches: matching.len(),
+                            tool: first_tool.to_string(),
+                            snippet: snippet(first_target, &needle, SNIPPET_WIDTH),
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    found.sort_by_key(|(started, _)| std::cmp::Reverse(*started));
+    let total = found.len();
+    let results: Vec<SearchResult> = found.into_iter().take(limit).map(|(_, r)| r).collect();
+
+    match format {
+        OutputFormat::Json => {
+            print_json(&SearchOutput {
+                query: query.to_string(),
+                tool: tool.map(str::to_string),
+                results,
+                total,
+            })?;
+        }
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("SESSION_ID\tSTARTED\tMATCHES\tTOOL\tSNIPPET");
+            for r in &results {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    r.session_id,
+                    r.started_at.as_deref().unwrap_or("-"),
+                    r.matches,
+                    r.tool,
+                    r.snippet
+                );
+            }
+        }
+        OutputFormat::Human => {
+            println!(
+                "\n{} Sessions matching '{}' ({} total)\n",
+                "🔍".cyan(),
+                query.bold(),
+                total
+            );
+
+            if results.is_empty() {
+                println!("No matching tool calls found.");
+                return Ok(());
+            }
+
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL_CONDENSED);
+            table.set_header(vec!["Session ID", "Started", "Matches", "Tool", "Snippet"]);
+
+            for r in &results {
+                table.add_row(vec![
+                    Cell::new(shorten_session_id(&r.session_id)).fg(Color::Cyan),
+                    Cell::new(r.started_at.as_deref().unwrap_or("-")),
+                    Cell::new(r.matches),
+                    Cell::new(&r.tool),
+                    Cell::new(&r.snippet),
+                ]);
+            }
+
+            println!("{table}\n");
+
+            if total > limit {
+                println!(
+                    "{}",
+                    format!(
+                        "Showing {} of {} sessions. Use --limit to see more.",
+                        limit, total
+                    )
+                    .dimmed()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Single-line excerpt of `text` of at most `width` characters, centred on
+/// the first case-insensitive occurrence of `needle` (already lowercase).
+fn snippet(text: &str, needle: &str, width: usize) -> String {
+    let chars: Vec<char> = text
+        .chars()
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+    if chars.len() <= width {
+        return chars.into_iter().collect();
+    }
+
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let needle: Vec<char> = needle.chars().collect();
+    let pos = lower
+        .windows(needle.len().max(1))
+        .position(|w| w == needle.as_slice())
+        .unwrap_or(0);
+
+    let start = pos
+        .saturating_sub(width.saturating_sub(needle.len()) / 2)
+        .min(chars.len() - width);
+    let end = start + width;
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    out.extend(&chars[start..end]);
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_short_text_is_kept() {
+        assert_eq!(snippet("cargo\ntest", "test", 60), "cargo test");
+    }
+
+    #[test]
+    fn test_snippet_centres_on_match() {
+        let text = format!("{}needle{}", "a".repeat(50), "b".repeat(50));
+        let s = snippet(&text, "needle", 20);
+
+        assert!(s.starts_with('…') && s.ends_with('…'));
+        assert!(s.contains("needle"));
+        assert_eq!(s.chars().count(), 22);
+    }
+
+    #[test]
+    fn test_snippet_match_at_end() {
+        let text = format!("{}TAIL", "x".repeat(100));
+        let s = snippet(&text, "tail", 10);
+
+        assert_eq!(s, "…xxxxxxTAIL");
+    }
+}
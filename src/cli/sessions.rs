@@ -1,16 +1,24 @@
 //! Sessions command handler - displays past Claude Code sessions
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use colored::Colorize;
 use comfy_table::{presets::UTF8_FULL_CONDENSED, Cell, Color, Table};
 use serde::Serialize;
 
-use super::output::print_json;
-use super::OutputFormat;
+use super::analytics::format_tokens;
+use super::output::{confirm, print_json};
+use super::replay::run_replay;
+use super::{OutputFormat, SessionExportFormat};
+use crate::core::pricing::{load_pricing, PricingTable};
+use crate::core::profile::{load_profile, resolve_profile_alias};
 use crate::core::transcript::{
-    get_global_transcripts_dir, list_sessions, parse_transcript, SessionDetail,
+    encode_project_path, get_global_transcripts_dir, get_profile_transcripts_dir, list_sessions,
+    move_transcripts, parse_transcript, parse_transcript_full, AgentCall, SessionDetail,
 };
 use crate::error::RafctlError;
 
@@ -26,9 +34,16 @@ struct SessionRow {
     started_at: Option<String>,
     duration: Option<String>,
     messages: u64,
+    /// Tool calls other than agent (Task) calls
     tool_calls: u64,
+    /// Agent (Task) calls; omitted with `--no-agents`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agent_calls: Option<u64>,
     errors: u64,
     model: Option<String>,
+    /// Calls to the `--used-tool` tool; omitted without that filter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    used_tool_calls: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -44,7 +59,19 @@ struct SessionDetailOutput {
     tool_calls: u64,
     tool_errors: u64,
     agent_calls: u64,
+    /// `None` when the transcript has no token usage
+    tokens: Option<SessionTokens>,
+    cost_estimated: Option<f64>,
     tool_breakdown: Vec<ToolBreakdownEntry>,
+    agent_breakdown: Vec<AgentBreakdownEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct SessionTokens {
+    input: u64,
+    output: u64,
+    cache_creation: u64,
+    cache_read: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -54,22 +81,210 @@ struct ToolBreakdownEntry {
     percentage: f64,
 }
 
+#[derive(Debug, Serialize)]
+struct AgentBreakdownEntry {
+    /// `unknown` when the Task call named no subagent type
+    subagent_type: String,
+    count: u64,
+    percentage: f64,
+}
+
+/// Optional behaviors for `rafctl sessions`
+#[derive(Debug, Clone, Default)]
+pub struct SessionsOptions {
+    /// Only list sessions started today
+    pub today: bool,
+    /// Only list sessions started on or after this date (`YYYY-MM-DD`)
+    pub since: Option<String>,
+    /// Only list sessions started on or before this date (`YYYY-MM-DD`)
+    pub until: Option<String>,
+    /// How many sessions to list
+    pub limit: usize,
+    /// Step through the session interactively
+    pub replay: bool,
+    /// Hide agent (Task) call counts from the list
+    pub no_agents: bool,
+    /// Only list sessions that called this tool
+    pub used_tool: Option<String>,
+    /// Export the session in this format instead of showing it
+    pub export: Option<SessionExportFormat>,
+    /// Write the export to this file instead of stdout
+    pub out: Option<PathBuf>,
+    /// Pipe the exported session to this command's stdin
+    pub summary_cmd: Option<String>,
+}
+
 pub fn handle_sessions(
     session_id: Option<&str>,
-    today_only: bool,
-    limit: usize,
+    options: &SessionsOptions,
     format: OutputFormat,
 ) -> Result<(), RafctlError> {
-    if let Some(sid) = session_id {
-        show_session_detail(sid, format)
-    } else {
-        show_session_list(today_only, limit, format)
+    let since = options.since.as_deref().map(parse_date).transpose()?;
+    let until = options.until.as_deref().map(parse_date).transpose()?;
+
+    match (session_id, options.replay) {
+        (Some(sid), true) => replay_session(sid),
+        (None, true) => Err(RafctlError::MissingArgument(
+            "<SESSION_ID> (required by --replay)".to_string(),
+        )),
+        (Some(sid), false) => match (options.export, options.summary_cmd.as_deref()) {
+            (export_format, Some(command)) => summarize_session(
+                sid,
+                export_format.unwrap_or(SessionExportFormat::Md),
+                command,
+            ),
+            (Some(export_format), None) => {
+                export_session(sid, export_format, options.out.as_deref())
+            }
+            (None, None) => show_session_detail(sid, format),
+        },
+        (None, false) => show_session_list(
+            options.today,
+            since,
+            until,
+            options.limit,
+            options.no_agents,
+            options.used_tool.as_deref(),
+            format,
+        ),
     }
 }
 
+#[derive(Debug, Serialize)]
+struct MigrateOutput {
+    profile: String,
+    project: String,
+    destination: String,
+    moved: usize,
+}
+
+/// Move a project's transcripts from `~/.claude/projects` into a profile's
+/// transcripts dir, so per-profile analytics and `claude --resume` see them.
+pub fn handle_migrate(
+    project: &Path,
+    to: &str,
+    skip_confirm: bool,
+    format: OutputFormat,
+) -> Result<(), RafctlError> {
+    let resolved_name = resolve_profile_alias(to)?;
+    let name_lower = resolved_name.to_lowercase();
+    let profile = load_profile(&name_lower)?;
+
+    if !profile.is_isolated() {
+        println!(
+            "{} Profile '{}' is not isolated; it already uses the global sessions",
+            "ℹ".cyan(),
+            name_lower
+        );
+        return Ok(());
+    }
+
+    let project = std::fs::canonicalize(project).unwrap_or_else(|_| project.to_path_buf());
+    let encoded = encode_project_path(&project);
+    let source = get_global_transcripts_dir()
+        .ok_or(RafctlError::NoHomeDir)?
+        .join(&encoded);
+    let destination = get_profile_transcripts_dir(&name_lower)
+        .ok_or(RafctlError::NoHomeDir)?
+        .join(&encoded);
+
+    let pending = std::fs::read_dir(&source)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().extension().map(|x| x == "jsonl").unwrap_or(false))
+                .count()
+        })
+        .unwrap_or(0);
+
+    if pending == 0 {
+        match format {
+            OutputFormat::Json => print_json(&MigrateOutput {
+                profile: name_lower,
+                project: project.display().to_string(),
+                destination: destination.display().to_string(),
+                moved: 0,
+            })?,
+            _ => println!(
+                "{} No global sessions found for {}",
+                "ℹ".cyan(),
+                project.display()
+            ),
+        }
+        return Ok(());
+    }
+
+    // Nobody can answer the prompt, and moving transcripts is too
+    // destructive to assume a yes
+    if !skip_confirm && !io::stdin().is_terminal() {
+        return Err(RafctlError::MissingArgument(
+            "--yes (required to move sessions without a terminal)".to_string(),
+        ));
+    }
+    if !skip_confirm
+        && !confirm(&format!(
+            "{} Move {} transcript file(s) for {} into profile '{}'? [y/N]",
+            "⚠".yellow(),
+            pending,
+            project.display(),
+            name_lower
+        ))?
+    {
+        println!("{} Cancelled", "ℹ".cyan());
+        return Ok(());
+    }
+
+    let moved = move_transcripts(&source, &destination).map_err(|e| RafctlError::ConfigWrite {
+        path: destination.clone(),
+        source: e,
+    })?;
+
+    match format {
+        OutputFormat::Json => print_json(&MigrateOutput {
+            profile: name_lower,
+            project: project.display().to_string(),
+            destination: destination.display().to_string(),
+            moved: moved.len(),
+        })?,
+        _ => {
+            println!(
+                "{} Moved {} transcript file(s) into profile '{}'",
+                "✓".green(),
+                moved.len(),
+                name_lower
+            );
+            let skipped = pending - moved.len();
+            if skipped > 0 {
+                println!(
+                    "{} {} file(s) already existed in {} and were left in place",
+                    "⚠".yellow(),
+                    skipped,
+                    destination.display()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, RafctlError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| RafctlError::InvalidDate(value.to_string()))
+}
+
+/// Both bounds are inclusive.
+fn date_in_range(date: NaiveDate, since: Option<NaiveDate>, until: Option<NaiveDate>) -> bool {
+    since.is_none_or(|s| date >= s) && until.is_none_or(|u| date <= u)
+}
+
 fn show_session_list(
     today_only: bool,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
     limit: usize,
+    no_agents: bool,
+    used_tool: Option<&str>,
     format: OutputFormat,
 ) -> Result<(), RafctlError> {
     let transcripts_dir = get_global_transcripts_dir().ok_or_else(|| RafctlError::ConfigRead {
@@ -83,7 +298,7 @@ fn show_session_list(
                 print_json(&SessionsListOutput {
                     sessions: vec![],
                     total: 0,
-                });
+                })?;
             }
             _ => {
                 println!(
@@ -114,6 +329,22 @@ fn show_session_list(
                                 continue;
                             }
                         }
+                        // Ranges use the local date, matching the Started column
+                        if since.is_some() || until.is_some() {
+                            let in_range = detail.summary.started_at.is_some_and(|started| {
+                                date_in_range(
+                                    started.with_timezone(&Local).date_naive(),
+                                    since,
+                                    until,
+                                )
+                            });
+                            if !in_range {
+                                continue;
+                            }
+                        }
+                        if used_tool.is_some_and(|tool| tool_call_count(&detail, tool) == 0) {
+                            continue;
+                        }
                         all_sessions.push((file, detail));
                     }
                 }
@@ -143,8 +374,10 @@ fn show_session_list(
                 duration,
                 messages: detail.summary.message_count,
                 tool_calls: detail.summary.tool_calls,
+                agent_calls: (!no_agents).then_some(detail.summary.agent_calls),
                 errors: detail.summary.tool_errors,
                 model: detail.summary.model.as_ref().map(|m| shorten_model(m)),
+                used_tool_calls: used_tool.map(|tool| tool_call_count(detail, tool)),
             }
         })
         .collect();
@@ -153,19 +386,36 @@ fn show_session_list(
 
     match format {
         OutputFormat::Json => {
-            print_json(&SessionsListOutput { sessions, total });
+            print_json(&SessionsListOutput { sessions, total })?;
         }
-        OutputFormat::Plain => {
-            println!("SESSION_ID\tSTARTED\tDURATION\tMESSAGES\tTOOLS\tERRORS");
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            let mut header = "SESSION_ID\tSTARTED\tDURATION\tMESSAGES\tTOOLS\tERRORS".to_string();
+            if !no_agents {
+                header.push_str("\tAGENTS");
+            }
+            if used_tool.is_some() {
+                header.push_str("\tUSED_TOOL_CALLS");
+            }
+            println!("{}", header);
             for s in &sessions {
+                let agents = s
+                    .agent_calls
+                    .map(|n| format!("\t{}", n))
+                    .unwrap_or_default();
+                let used = s
+                    .used_tool_calls
+                    .map(|n| format!("\t{}", n))
+                    .unwrap_or_default();
                 println!(
-                    "{}\t{}\t{}\t{}\t{}\t{}",
+                    "{}\t{}\t{}\t{}\t{}\t{}{}{}",
                     s.session_id,
                     s.started_at.as_deref().unwrap_or("-"),
                     s.duration.as_deref().unwrap_or("-"),
                     s.messages,
                     s.tool_calls,
-                    s.errors
+                    s.errors,
+                    agents,
+                    used
                 );
             }
         }
@@ -185,14 +435,16 @@ fn show_session_list(
 
             let mut table = Table::new();
             table.load_preset(UTF8_FULL_CONDENSED);
-            table.set_header(vec![
-                "Session ID",
-                "Started",
-                "Duration",
-                "Messages",
-                "Tools",
-                "Errors",
-            ]);
+            let mut header = vec!["Session ID", "Started", "Duration", "Messages", "Tools"];
+            if !no_agents {
+                header.push("Agents");
+            }
+            header.push("Errors");
+            let used_header = used_tool.map(|tool| format!("{} calls", tool));
+            if let Some(h) = &used_header {
+                header.push(h);
+            }
+            table.set_header(header);
 
             for s in &sessions {
                 let error_cell = if s.errors > 0 {
@@ -201,14 +453,21 @@ fn show_session_list(
                     Cell::new(s.errors).fg(Color::Green)
                 };
 
-                table.add_row(vec![
+                let mut row = vec![
                     Cell::new(&s.session_id).fg(Color::Cyan),
                     Cell::new(s.started_at.as_deref().unwrap_or("-")),
                     Cell::new(s.duration.as_deref().unwrap_or("-")),
                     Cell::new(s.messages),
                     Cell::new(s.tool_calls),
-                    error_cell,
-                ]);
+                ];
+                if let Some(agents) = s.agent_calls {
+                    row.push(Cell::new(agents));
+                }
+                row.push(error_cell);
+                if let Some(used) = s.used_tool_calls {
+                    row.push(Cell::new(used).fg(Color::Cyan));
+                }
+                table.add_row(row);
             }
 
             println!("{table}\n");
@@ -229,16 +488,25 @@ fn show_session_list(
     Ok(())
 }
 
-fn show_session_detail(session_id: &str, format: OutputFormat) -> Result<(), RafctlError> {
+/// Calls to `tool` in a session; tool names match case-insensitively.
+fn tool_call_count(detail: &SessionDetail, tool: &str) -> u64 {
+    detail
+        .tool_breakdown
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case(tool))
+        .map(|(_, count)| count)
+        .sum()
+}
+
+/// Locate a session transcript by full or partial ID.
+fn find_session(session_id: &str) -> Result<(PathBuf, SessionDetail), RafctlError> {
     let transcripts_dir = get_global_transcripts_dir().ok_or_else(|| RafctlError::ConfigRead {
         path: PathBuf::from("~/.claude/projects"),
         source: std::io::Error::new(std::io::ErrorKind::NotFound, "Home directory not found"),
     })?;
 
-    let mut found_detail: Option<SessionDetail> = None;
-
     if let Ok(projects) = std::fs::read_dir(&transcripts_dir) {
-        'outer: for project in projects.flatten() {
+        for project in projects.flatten() {
             let project_path = project.path();
             if project_path.is_dir() {
                 let session_files = list_sessions(&project_path);
@@ -248,8 +516,7 @@ fn show_session_detail(session_id: &str, format: OutputFormat) -> Result<(), Raf
                             || detail.summary.session_id.ends_with(session_id)
                             || detail.summary.session_id.contains(session_id)
                         {
-                            found_detail = Some(detail);
-                            break 'outer;
+                            return Ok((file, detail));
                         }
                     }
                 }
@@ -257,12 +524,207 @@ fn show_session_detail(session_id: &str, format: OutputFormat) -> Result<(), Raf
         }
     }
 
-    let detail = found_detail.ok_or_else(|| {
-        RafctlError::ProfileNotFound(format!("Session '{}' not found", session_id))
+    Err(RafctlError::SessionNotFound(session_id.to_string()))
+}
+
+fn replay_session(session_id: &str) -> Result<(), RafctlError> {
+    if !std::io::stdout().is_terminal() {
+        return Err(RafctlError::MissingArgument(
+            "an interactive terminal (required by --replay)".to_string(),
+        ));
+    }
+
+    let (path, detail) = find_session(session_id)?;
+    let events = parse_transcript_full(&path).ok_or_else(|| RafctlError::ConfigRead {
+        path: path.clone(),
+        source: std::io::Error::new(std::io::ErrorKind::NotFound, "Transcript not readable"),
     })?;
 
-    let duration = calculate_duration(detail.summary.started_at, detail.summary.ended_at);
+    if events.is_empty() {
+        println!(
+            "{} Session {} has no events to replay",
+            "ℹ".cyan(),
+            shorten_session_id(&detail.summary.session_id)
+        );
+        return Ok(());
+    }
+
+    run_replay(&detail.summary.session_id, events)
+}
+
+fn show_session_detail(session_id: &str, format: OutputFormat) -> Result<(), RafctlError> {
+    let (_, detail) = find_session(session_id)?;
+    let pricing = load_pricing()?;
+    print_session_detail(&detail, &pricing, format)
+}
+
+fn render_export(detail: &SessionDetail, export_format: SessionExportFormat) -> String {
+    match export_format {
+        SessionExportFormat::Md => render_markdown(detail),
+    }
+}
+
+fn export_session(
+    session_id: &str,
+    export_format: SessionExportFormat,
+    out: Option<&Path>,
+) -> Result<(), RafctlError> {
+    let (_, detail) = find_session(session_id)?;
+    let document = render_export(&detail, export_format);
+
+    match out {
+        Some(path) => {
+            std::fs::write(path, document).map_err(|e| RafctlError::ConfigWrite {
+                path: path.to_path_buf(),
+                source: e,
+            })?;
+            eprintln!(
+                "{} Session {} exported to {}",
+                "✓".green(),
+                shorten_session_id(&detail.summary.session_id),
+                path.display()
+            );
+        }
+        None => print!("{}", document),
+    }
+
+    Ok(())
+}
+
+/// Pipe the exported session into a shell command (e.g. an LLM CLI) and let
+/// it print to the terminal.
+fn summarize_session(
+    session_id: &str,
+    export_format: SessionExportFormat,
+    command: &str,
+) -> Result<(), RafctlError> {
+    let (_, detail) = find_session(session_id)?;
+    let document = render_export(&detail, export_format);
+
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    let spawn_error = |e: io::Error| RafctlError::ProcessSpawn {
+        tool: command.to_string(),
+        message: e.to_string(),
+    };
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(spawn_error)?;
 
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that stops reading early is not an error on our side
+        match stdin.write_all(document.as_bytes()) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(spawn_error(e)),
+            _ => {}
+        }
+    }
+
+    let status = child.wait().map_err(spawn_error)?;
+    if !status.success() {
+        return Err(RafctlError::HookFailed {
+            hook: "summary",
+            code: status.code(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Markdown summary of a session: metadata, tool usage and every tool call in order.
+fn render_markdown(detail: &SessionDetail) -> String {
+    let summary = &detail.summary;
+    let local_time = |dt: Option<DateTime<Utc>>| {
+        dt.map(|dt| {
+            dt.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| "-".to_string())
+    };
+
+    let mut md = format!("# Session {}\n\n", summary.session_id);
+    md.push_str(&format!(
+        "- **Started:** {}\n",
+        local_time(summary.started_at)
+    ));
+    md.push_str(&format!(
+        "- **Duration:** {}\n",
+        calculate_duration(summary.started_at, summary.ended_at)
+            .as_deref()
+            .unwrap_or("-")
+    ));
+    md.push_str(&format!(
+        "- **Model:** {}\n",
+        summary.model.as_deref().unwrap_or("-")
+    ));
+    md.push_str(&format!(
+        "- **Directory:** `{}`\n",
+        summary.cwd.as_deref().unwrap_or("-")
+    ));
+    md.push_str(&format!(
+        "- **Git Branch:** {}\n",
+        summary.git_branch.as_deref().unwrap_or("-")
+    ));
+    md.push_str(&format!(
+        "- **Messages:** {} · **Tool Calls:** {} ({} errors) · **Agent Calls:** {}\n",
+        summary.message_count, summary.tool_calls, summary.tool_errors, summary.agent_calls
+    ));
+
+    md.push_str("\n## Tool Usage\n\n");
+    let breakdown = tool_breakdown_entries(detail);
+    if breakdown.is_empty() {
+        md.push_str("No tool calls.\n");
+    } else {
+        md.push_str("| Tool | Calls | Share |\n|------|------:|------:|\n");
+        for entry in &breakdown {
+            md.push_str(&format!(
+                "| {} | {} | {:.0}% |\n",
+                markdown_cell(&entry.tool),
+                entry.count,
+                entry.percentage
+            ));
+        }
+    }
+
+    if !detail.tool_calls.is_empty() {
+        md.push_str("\n## Tool Calls\n\n");
+        md.push_str("| Time | Tool | Target | Status |\n|------|------|--------|--------|\n");
+        for call in &detail.tool_calls {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                call.timestamp
+                    .map(|dt| dt.with_timezone(&Local).format("%H:%M:%S").to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                markdown_cell(&call.name),
+                call.target
+                    .as_deref()
+                    .map(|t| format!("`{}`", markdown_cell(t)))
+                    .unwrap_or_else(|| "-".to_string()),
+                if call.is_error { "error" } else { "ok" }
+            ));
+        }
+    }
+
+    md
+}
+
+/// Keep a value on one table row: escape pipes and flatten newlines.
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+fn tool_breakdown_entries(detail: &SessionDetail) -> Vec<ToolBreakdownEntry> {
     let mut tool_breakdown: Vec<ToolBreakdownEntry> = detail
         .tool_breakdown
         .iter()
@@ -280,7 +742,59 @@ fn show_session_detail(session_id: &str, format: OutputFormat) -> Result<(), Raf
         })
         .collect();
 
-    tool_breakdown.sort_by(|a, b| b.count.cmp(&a.count));
+    tool_breakdown.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tool.cmp(&b.tool)));
+    tool_breakdown
+}
+
+/// Task invocations grouped by subagent type, most used first.
+fn agent_breakdown_entries(agent_calls: &[AgentCall]) -> Vec<AgentBreakdownEntry> {
+    let mut counts: HashMap<&str, u64> = HashMap::new();
+    for call in agent_calls {
+        *counts
+            .entry(call.subagent_type.as_deref().unwrap_or("unknown"))
+            .or_insert(0) += 1;
+    }
+
+    let total = agent_calls.len() as f64;
+    let mut breakdown: Vec<AgentBreakdownEntry> = counts
+        .into_iter()
+        .map(|(subagent_type, count)| AgentBreakdownEntry {
+            subagent_type: subagent_type.to_string(),
+            count,
+            percentage: count as f64 / total * 100.0,
+        })
+        .collect();
+
+    breakdown.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.subagent_type.cmp(&b.subagent_type))
+    });
+    breakdown
+}
+
+/// Print the `rafctl sessions <id>` detail view for a parsed transcript.
+pub fn print_session_detail(
+    detail: &SessionDetail,
+    pricing: &PricingTable,
+    format: OutputFormat,
+) -> Result<(), RafctlError> {
+    let summary = &detail.summary;
+    let duration = calculate_duration(summary.started_at, summary.ended_at);
+    let tool_breakdown = tool_breakdown_entries(detail);
+    let agent_breakdown = agent_breakdown_entries(&detail.agent_calls);
+
+    let tokens = summary.has_usage.then_some(SessionTokens {
+        input: summary.input_tokens,
+        output: summary.output_tokens,
+        cache_creation: summary.cache_creation_tokens,
+        cache_read: summary.cache_read_tokens,
+    });
+    let cost_estimated = summary.has_usage.then(|| {
+        pricing
+            .lookup(summary.model.as_deref().unwrap_or_default())
+            .estimate_cost(summary.input_tokens, summary.output_tokens)
+    });
 
     let output = SessionDetailOutput {
         session_id: detail.summary.session_id.clone(),
@@ -302,14 +816,17 @@ fn show_session_detail(session_id: &str, format: OutputFormat) -> Result<(), Raf
         tool_calls: detail.summary.tool_calls,
         tool_errors: detail.summary.tool_errors,
         agent_calls: detail.summary.agent_calls,
+        tokens,
+        cost_estimated,
         tool_breakdown,
+        agent_breakdown,
     };
 
     match format {
         OutputFormat::Json => {
-            print_json(&output);
+            print_json(&output)?;
         }
-        OutputFormat::Plain => {
+        OutputFormat::Plain | OutputFormat::Porcelain => {
             println!("SESSION_ID\t{}", output.session_id);
             println!("STARTED\t{}", output.started_at.as_deref().unwrap_or("-"));
             println!("ENDED\t{}", output.ended_at.as_deref().unwrap_or("-"));
@@ -321,6 +838,24 @@ fn show_session_detail(session_id: &str, format: OutputFormat) -> Result<(), Raf
             println!("TOOLS\t{}", output.tool_calls);
             println!("ERRORS\t{}", output.tool_errors);
             println!("AGENTS\t{}", output.agent_calls);
+            match &output.tokens {
+                Some(t) => {
+                    println!("INPUT_TOKENS\t{}", t.input);
+                    println!("OUTPUT_TOKENS\t{}", t.output);
+                    println!("CACHE_CREATION_TOKENS\t{}", t.cache_creation);
+                    println!("CACHE_READ_TOKENS\t{}", t.cache_read);
+                }
+                None => {
+                    println!("INPUT_TOKENS\t-");
+                    println!("OUTPUT_TOKENS\t-");
+                    println!("CACHE_CREATION_TOKENS\t-");
+                    println!("CACHE_READ_TOKENS\t-");
+                }
+            }
+            match output.cost_estimated {
+                Some(cost) => println!("COST_ESTIMATED\t{:.4}", cost),
+                None => println!("COST_ESTIMATED\t-"),
+            }
         }
         OutputFormat::Human => {
             println!(
@@ -354,6 +889,24 @@ fn show_session_detail(session_id: &str, format: OutputFormat) -> Result<(), Raf
                 }
             );
             println!("Agent Calls: {}", output.agent_calls.to_string().cyan());
+            match &output.tokens {
+                Some(t) => println!(
+                    "Tokens:      {} in / {} out ({} cache write, {} cache read)",
+                    format_tokens(t.input).cyan(),
+                    format_tokens(t.output).cyan(),
+                    format_tokens(t.cache_creation),
+                    format_tokens(t.cache_read)
+                ),
+                None => println!("Tokens:      —"),
+            }
+            match output.cost_estimated {
+                Some(cost) => println!(
+                    "Est. Cost:   ~${:.2} {}",
+                    cost,
+                    "(input/output tokens, excludes cache)".dimmed()
+                ),
+                None => println!("Est. Cost:   —"),
+            }
             println!();
 
             if !output.tool_breakdown.is_empty() {
@@ -367,9 +920,20 @@ fn show_session_detail(session_id: &str, format: OutputFormat) -> Result<(), Raf
                 }
                 println!();
             }
+
+            if !output.agent_breakdown.is_empty() {
+                println!("{}", "Agents Used:".bold());
+                for entry in &output.agent_breakdown {
+                    let bar = progress_bar(entry.percentage, 10);
+                    println!(
+                        "  {} {:<12} {:>4} calls ({:.0}%)",
+                        bar, entry.subagent_type, entry.count, entry.percentage
+                    );
+                }
+                println!();
+            }
         }
     }
-
     Ok(())
 }
 
@@ -392,7 +956,7 @@ fn calculate_duration(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>)
     }
 }
 
-fn shorten_session_id(id: &str) -> String {
+pub fn shorten_session_id(id: &str) -> String {
     if id.len() > 12 {
         format!("{}...", &id[..12])
     } else {
@@ -410,8 +974,63 @@ fn shorten_model(model: &str) -> String {
         .to_string()
 }
 
-fn progress_bar(percentage: f64, width: usize) -> String {
+pub(super) fn progress_bar(percentage: f64, width: usize) -> String {
     let filled = ((percentage / 100.0) * width as f64).round() as usize;
     let empty = width.saturating_sub(filled);
     format!("{}{}", "█".repeat(filled), "░".repeat(empty))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        parse_date(s).unwrap()
+    }
+
+    #[test]
+    fn test_agent_breakdown_groups_and_sorts() {
+        let call = |subagent_type: Option<&str>| AgentCall {
+            subagent_type: subagent_type.map(str::to_string),
+            description: None,
+            timestamp: None,
+        };
+        let breakdown = agent_breakdown_entries(&[
+            call(Some("explore")),
+            call(None),
+            call(Some("explore")),
+            call(Some("reviewer")),
+        ]);
+
+        let rows: Vec<(&str, u64)> = breakdown
+            .iter()
+            .map(|e| (e.subagent_type.as_str(), e.count))
+            .collect();
+        assert_eq!(rows, [("explore", 2), ("reviewer", 1), ("unknown", 1)]);
+        assert_eq!(breakdown[0].percentage, 50.0);
+        assert!(agent_breakdown_entries(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_date_range_is_inclusive() {
+        let since = Some(date("2026-01-05"));
+        let until = Some(date("2026-01-07"));
+
+        assert!(!date_in_range(date("2026-01-04"), since, until));
+        assert!(date_in_range(date("2026-01-05"), since, until));
+        assert!(date_in_range(date("2026-01-07"), since, until));
+        assert!(!date_in_range(date("2026-01-08"), since, until));
+
+        assert!(date_in_range(date("2020-01-01"), None, until));
+        assert!(date_in_range(date("2030-01-01"), since, None));
+    }
+
+    #[test]
+    fn test_parse_date_rejects_malformed() {
+        assert!(matches!(
+            parse_date("2026-13-01"),
+            Err(RafctlError::InvalidDate(_))
+        ));
+        assert!(parse_date("01/05/2026").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use colored::Colorize;
+use serde::Serialize;
+
+use super::output::print_json;
+use super::OutputFormat;
+use crate::core::profile::{load_profile, resolve_profile_alias};
+use crate::core::stats::{
+    get_profile_stats_path, load_profile_stats, load_stats_cache, read_stats_cache,
+    save_stats_cache, MergeStrategy,
+};
+use crate::error::RafctlError;
+
+#[derive(Serialize)]
+struct ImportOutput {
+    profile: String,
+    strategy: MergeStrategy,
+    /// Days in the imported file
+    days: usize,
+    /// Imported days the profile already had, combined per `strategy`
+    overlapping_days: usize,
+    stats_file: String,
+}
+
+/// Write a profile's stats cache as pretty JSON, e.g. to archive usage
+/// history before the tool prunes old days. The output can be fed back to
+/// `rafctl stats import`.
+pub fn handle_export(profile_name: &str, out: Option<&Path>) -> Result<(), RafctlError> {
+    let name = resolve_profile_alias(profile_name)?.to_lowercase();
+    let profile = load_profile(&name)?;
+    let stats = load_profile_stats(&name, profile.tool);
+
+    let document = serde_json::to_string_pretty(&stats).map_err(|e| RafctlError::ConfigWrite {
+        path: out.map(Path::to_path_buf).unwrap_or_default(),
+        source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+    })?;
+
+    match out {
+        Some(path) => {
+            std::fs::write(path, format!("{}\n", document)).map_err(|e| {
+                RafctlError::ConfigWrite {
+                    path: path.to_path_buf(),
+                    source: e,
+                }
+            })?;
+            eprintln!(
+                "{} Exported {} day(s) of usage from '{}' to {}",
+                "✓".green(),
+                stats.daily_activity.len(),
+                name,
+                path.display()
+            );
+        }
+        None => println!("{}", document),
+    }
+
+    Ok(())
+}
+
+/// Merge a foreign `stats-cache.json` (e.g. from a CI machine) into a
+/// profile's local stats.
+pub fn handle_import(
+    file: &Path,
+    profile_name: &str,
+    strategy: MergeStrategy,
+    format: OutputFormat,
+) -> Result<(), RafctlError> {
+    let name = resolve_profile_alias(profile_name)?.to_lowercase();
+    let profile = load_profile(&name)?;
+    let imported = read_stats_cache(file)?;
+
+    let stats_path = get_profile_stats_path(&name, profile.tool)?;
+    let mut stats = load_stats_cache(&stats_path);
+    let overlapping_days = imported
+        .daily_activity
+        .iter()
+        .filter(|day| stats.activity_for_date(&day.date).is_some())
+        .count();
+    stats.merge_with(&imported, strategy);
+    save_stats_cache(&stats_path, &stats)?;
+
+    let output = ImportOutput {
+        profile: name,
+        strategy,
+        days: imported.daily_activity.len(),
+        overlapping_days,
+        stats_file: stats_path.display().to_string(),
+    };
+
+    match format {
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("profile={}", output.profile);
+            println!("strategy={}", output.strategy);
+            println!("days={}", output.days);
+            println!("overlapping_days={}", output.overlapping_days);
+            println!("stats_file={}", output.stats_file);
+        }
+        OutputFormat::Human => {
+            println!(
+                "{} Imported {} day(s) of usage into '{}'",
+                "✓".green(),
+                output.days,
+                output.profile
+            );
+            if output.overlapping_days > 0 {
+                println!(
+                    "{}",
+                    format!(
+                        "{} day(s) already present were combined with --strategy {}",
+                        output.overlapping_days, output.strategy
+                    )
+                    .dimmed()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
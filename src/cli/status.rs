@@ -2,11 +2,15 @@ use colored::Colorize;
 use comfy_table::{presets::UTF8_FULL, Cell, CellAlignment, Color, ContentArrangement, Table};
 use serde::Serialize;
 
-use crate::cli::output::print_json;
+use crate::cli::output::{
+    print_json, print_porcelain_header, warn_corrupted_profile, PorcelainRecord,
+};
 use crate::cli::OutputFormat;
 use crate::core::config::load_global_config;
 use crate::core::credentials;
-use crate::core::profile::{list_profiles, load_profile, AuthMode, ToolType};
+use crate::core::profile::{
+    list_profiles, list_profiles_with_tag, load_profile, AuthMode, ToolType,
+};
 use crate::error::RafctlError;
 use crate::tools::is_authenticated;
 
@@ -27,14 +31,19 @@ struct StatusOutput {
     profiles: Vec<ProfileStatus>,
 }
 
-pub fn handle_status(profile_name: Option<&str>, format: OutputFormat) -> Result<(), RafctlError> {
+/// Print profile status. Returns whether every reported profile is authenticated.
+pub fn handle_status(
+    profile_name: Option<&str>,
+    tag: Option<&str>,
+    format: OutputFormat,
+) -> Result<bool, RafctlError> {
     match profile_name {
         Some(name) => show_single_status(name, format),
-        None => show_all_status(format),
+        None => show_all_status(tag, format),
     }
 }
 
-fn show_single_status(profile_name: &str, format: OutputFormat) -> Result<(), RafctlError> {
+fn show_single_status(profile_name: &str, format: OutputFormat) -> Result<bool, RafctlError> {
     let name_lower = profile_name.to_lowercase();
     let profile = load_profile(&name_lower)?;
     let config = load_global_config()?;
@@ -70,8 +79,9 @@ fn show_single_status(profile_name: &str, format: OutputFormat) -> Result<(), Ra
 
     match format {
         OutputFormat::Json => {
-            print_json(&status);
+            print_json(&status)?;
         }
+        OutputFormat::Porcelain => print_status_porcelain(&[status]),
         OutputFormat::Plain => {
             println!("Profile: {}", profile.name);
             if is_default {
@@ -166,25 +176,45 @@ fn show_single_status(profile_name: &str, format: OutputFormat) -> Result<(), Ra
         }
     }
 
-    Ok(())
+    Ok(authenticated)
 }
 
-fn show_all_status(format: OutputFormat) -> Result<(), RafctlError> {
-    let profiles = list_profiles()?;
+fn print_status_porcelain(statuses: &[ProfileStatus]) {
+    print_porcelain_header();
+    for s in statuses {
+        PorcelainRecord::new("status")
+            .field("name", &s.name)
+            .field("tool", &s.tool)
+            .opt_field("auth_mode", s.auth_mode.as_ref())
+            .field("authenticated", s.authenticated)
+            .field("is_default", s.is_default)
+            .field("is_last_used", s.is_last_used)
+            .field("created_at", &s.created_at)
+            .opt_field("last_used", s.last_used.as_ref())
+            .print();
+    }
+}
+
+fn show_all_status(tag: Option<&str>, format: OutputFormat) -> Result<bool, RafctlError> {
+    let profiles = match tag {
+        Some(tag) => list_profiles_with_tag(tag)?,
+        None => list_profiles()?,
+    };
 
     if profiles.is_empty() {
         match format {
-            OutputFormat::Json => print_json(&StatusOutput { profiles: vec![] }),
+            OutputFormat::Json => print_json(&StatusOutput { profiles: vec![] })?,
+            OutputFormat::Porcelain => print_status_porcelain(&[]),
             OutputFormat::Plain => {
                 println!("No profiles found.");
             }
             OutputFormat::Human => {
                 println!(
-                    "No profiles found. Create one with: rafctl profile add <name> --tool <claude|codex>"
+                    "No profiles found. Create one with: rafctl profile add <name> --tool <claude|codex|gemini>"
                 );
             }
         }
-        return Ok(());
+        return Ok(true);
     }
 
     let config = load_global_config()?;
@@ -192,44 +222,52 @@ fn show_all_status(format: OutputFormat) -> Result<(), RafctlError> {
     let mut status_list: Vec<ProfileStatus> = Vec::new();
 
     for name in &profiles {
-        if let Ok(profile) = load_profile(name) {
-            let authenticated = is_authenticated(profile.tool, name).unwrap_or(false);
-            let is_default = config
-                .default_profile
-                .as_ref()
-                .map(|d| d == name)
-                .unwrap_or(false);
-            let is_last_used = config
-                .last_used_profile
-                .as_ref()
-                .map(|d| d == name)
-                .unwrap_or(false);
-
-            status_list.push(ProfileStatus {
-                name: profile.name.clone(),
-                tool: profile.tool.to_string(),
-                auth_mode: if profile.tool == ToolType::Claude {
-                    Some(profile.auth_mode.to_string())
-                } else {
-                    None
-                },
-                authenticated,
-                is_default,
-                is_last_used,
-                created_at: profile.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-                last_used: profile
-                    .last_used
-                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
-            });
-        }
+        let profile = match load_profile(name) {
+            Ok(profile) => profile,
+            Err(e) => {
+                warn_corrupted_profile(name, &e, format);
+                continue;
+            }
+        };
+        let authenticated = is_authenticated(profile.tool, name).unwrap_or(false);
+        let is_default = config
+            .default_profile
+            .as_ref()
+            .map(|d| d == name)
+            .unwrap_or(false);
+        let is_last_used = config
+            .last_used_profile
+            .as_ref()
+            .map(|d| d == name)
+            .unwrap_or(false);
+
+        status_list.push(ProfileStatus {
+            name: profile.name.clone(),
+            tool: profile.tool.to_string(),
+            auth_mode: if profile.tool == ToolType::Claude {
+                Some(profile.auth_mode.to_string())
+            } else {
+                None
+            },
+            authenticated,
+            is_default,
+            is_last_used,
+            created_at: profile.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            last_used: profile
+                .last_used
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+        });
     }
 
+    let all_authenticated = status_list.iter().all(|s| s.authenticated);
+
     match format {
         OutputFormat::Json => {
             print_json(&StatusOutput {
                 profiles: status_list,
-            });
+            })?;
         }
+        OutputFormat::Porcelain => print_status_porcelain(&status_list),
         OutputFormat::Plain => {
             println!("NAME\tTOOL\tAUTH\tLAST_USED");
             for s in &status_list {
@@ -303,5 +341,5 @@ fn show_all_status(format: OutputFormat) -> Result<(), RafctlError> {
         }
     }
 
-    Ok(())
+    Ok(all_authenticated)
 }
@@ -0,0 +1,49 @@
+//! `rafctl version`: the crate version plus build metadata for automation.
+
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::cli::output::print_json;
+use crate::cli::OutputFormat;
+use crate::error::RafctlError;
+
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    /// Target triple the binary was compiled for
+    pub target: &'static str,
+    /// Commit the binary was built from; `None` outside a git checkout
+    pub git_sha: Option<&'static str>,
+}
+
+pub fn version_info() -> VersionInfo {
+    VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        target: env!("RAFCTL_BUILD_TARGET"),
+        git_sha: option_env!("RAFCTL_GIT_SHA"),
+    }
+}
+
+pub fn handle_version(format: OutputFormat) -> Result<(), RafctlError> {
+    let info = version_info();
+
+    match format {
+        OutputFormat::Json => print_json(&info)?,
+        OutputFormat::Plain | OutputFormat::Porcelain => {
+            println!("version={}", info.version);
+            println!("target={}", info.target);
+            println!("git_sha={}", info.git_sha.unwrap_or_default());
+        }
+        OutputFormat::Human => {
+            println!("{} {}", "rafctl".bold(), info.version);
+            println!("  {:<8} {}", "Target:".dimmed(), info.target);
+            println!(
+                "  {:<8} {}",
+                "Commit:".dimmed(),
+                info.git_sha.unwrap_or("unknown")
+            );
+        }
+    }
+
+    Ok(())
+}
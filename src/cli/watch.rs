@@ -10,30 +10,69 @@ use std::time::Duration;
 use chrono::{DateTime, Local};
 use colored::Colorize;
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
 
-use crate::core::transcript::{get_global_transcripts_dir, list_sessions};
+use super::output::print_json_line;
+use super::OutputFormat;
+
+use crate::core::transcript::{
+    find_sessions_matching, get_global_transcripts_dir, newest_session, truncate_str,
+};
 use crate::error::RafctlError;
 
-pub fn handle_watch(profile: Option<&str>) -> Result<(), RafctlError> {
+/// Characters of assistant text shown per block with `--show-text`
+const TEXT_SNIPPET_WIDTH: usize = 80;
+
+/// A single live event, as emitted by `watch --json`.
+#[derive(Debug, Serialize)]
+struct WatchEvent {
+    #[serde(rename = "type")]
+    event_type: &'static str,
+    timestamp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    /// First line of an assistant text block (`--show-text` only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    is_error: bool,
+}
+
+pub fn handle_watch(
+    profile: Option<&str>,
+    session: Option<&str>,
+    show_text: bool,
+    format: OutputFormat,
+) -> Result<(), RafctlError> {
     let transcripts_dir = get_global_transcripts_dir().ok_or_else(|| RafctlError::ConfigRead {
         path: PathBuf::from("~/.claude/projects"),
         source: std::io::Error::new(std::io::ErrorKind::NotFound, "Home directory not found"),
     })?;
 
     if !transcripts_dir.exists() {
-        println!(
-            "{} No sessions found. Start Claude Code to create sessions.",
-            "ℹ".cyan()
-        );
+        if format != OutputFormat::Json {
+            println!(
+                "{} No sessions found. Start Claude Code to create sessions.",
+                "ℹ".cyan()
+            );
+        }
         return Ok(());
     }
 
-    let session_file = find_most_recent_session(&transcripts_dir)?;
+    let session_file = match session {
+        Some(id) => find_session_by_id(&transcripts_dir, id)?,
+        None => find_most_recent_session(&transcripts_dir)?,
+    };
     let session_id = session_file
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("unknown");
 
+    if format == OutputFormat::Json {
+        return watch_session_file(&session_file, show_text, format);
+    }
+
     let profile_display = profile.unwrap_or("default");
 
     println!();
@@ -48,42 +87,47 @@ pub fn handle_watch(profile: Option<&str>) -> Result<(), RafctlError> {
     println!("{}", "Press Ctrl+C to stop watching".dimmed());
     println!();
 
-    watch_session_file(&session_file)
+    watch_session_file(&session_file, show_text, format)
 }
 
 fn find_most_recent_session(transcripts_dir: &std::path::Path) -> Result<PathBuf, RafctlError> {
-    let mut all_sessions: Vec<PathBuf> = Vec::new();
-
-    if let Ok(projects) = std::fs::read_dir(transcripts_dir) {
-        for project in projects.flatten() {
-            let project_path = project.path();
-            if project_path.is_dir() {
-                let sessions = list_sessions(&project_path);
-                all_sessions.extend(sessions);
-            }
-        }
-    }
-
-    all_sessions.sort_by(|a, b| {
-        let a_time = std::fs::metadata(a).and_then(|m| m.modified()).ok();
-        let b_time = std::fs::metadata(b).and_then(|m| m.modified()).ok();
-        b_time.cmp(&a_time)
-    });
-
-    all_sessions.into_iter().next().ok_or_else(|| {
+    newest_session(transcripts_dir).ok_or_else(|| {
         RafctlError::ProfileNotFound("No session files found. Start Claude Code first.".to_string())
     })
 }
 
-fn watch_session_file(path: &PathBuf) -> Result<(), RafctlError> {
+fn find_session_by_id(
+    transcripts_dir: &std::path::Path,
+    session_id: &str,
+) -> Result<PathBuf, RafctlError> {
+    let mut matches = find_sessions_matching(transcripts_dir, session_id);
+
+    match matches.len() {
+        0 => Err(RafctlError::SessionNotFound(session_id.to_string())),
+        1 => Ok(matches.remove(0)),
+        _ => Err(RafctlError::AmbiguousSession {
+            id: session_id.to_string(),
+            candidates: matches
+                .iter()
+                .filter_map(|p| p.file_stem().and_then(|s| s.to_str()))
+                .map(|s| s.to_string())
+                .collect(),
+        }),
+    }
+}
+
+fn watch_session_file(
+    path: &PathBuf,
+    show_text: bool,
+    format: OutputFormat,
+) -> Result<(), RafctlError> {
     let mut file = File::open(path).map_err(|e| RafctlError::ConfigRead {
         path: path.clone(),
         source: e,
     })?;
 
-    let mut seen_ids: HashSet<String> = HashSet::new();
-    let initial_pos = read_existing_entries(&mut file, &mut seen_ids)?;
-    file.seek(SeekFrom::Start(initial_pos)).ok();
+    let mut state = TailState::default();
+    read_existing_entries(&mut file, &mut state)?;
 
     let (tx, rx) = channel();
 
@@ -101,39 +145,81 @@ fn watch_session_file(path: &PathBuf) -> Result<(), RafctlError> {
         .watch(path, RecursiveMode::NonRecursive)
         .map_err(|e| RafctlError::ProfileNotFound(format!("Failed to watch file: {}", e)))?;
 
-    watch_loop(&rx, &mut file, &mut seen_ids)?;
+    watch_loop(&rx, &mut file, &mut state, show_text, format)?;
+
+    Ok(())
+}
+
+/// Read position and dedup set for the transcript being tailed.
+#[derive(Debug, Default)]
+struct TailState {
+    /// Byte offset just past the last complete line consumed.
+    pos: u64,
+    seen_ids: HashSet<String>,
+}
 
+fn read_existing_entries(file: &mut File, state: &mut TailState) -> Result<(), RafctlError> {
+    for entry in read_complete_lines(file, state)? {
+        if let Some(id) = extract_tool_id(&entry) {
+            state.seen_ids.insert(id);
+        }
+    }
     Ok(())
 }
 
-fn read_existing_entries(
+/// Parse every complete line after `state.pos` and advance the position.
+///
+/// If the file shrank below the last read position, Claude truncated or
+/// rewrote it: start over from the beginning with an empty dedup set so
+/// entries written after the truncation are not lost.
+fn read_complete_lines(
     file: &mut File,
-    seen_ids: &mut HashSet<String>,
-) -> Result<u64, RafctlError> {
-    let reader = BufReader::new(file.try_clone().unwrap());
-    let mut last_pos = 0u64;
-
-    for line in reader.lines().map_while(Result::ok) {
-        last_pos += line.len() as u64 + 1;
-        if let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) {
-            if let Some(id) = extract_tool_id(&entry) {
-                seen_ids.insert(id);
-            }
+    state: &mut TailState,
+) -> Result<Vec<serde_json::Value>, RafctlError> {
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if len < state.pos {
+        state.pos = 0;
+        state.seen_ids.clear();
+    }
+
+    file.seek(SeekFrom::Start(state.pos)).ok();
+    let mut reader = BufReader::new(&mut *file);
+    let mut entries = Vec::new();
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).unwrap_or(0);
+        // Stop at EOF or a partially written line; it is re-read next time
+        if read == 0 || !line.ends_with('\n') {
+            break;
+        }
+        state.pos += read as u64;
+
+        if let Ok(entry) = serde_json::from_str::<serde_json::Value>(line.trim()) {
+            entries.push(entry);
         }
     }
 
-    Ok(last_pos)
+    Ok(entries)
 }
 
 fn watch_loop(
     rx: &Receiver<Event>,
     file: &mut File,
-    seen_ids: &mut HashSet<String>,
+    state: &mut TailState,
+    show_text: bool,
+    format: OutputFormat,
 ) -> Result<(), RafctlError> {
     loop {
         match rx.recv_timeout(Duration::from_millis(500)) {
             Ok(_event) => {
-                read_new_lines(file, seen_ids)?;
+                for event in read_new_events(file, state, show_text)? {
+                    match format {
+                        OutputFormat::Json => print_json_line(&event),
+                        _ => print_event(&event),
+                    }
+                }
             }
             Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
                 continue;
@@ -146,27 +232,23 @@ fn watch_loop(
     Ok(())
 }
 
-fn read_new_lines(file: &mut File, seen_ids: &mut HashSet<String>) -> Result<(), RafctlError> {
-    let reader = BufReader::new(file.try_clone().unwrap());
-
-    for line in reader.lines().map_while(Result::ok) {
-        if line.is_empty() {
-            continue;
-        }
-
-        if let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) {
-            if let Some(id) = extract_tool_id(&entry) {
-                if seen_ids.contains(&id) {
-                    continue;
-                }
-                seen_ids.insert(id);
+fn read_new_events(
+    file: &mut File,
+    state: &mut TailState,
+    show_text: bool,
+) -> Result<Vec<WatchEvent>, RafctlError> {
+    let mut events = Vec::new();
+
+    for entry in read_complete_lines(file, state)? {
+        if let Some(id) = extract_tool_id(&entry) {
+            if !state.seen_ids.insert(id) {
+                continue;
             }
-
-            print_entry(&entry);
         }
+        events.extend(entry_events(&entry, show_text));
     }
 
-    Ok(())
+    Ok(events)
 }
 
 fn extract_tool_id(entry: &serde_json::Value) -> Option<String> {
@@ -180,34 +262,43 @@ fn extract_tool_id(entry: &serde_json::Value) -> Option<String> {
         .map(|s| s.to_string())
 }
 
-fn print_entry(entry: &serde_json::Value) {
+fn entry_events(entry: &serde_json::Value, show_text: bool) -> Vec<WatchEvent> {
     let timestamp = entry
         .get("timestamp")
         .and_then(|t| t.as_str())
-        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-        .map(|dt| dt.with_timezone(&Local).format("%H:%M:%S").to_string())
-        .unwrap_or_else(|| "??:??:??".to_string());
+        .map(|s| s.to_string());
 
     let entry_type = entry.get("type").and_then(|t| t.as_str()).unwrap_or("");
 
     match entry_type {
-        "user" => {
-            println!("[{}] {} User message", timestamp.dimmed(), "💬".cyan());
-        }
-        "assistant" => {
-            if let Some(content) = entry.get("message").and_then(|m| m.get("content")) {
-                if let Some(blocks) = content.as_array() {
-                    for block in blocks {
-                        print_content_block(&timestamp, block);
-                    }
-                }
-            }
-        }
-        _ => {}
+        "user" => vec![WatchEvent {
+            event_type: "user",
+            timestamp,
+            tool: None,
+            target: None,
+            text: None,
+            is_error: false,
+        }],
+        "assistant" => entry
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array())
+            .map(|blocks| {
+                blocks
+                    .iter()
+                    .filter_map(|block| content_block_event(timestamp.clone(), block, show_text))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => vec![],
     }
 }
 
-fn print_content_block(timestamp: &str, block: &serde_json::Value) {
+fn content_block_event(
+    timestamp: Option<String>,
+    block: &serde_json::Value,
+    show_text: bool,
+) -> Option<WatchEvent> {
     let block_type = block.get("type").and_then(|t| t.as_str()).unwrap_or("");
 
     match block_type {
@@ -216,31 +307,85 @@ fn print_content_block(timestamp: &str, block: &serde_json::Value) {
                 .get("name")
                 .and_then(|n| n.as_str())
                 .unwrap_or("Unknown");
-            let target = extract_target(tool_name, block.get("input"));
-            let icon = tool_icon(tool_name);
+            Some(WatchEvent {
+                event_type: "tool_use",
+                timestamp,
+                target: extract_target(tool_name, block.get("input")),
+                tool: Some(tool_name.to_string()),
+                text: None,
+                is_error: false,
+            })
+        }
+        "tool_result" => Some(WatchEvent {
+            event_type: "tool_result",
+            timestamp,
+            tool: None,
+            target: None,
+            text: None,
+            is_error: block
+                .get("is_error")
+                .and_then(|e| e.as_bool())
+                .unwrap_or(false),
+        }),
+        "text" if show_text => {
+            let line = block
+                .get("text")
+                .and_then(|t| t.as_str())?
+                .lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())?;
+            Some(WatchEvent {
+                event_type: "text",
+                timestamp,
+                tool: None,
+                target: None,
+                text: Some(truncate_str(line, TEXT_SNIPPET_WIDTH)),
+                is_error: false,
+            })
+        }
+        // Text blocks are only shown with --show-text
+        _ => None,
+    }
+}
 
-            let target_display = target.map(|t| format!(" → {}", t)).unwrap_or_default();
+fn print_event(event: &WatchEvent) {
+    let timestamp = event
+        .timestamp
+        .as_deref()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&Local).format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| "??:??:??".to_string());
+
+    match event.event_type {
+        "user" => {
+            println!("[{}] {} User message", timestamp.dimmed(), "💬".cyan());
+        }
+        "tool_use" => {
+            let tool_name = event.tool.as_deref().unwrap_or("Unknown");
+            let target_display = event
+                .target
+                .as_ref()
+                .map(|t| format!(" → {}", t))
+                .unwrap_or_default();
 
             println!(
                 "[{}] {} {}{}",
                 timestamp.dimmed(),
-                icon,
+                tool_icon(tool_name),
                 tool_name.yellow(),
                 target_display.dimmed()
             );
         }
-        "tool_result" => {
-            let is_error = block
-                .get("is_error")
-                .and_then(|e| e.as_bool())
-                .unwrap_or(false);
-
-            if is_error {
-                println!("[{}] {} Tool error", timestamp.dimmed(), "✗".red());
-            }
-        }
         "text" => {
-            // Skip text blocks in live view
+            println!(
+                "[{}] {} {}",
+                timestamp.dimmed(),
+                "💭".dimmed(),
+                event.text.as_deref().unwrap_or_default().dimmed()
+            );
+        }
+        "tool_result" if event.is_error => {
+            println!("[{}] {} Tool error", timestamp.dimmed(), "✗".red());
         }
         _ => {}
     }
@@ -281,15 +426,6 @@ fn truncate_path(path: &str) -> String {
         .to_string()
 }
 
-fn truncate_str(s: &str, max_len: usize) -> String {
-    if s.chars().count() <= max_len {
-        s.to_string()
-    } else {
-        let truncated: String = s.chars().take(max_len.saturating_sub(3)).collect();
-        format!("{}...", truncated)
-    }
-}
-
 fn tool_icon(name: &str) -> &'static str {
     match name {
         "Read" => "📖",
@@ -330,6 +466,97 @@ mod tests {
         assert_eq!(shorten_id("abcdef123456789"), "abcdef12...");
     }
 
+    #[test]
+    fn test_entry_events_assistant_blocks() {
+        let entry = serde_json::json!({
+            "type": "assistant",
+            "timestamp": "2026-01-06T10:00:00Z",
+            "message": {"content": [
+                {"type": "text", "text": "thinking"},
+                {"type": "tool_use", "id": "t1", "name": "Read", "input": {"file_path": "/a/b/main.rs"}},
+                {"type": "tool_result", "tool_use_id": "t1", "is_error": true}
+            ]}
+        });
+
+        let events = entry_events(&entry, false);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "tool_use");
+        assert_eq!(events[0].tool.as_deref(), Some("Read"));
+        assert_eq!(events[0].target.as_deref(), Some("main.rs"));
+        assert!(events[1].is_error);
+
+        let line = serde_json::to_string(&events[0]).unwrap();
+        assert!(line.contains("\"type\":\"tool_use\""));
+    }
+
+    #[test]
+    fn test_entry_events_show_text() {
+        let long_line = "x".repeat(200);
+        let entry = serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [
+                {"type": "text", "text": "\n  Let me check the tests.\nSecond line"},
+                {"type": "text", "text": long_line},
+                {"type": "text", "text": "   "}
+            ]}
+        });
+
+        assert!(entry_events(&entry, false).is_empty());
+
+        let events = entry_events(&entry, true);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "text");
+        assert_eq!(events[0].text.as_deref(), Some("Let me check the tests."));
+        assert_eq!(
+            events[1].text.as_deref().map(|t| t.chars().count()),
+            Some(TEXT_SNIPPET_WIDTH)
+        );
+    }
+
+    #[test]
+    fn test_read_new_events_after_truncation() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let tool_use = |id: &str, name: &str| {
+            format!(
+                r#"{{"type":"assistant","timestamp":"2026-01-06T10:00:00Z","message":{{"content":[{{"type":"tool_use","id":"{}","name":"{}","input":{{}}}}]}}}}"#,
+                id, name
+            )
+        };
+        std::fs::write(
+            &path,
+            format!("{}\n{}\n", tool_use("t1", "Read"), tool_use("t2", "Grep")),
+        )
+        .unwrap();
+
+        let mut file = File::open(&path).unwrap();
+        let mut state = TailState::default();
+        read_existing_entries(&mut file, &mut state).unwrap();
+        assert!(read_new_events(&mut file, &mut state, false)
+            .unwrap()
+            .is_empty());
+
+        // Truncate, then append a new entry that reuses an already-seen id
+        std::fs::write(&path, "").unwrap();
+        let mut writer = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        writeln!(writer, "{}", tool_use("t1", "Bash")).unwrap();
+
+        let events = read_new_events(&mut file, &mut state, false).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tool.as_deref(), Some("Bash"));
+
+        // Regular appends keep working after the reset
+        writeln!(writer, "{}", tool_use("t3", "Edit")).unwrap();
+        let events = read_new_events(&mut file, &mut state, false).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tool.as_deref(), Some("Edit"));
+    }
+
     #[test]
     fn test_truncate_str_watch() {
         assert_eq!(truncate_str("hello", 10), "hello");
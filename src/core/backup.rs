@@ -0,0 +1,408 @@
+//! Whole-config archives for `rafctl config backup` / `restore`.
+//!
+//! A backup is a plain tar of the rafctl config dir (minus the cache and the
+//! OAuth lock) with a `rafctl-backup.json` manifest as its first entry.
+//! Keyring secrets live outside the config dir, so the manifest records which
+//! profiles will need to be re-authenticated after a restore.
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::config::{load_global_config, save_global_config, GlobalConfig};
+use crate::core::constants::{CACHE_DIR, GLOBAL_CONFIG_FILE, OAUTH_LOCK_FILE};
+use crate::core::profile::{
+    get_config_dir, get_profiles_dir, list_profiles, load_profile, ToolType,
+};
+use crate::error::RafctlError;
+
+/// Current backup format version
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Manifest entry name inside the archive
+pub const BACKUP_MANIFEST_FILE: &str = "rafctl-backup.json";
+
+/// Describes what a backup archive contains.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub profiles: Vec<String>,
+    /// Profiles whose credentials are kept in the keyring and are not archived
+    pub needs_reauth: Vec<String>,
+}
+
+/// Outcome of `restore_backup`.
+#[derive(Debug)]
+pub struct RestoreSummary {
+    pub manifest: BackupManifest,
+    pub restored: Vec<String>,
+    /// Profiles left untouched because they already existed (merge mode only)
+    pub skipped: Vec<String>,
+}
+
+fn read_error(path: &Path, e: std::io::Error) -> RafctlError {
+    RafctlError::ConfigRead {
+        path: path.to_path_buf(),
+        source: e,
+    }
+}
+
+fn write_error(path: &Path, e: std::io::Error) -> RafctlError {
+    RafctlError::ConfigWrite {
+        path: path.to_path_buf(),
+        source: e,
+    }
+}
+
+/// Write every file under the config dir into a tar archive at `file`.
+pub fn create_backup(file: &Path) -> Result<BackupManifest, RafctlError> {
+    let config_dir = get_config_dir()?;
+    if !config_dir.exists() {
+        return Err(read_error(
+            &config_dir,
+            std::io::Error::new(std::io::ErrorKind::NotFound, "nothing to back up"),
+        ));
+    }
+
+    let profiles = list_profiles()?;
+    // Claude keeps OAuth tokens and API keys in the keyring; Codex and Gemini
+    // store their logins inside the profile dir, which is archived
+    let needs_reauth = profiles
+        .iter()
+        .filter(|name| load_profile(name).is_ok_and(|p| p.tool == ToolType::Claude))
+        .cloned()
+        .collect();
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: Utc::now(),
+        profiles,
+        needs_reauth,
+    };
+
+    let out = create_private(file).map_err(|e| write_error(file, e))?;
+    let mut builder = tar::Builder::new(out);
+    builder.follow_symlinks(false);
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| write_error(file, std::io::Error::other(e)))?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(manifest.created_at.timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, BACKUP_MANIFEST_FILE, manifest_json.as_slice())
+        .map_err(|e| write_error(file, e))?;
+
+    let mut entries: Vec<_> = fs::read_dir(&config_dir)
+        .map_err(|e| read_error(&config_dir, e))?
+        .flatten()
+        .collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name();
+        if name == CACHE_DIR || name == OAUTH_LOCK_FILE {
+            continue;
+        }
+        let path = entry.path();
+        let result = if path.is_dir() {
+            builder.append_dir_all(&name, &path)
+        } else {
+            builder.append_path_with_name(&path, &name)
+        };
+        result.map_err(|e| read_error(&path, e))?;
+    }
+
+    builder.finish().map_err(|e| write_error(file, e))?;
+    Ok(manifest)
+}
+
+/// Create (or truncate) a file readable only by the user, for archives and
+/// bundles that hold credentials.
+pub fn create_private(file: &Path) -> std::io::Result<File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let out = options.open(file)?;
+        // `mode` only applies to newly created files
+        out.set_permissions(fs::Permissions::from_mode(0o600))?;
+        Ok(out)
+    }
+    #[cfg(not(unix))]
+    options.open(file)
+}
+
+/// Read only the manifest of a backup archive.
+pub fn read_backup_manifest(file: &Path) -> Result<BackupManifest, RafctlError> {
+    let input = File::open(file).map_err(|e| read_error(file, e))?;
+    let mut archive = tar::Archive::new(input);
+
+    for entry in archive.entries().map_err(|e| read_error(file, e))? {
+        let mut entry = entry.map_err(|e| read_error(file, e))?;
+        if entry.path().map_err(|e| read_error(file, e))?.as_ref()
+            == Path::new(BACKUP_MANIFEST_FILE)
+        {
+            return parse_manifest(file, &mut entry);
+        }
+    }
+
+    Err(read_error(
+        file,
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "not a rafctl backup (manifest missing)",
+        ),
+    ))
+}
+
+fn parse_manifest(file: &Path, entry: &mut impl Read) -> Result<BackupManifest, RafctlError> {
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| read_error(file, e))?;
+    let manifest: BackupManifest = serde_json::from_str(&content).map_err(|e| {
+        read_error(
+            file,
+            std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        )
+    })?;
+
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(read_error(
+            file,
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "backup format version {} is newer than supported version {}",
+                    manifest.format_version, BACKUP_FORMAT_VERSION
+                ),
+            ),
+        ));
+    }
+    Ok(manifest)
+}
+
+/// Name of the profile an archive path belongs to (`profiles/<name>/...`).
+fn profile_of(path: &Path) -> Option<String> {
+    let mut components = path.components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(dir)), Some(Component::Normal(name))) if dir == "profiles" => {
+            name.to_str().map(str::to_string)
+        }
+        _ => None,
+    }
+}
+
+/// Unpack a backup into the config dir.
+///
+/// Without `merge`, existing profiles and `config.yaml` are replaced by the
+/// archive's. With `merge`, profiles and files that already exist locally are
+/// kept and `config.yaml` only gains the settings it does not have yet.
+pub fn restore_backup(file: &Path, merge: bool) -> Result<RestoreSummary, RafctlError> {
+    let manifest = read_backup_manifest(file)?;
+    let config_dir = get_config_dir()?;
+    fs::create_dir_all(&config_dir).map_err(|e| write_error(&config_dir, e))?;
+
+    if merge {
+        return merge_backup(file, &config_dir, manifest);
+    }
+
+    // Unpack everything aside first: a bad or truncated archive must leave the
+    // current config untouched
+    let staging = sibling_dir(&config_dir, "restore");
+    let result = unpack_all(file, &staging).and_then(|()| swap_in(&staging, &config_dir));
+    let _ = fs::remove_dir_all(&staging);
+    result?;
+
+    Ok(RestoreSummary {
+        restored: manifest.profiles.clone(),
+        manifest,
+        skipped: Vec::new(),
+    })
+}
+
+/// Scratch dir next to the config dir, so renames stay on one filesystem
+fn sibling_dir(config_dir: &Path, purpose: &str) -> PathBuf {
+    let name = config_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    config_dir.with_file_name(format!("{}.{}-{}", name, purpose, std::process::id()))
+}
+
+fn unpack_all(file: &Path, dest: &Path) -> Result<(), RafctlError> {
+    if dest.exists() {
+        fs::remove_dir_all(dest).map_err(|e| write_error(dest, e))?;
+    }
+    fs::create_dir_all(dest).map_err(|e| write_error(dest, e))?;
+
+    let input = File::open(file).map_err(|e| read_error(file, e))?;
+    let mut archive = tar::Archive::new(input);
+    for entry in archive.entries().map_err(|e| read_error(file, e))? {
+        let mut entry = entry.map_err(|e| read_error(file, e))?;
+        let path: PathBuf = entry.path().map_err(|e| read_error(file, e))?.into_owned();
+        if path == Path::new(BACKUP_MANIFEST_FILE) {
+            continue;
+        }
+        entry.unpack_in(dest).map_err(|e| read_error(file, e))?;
+    }
+    Ok(())
+}
+
+/// Replace the config dir's profiles, `config.yaml` and every other top-level
+/// entry the archive has with the unpacked ones. The current entries are
+/// moved aside first and put back if a rename fails.
+fn swap_in(staging: &Path, config_dir: &Path) -> Result<(), RafctlError> {
+    let mut names: Vec<OsString> = fs::read_dir(staging)
+        .map_err(|e| read_error(staging, e))?
+        .flatten()
+        .map(|entry| entry.file_name())
+        .collect();
+    let profiles_dir = get_profiles_dir()?;
+    for always in [
+        profiles_dir.file_name().unwrap_or_default(),
+        GLOBAL_CONFIG_FILE.as_ref(),
+    ] {
+        if !names.iter().any(|name| name == always) {
+            names.push(always.to_os_string());
+        }
+    }
+
+    let previous = sibling_dir(config_dir, "previous");
+    fs::create_dir_all(&previous).map_err(|e| write_error(&previous, e))?;
+
+    let mut moved_aside = Vec::new();
+    let mut swap = || -> std::io::Result<()> {
+        for name in &names {
+            let current = config_dir.join(name);
+            if current.symlink_metadata().is_ok() {
+                fs::rename(&current, previous.join(name))?;
+                moved_aside.push(name.clone());
+            }
+        }
+        for name in &names {
+            let staged = staging.join(name);
+            if staged.symlink_metadata().is_ok() {
+                fs::rename(&staged, config_dir.join(name))?;
+            }
+        }
+        Ok(())
+    };
+    let result = swap();
+
+    if let Err(e) = result {
+        for name in &names {
+            let _ = remove_entry(&config_dir.join(name));
+        }
+        for name in &moved_aside {
+            let _ = fs::rename(previous.join(name), config_dir.join(name));
+        }
+        let _ = fs::remove_dir_all(&previous);
+        return Err(write_error(config_dir, e));
+    }
+
+    let _ = fs::remove_dir_all(&previous);
+    Ok(())
+}
+
+fn remove_entry(path: &Path) -> std::io::Result<()> {
+    match path.symlink_metadata() {
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Unpack only what the config dir does not have yet; see [`restore_backup`].
+fn merge_backup(
+    file: &Path,
+    config_dir: &Path,
+    manifest: BackupManifest,
+) -> Result<RestoreSummary, RafctlError> {
+    let existing: HashSet<String> = list_profiles()?.into_iter().collect();
+
+    let input = File::open(file).map_err(|e| read_error(file, e))?;
+    let mut archive = tar::Archive::new(input);
+    let mut skipped: HashSet<String> = HashSet::new();
+
+    for entry in archive.entries().map_err(|e| read_error(file, e))? {
+        let mut entry = entry.map_err(|e| read_error(file, e))?;
+        let path: PathBuf = entry.path().map_err(|e| read_error(file, e))?.into_owned();
+
+        if path == Path::new(BACKUP_MANIFEST_FILE) {
+            continue;
+        }
+
+        if let Some(name) = profile_of(&path) {
+            if existing.contains(&name) {
+                skipped.insert(name);
+                continue;
+            }
+        } else if path == Path::new(GLOBAL_CONFIG_FILE) {
+            let mut content = String::new();
+            entry
+                .read_to_string(&mut content)
+                .map_err(|e| read_error(file, e))?;
+            let mut config: GlobalConfig = serde_yaml::from_str(&content).map_err(|e| {
+                read_error(
+                    file,
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+                )
+            })?;
+            config.merge(load_global_config()?);
+            save_global_config(&config)?;
+            continue;
+        } else if config_dir.join(&path).is_file() {
+            continue;
+        }
+
+        entry
+            .unpack_in(config_dir)
+            .map_err(|e| write_error(&config_dir.join(&path), e))?;
+    }
+
+    let restored = manifest
+        .profiles
+        .iter()
+        .filter(|name| !skipped.contains(*name))
+        .cloned()
+        .collect();
+    let mut skipped: Vec<String> = skipped.into_iter().collect();
+    skipped.sort();
+
+    Ok(RestoreSummary {
+        manifest,
+        restored,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_of() {
+        assert_eq!(
+            profile_of(Path::new("profiles/work/meta.yaml")),
+            Some("work".to_string())
+        );
+        assert_eq!(
+            profile_of(Path::new("profiles/work")),
+            Some("work".to_string())
+        );
+        assert_eq!(profile_of(Path::new("profiles")), None);
+        assert_eq!(profile_of(Path::new("config.yaml")), None);
+        assert_eq!(profile_of(Path::new("snapshots/work/x.json")), None);
+    }
+}
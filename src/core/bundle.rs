@@ -0,0 +1,281 @@
+//! Portable profile bundles for `rafctl profile export` / `import`.
+//!
+//! A bundle is a single JSON document, so it can be copied between machines
+//! without extra tooling.
+
+use std::fs;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::credentials::{self, CredentialType};
+use crate::core::profile::{
+    ensure_dir_with_permissions, get_profile_dir, load_profile, profile_exists, save_profile,
+    validate_profile_name, Profile,
+};
+use crate::error::RafctlError;
+
+/// Current bundle format version
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Settings file copied alongside the profile metadata
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Exported profile bundle.
+///
+/// ```json
+/// {
+///   "format_version": 1,
+///   "exported_at": "2026-01-09T12:00:00Z",
+///   "profile": { "name": "work", "tool": "claude", "auth_mode": "oauth", ... },
+///   "settings": { "statusLine": { "command": "rafctl-hud" } },
+///   "secrets": { "oauth_token": "...", "api_key": "...", "extra_api_keys": ["..."] }
+/// }
+/// ```
+///
+/// `profile` mirrors `meta.yaml`, `settings` is the tool's `settings.json`
+/// (if present) and `secrets` is only set with `--include-secrets`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfileBundle {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub profile: Profile,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<BundleSecrets>,
+}
+
+/// Credentials carried in a bundle (plaintext — handle the file accordingly)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BundleSecrets {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Keys added with `auth add-key`, in keyring order after `api_key`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_api_keys: Vec<String>,
+}
+
+impl BundleSecrets {
+    pub fn is_empty(&self) -> bool {
+        self.oauth_token.is_none() && self.api_key.is_none()
+    }
+
+    /// Number of API keys the bundle restores
+    fn api_key_count(&self) -> usize {
+        if self.api_key.is_some() {
+            1 + self.extra_api_keys.len()
+        } else {
+            0
+        }
+    }
+}
+
+/// Point `active_api_key` back at the first key if the key it names is not
+/// stored, so `rafctl run` does not fail with a missing key.
+fn clamp_active_api_key(profile: &mut Profile, stored_keys: usize) {
+    if profile
+        .active_api_key
+        .is_some_and(|index| index >= stored_keys)
+    {
+        profile.active_api_key = None;
+    }
+}
+
+/// Build a bundle for an existing profile
+#[allow(deprecated)]
+pub fn build_bundle(name: &str, include_secrets: bool) -> Result<ProfileBundle, RafctlError> {
+    let mut profile = load_profile(name)?;
+    let settings_path = profile
+        .tool
+        .config_dir_for_profile(name)?
+        .join(SETTINGS_FILE);
+
+    let settings = if settings_path.exists() {
+        let content = fs::read_to_string(&settings_path).map_err(|e| RafctlError::ConfigRead {
+            path: settings_path.clone(),
+            source: e,
+        })?;
+        Some(
+            serde_json::from_str(&content).map_err(|e| RafctlError::ConfigRead {
+                path: settings_path,
+                source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+            })?,
+        )
+    } else {
+        None
+    };
+
+    // Never leak the legacy plaintext key through `profile`; it moves to `secrets`
+    let legacy_api_key = profile.api_key.take();
+
+    let secrets = if include_secrets {
+        let (api_key, extra_api_keys) = match legacy_api_key {
+            Some(key) => (Some(key), Vec::new()),
+            None => {
+                let count = credentials::count_credentials(name, CredentialType::ApiKey)?;
+                let mut keys = Vec::with_capacity(count);
+                for index in 0..count {
+                    if let Some(key) =
+                        credentials::get_indexed_credential(name, CredentialType::ApiKey, index)?
+                    {
+                        keys.push(key);
+                    }
+                }
+                let mut keys = keys.into_iter();
+                (keys.next(), keys.collect())
+            }
+        };
+        let secrets = BundleSecrets {
+            oauth_token: credentials::get_credential(name, CredentialType::OAuthToken)?,
+            api_key,
+            extra_api_keys,
+        };
+        (!secrets.is_empty()).then_some(secrets)
+    } else {
+        None
+    };
+
+    Ok(ProfileBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        profile,
+        settings,
+        secrets,
+    })
+}
+
+/// Recreate a profile from a bundle, storing any secrets in the keyring.
+///
+/// Run hooks are shell commands, so a bundle's `pre_run`/`post_run` are only
+/// kept with `with_hooks`.
+pub fn restore_bundle(
+    bundle: ProfileBundle,
+    force: bool,
+    with_hooks: bool,
+) -> Result<Profile, RafctlError> {
+    if bundle.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(RafctlError::ConfigRead {
+            path: "bundle".into(),
+            source: std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "bundle format version {} is newer than supported version {}",
+                    bundle.format_version, BUNDLE_FORMAT_VERSION
+                ),
+            ),
+        });
+    }
+
+    let mut profile = bundle.profile;
+    validate_profile_name(&profile.name)?;
+    profile.name = profile.name.to_lowercase();
+    if !with_hooks {
+        profile.pre_run = None;
+        profile.post_run = None;
+    }
+    clamp_active_api_key(
+        &mut profile,
+        bundle
+            .secrets
+            .as_ref()
+            .map_or(0, BundleSecrets::api_key_count),
+    );
+
+    if profile_exists(&profile.name)? && !force {
+        return Err(RafctlError::ProfileAlreadyExists(profile.name));
+    }
+
+    ensure_dir_with_permissions(&get_profile_dir(&profile.name)?)?;
+    save_profile(&profile)?;
+
+    if let Some(settings) = &bundle.settings {
+        let config_dir = profile.tool.config_dir_for_profile(&profile.name)?;
+        ensure_dir_with_permissions(&config_dir)?;
+        let settings_path = config_dir.join(SETTINGS_FILE);
+        let content =
+            serde_json::to_string_pretty(settings).map_err(|e| RafctlError::ConfigWrite {
+                path: settings_path.clone(),
+                source: std::io::Error::other(e),
+            })?;
+        fs::write(&settings_path, content).map_err(|e| RafctlError::ConfigWrite {
+            path: settings_path,
+            source: e,
+        })?;
+    }
+
+    if let Some(secrets) = &bundle.secrets {
+        if let Some(token) = &secrets.oauth_token {
+            credentials::store_credential(&profile.name, CredentialType::OAuthToken, token)?;
+        }
+        if let Some(key) = &secrets.api_key {
+            // Replace every key, so an overwritten profile keeps none of its old ones
+            credentials::delete_all_credentials(&profile.name, CredentialType::ApiKey)?;
+            credentials::store_credential(&profile.name, CredentialType::ApiKey, key)?;
+            for (offset, key) in secrets.extra_api_keys.iter().enumerate() {
+                credentials::store_indexed_credential(
+                    &profile.name,
+                    CredentialType::ApiKey,
+                    offset + 1,
+                    key,
+                )?;
+            }
+        }
+    }
+
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::profile::ToolType;
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let bundle = ProfileBundle {
+            format_version: BUNDLE_FORMAT_VERSION,
+            exported_at: Utc::now(),
+            profile: Profile::new("work".to_string(), ToolType::Claude),
+            settings: Some(serde_json::json!({"statusLine": {"command": "rafctl-hud"}})),
+            secrets: None,
+        };
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains("secrets"));
+
+        let restored: ProfileBundle = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.profile.name, "work");
+        assert_eq!(restored.profile.tool, ToolType::Claude);
+        assert!(restored.settings.is_some());
+        assert!(restored.secrets.is_none());
+    }
+
+    #[test]
+    fn test_bundle_secrets_is_empty() {
+        assert!(BundleSecrets::default().is_empty());
+        let secrets = BundleSecrets {
+            oauth_token: None,
+            api_key: Some("sk-ant-api-test".to_string()),
+            extra_api_keys: vec!["sk-ant-api-second".to_string()],
+        };
+        assert!(!secrets.is_empty());
+        assert_eq!(secrets.api_key_count(), 2);
+    }
+
+    #[test]
+    fn test_clamp_active_api_key() {
+        let mut profile = Profile::new("work".to_string(), ToolType::Claude);
+        profile.active_api_key = Some(1);
+
+        clamp_active_api_key(&mut profile, 2);
+        assert_eq!(profile.active_api_key, Some(1));
+
+        // Exported without secrets, or by a version that only kept key 0
+        clamp_active_api_key(&mut profile, 1);
+        assert_eq!(profile.active_api_key, None);
+    }
+}
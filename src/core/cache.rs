@@ -0,0 +1,153 @@
+//! Housekeeping for `~/.rafctl/cache`.
+//!
+//! Cache files are named `<kind>-<profile>.json` (e.g. `quota-work.json`).
+//! Entries for profiles that no longer exist are always removed. Stale entries
+//! are deliberately kept around for a while: `quota --watch` shows the last
+//! known usage when a fetch fails, so only files untouched for
+//! `CACHE_MAX_AGE_DAYS` count as expired.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::core::constants::{CACHE_DIR, CACHE_MAX_AGE_DAYS, QUOTA_CACHE_PREFIX};
+use crate::core::profile::{get_config_dir, list_profiles};
+use crate::error::RafctlError;
+
+/// Why a cache file was removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneReason {
+    /// Belongs to a profile that no longer exists
+    Orphaned,
+    /// Not updated for longer than the maximum age
+    Expired,
+}
+
+#[derive(Debug)]
+pub struct PrunedFile {
+    pub path: PathBuf,
+    pub reason: PruneReason,
+}
+
+pub fn cache_dir() -> Result<PathBuf, RafctlError> {
+    Ok(get_config_dir()?.join(CACHE_DIR))
+}
+
+/// Profile a cache file belongs to, or `None` for files rafctl did not write.
+fn cache_entry_profile(file_name: &str) -> Option<&str> {
+    file_name
+        .strip_prefix(QUOTA_CACHE_PREFIX)?
+        .strip_suffix(".json")
+        .filter(|name| !name.is_empty())
+}
+
+/// Remove orphaned and expired cache files and report what went.
+pub fn prune_cache() -> Result<Vec<PrunedFile>, RafctlError> {
+    let profiles: HashSet<String> = list_profiles()?.into_iter().collect();
+    let max_age = Duration::from_secs(CACHE_MAX_AGE_DAYS * 24 * 60 * 60);
+    prune_cache_dir(&cache_dir()?, &profiles, max_age, SystemTime::now())
+}
+
+fn prune_cache_dir(
+    dir: &Path,
+    profiles: &HashSet<String>,
+    max_age: Duration,
+    now: SystemTime,
+) -> Result<Vec<PrunedFile>, RafctlError> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut pruned = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(profile) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(cache_entry_profile)
+        else {
+            continue;
+        };
+
+        let reason = if !profiles.contains(profile) {
+            PruneReason::Orphaned
+        } else {
+            let age = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok());
+            match age {
+                Some(age) if age > max_age => PruneReason::Expired,
+                _ => continue,
+            }
+        };
+
+        fs::remove_file(&path).map_err(|e| RafctlError::ConfigWrite {
+            path: path.clone(),
+            source: e,
+        })?;
+        pruned.push(PrunedFile { path, reason });
+    }
+
+    pruned.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(pruned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_entry_profile() {
+        assert_eq!(cache_entry_profile("quota-work.json"), Some("work"));
+        assert_eq!(cache_entry_profile("quota-my-work.json"), Some("my-work"));
+        assert_eq!(cache_entry_profile("quota-.json"), None);
+        assert_eq!(cache_entry_profile("notes.txt"), None);
+    }
+
+    #[test]
+    fn test_prune_cache_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "quota-work.json",
+            "quota-old.json",
+            "quota-gone.json",
+            "keep.txt",
+        ] {
+            fs::write(dir.path().join(name), "{}").unwrap();
+        }
+        let profiles: HashSet<String> = ["work", "old"].map(String::from).into();
+
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        let old = fs::File::options()
+            .write(true)
+            .open(dir.path().join("quota-old.json"))
+            .unwrap();
+        old.set_modified(SystemTime::now() - week * 2).unwrap();
+
+        let pruned = prune_cache_dir(dir.path(), &profiles, week, SystemTime::now()).unwrap();
+        let summary: Vec<(&str, PruneReason)> = pruned
+            .iter()
+            .map(|p| (p.path.file_name().unwrap().to_str().unwrap(), p.reason))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("quota-gone.json", PruneReason::Orphaned),
+                ("quota-old.json", PruneReason::Expired),
+            ]
+        );
+        assert!(dir.path().join("quota-work.json").exists());
+        assert!(dir.path().join("keep.txt").exists());
+
+        // A missing cache dir is simply empty
+        let missing = dir.path().join("missing");
+        assert!(
+            prune_cache_dir(&missing, &profiles, week, SystemTime::now())
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -12,6 +12,62 @@ pub struct GlobalConfig {
     pub default_profile: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_used_profile: Option<String>,
+    /// Default profile before the last change, for `rafctl switch -`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_default_profile: Option<String>,
+}
+
+impl GlobalConfig {
+    /// The settings worth carrying to another machine. `last_used_profile`
+    /// and `previous_default_profile` are local state, so they are left out.
+    pub fn portable(&self) -> GlobalConfig {
+        GlobalConfig {
+            default_profile: self.default_profile.clone(),
+            last_used_profile: None,
+            previous_default_profile: None,
+        }
+    }
+
+    /// Make `profile_name` the default, remembering the one it replaces.
+    pub fn set_default_profile(&mut self, profile_name: &str) {
+        let name = profile_name.to_lowercase();
+        if self.default_profile.as_deref() == Some(name.as_str()) {
+            return;
+        }
+        if let Some(old) = self.default_profile.replace(name) {
+            self.previous_default_profile = Some(old);
+        }
+    }
+
+    /// Drop every reference to a removed profile. Returns whether any changed.
+    pub fn forget_profile(&mut self, profile_name: &str) -> bool {
+        let mut changed = false;
+        for slot in [
+            &mut self.default_profile,
+            &mut self.last_used_profile,
+            &mut self.previous_default_profile,
+        ] {
+            if slot.as_deref() == Some(profile_name) {
+                *slot = None;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Overlay the fields set in `other`; fields it leaves unset keep their
+    /// current value.
+    pub fn merge(&mut self, other: GlobalConfig) {
+        if other.default_profile.is_some() {
+            self.default_profile = other.default_profile;
+        }
+        if other.last_used_profile.is_some() {
+            self.last_used_profile = other.last_used_profile;
+        }
+        if other.previous_default_profile.is_some() {
+            self.previous_default_profile = other.previous_default_profile;
+        }
+    }
 }
 
 fn get_config_path() -> Result<PathBuf, RafctlError> {
@@ -54,12 +110,27 @@ pub fn save_global_config(config: &GlobalConfig) -> Result<(), RafctlError> {
     atomic_write(&config_path, &yaml)
 }
 
+/// Overwrite `config.yaml` with defaults. Profiles and credentials are untouched.
+pub fn reset_global_config() -> Result<(), RafctlError> {
+    save_global_config(&GlobalConfig::default())
+}
+
 pub fn set_last_used_profile(profile_name: &str) -> Result<(), RafctlError> {
     let mut config = load_global_config()?;
     config.last_used_profile = Some(profile_name.to_lowercase());
     save_global_config(&config)
 }
 
+/// The profile recorded by the most recent `rafctl run`, ignoring the default.
+pub fn get_last_used_profile() -> Result<Option<String>, RafctlError> {
+    Ok(load_global_config()?.last_used_profile)
+}
+
+/// Default profile before the last `switch` / `config set-default`.
+pub fn get_previous_default_profile() -> Result<Option<String>, RafctlError> {
+    Ok(load_global_config()?.previous_default_profile)
+}
+
 pub fn get_default_profile() -> Result<Option<String>, RafctlError> {
     if let Ok(env_profile) = std::env::var("RAFCTL_DEFAULT_PROFILE") {
         if !env_profile.is_empty() {
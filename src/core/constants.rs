@@ -13,6 +13,12 @@ pub const RAFCTL_DIR_NAME: &str = ".rafctl";
 /// Profile metadata filename
 pub const PROFILE_META_FILE: &str = "meta.yaml";
 
+/// Per-profile launch history filename (JSON lines)
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+/// Per-profile ledger of session costs recorded by `run --capture-cost` (JSON lines)
+pub const COSTS_FILE: &str = "costs.jsonl";
+
 /// Global config filename
 pub const GLOBAL_CONFIG_FILE: &str = "config.yaml";
 
@@ -22,6 +28,24 @@ pub const STATS_CACHE_FILE: &str = "stats-cache.json";
 /// Transcripts directory name
 pub const TRANSCRIPTS_DIR: &str = "transcripts";
 
+/// Cache directory name (under the rafctl config dir)
+pub const CACHE_DIR: &str = "cache";
+
+/// Model pricing overrides for `rafctl analytics --cost` (under the rafctl config dir)
+pub const PRICING_FILE: &str = "pricing.toml";
+
+/// HUD display settings (under the rafctl config dir)
+pub const HUD_CONFIG_FILE: &str = "hud.toml";
+
+/// Lock file guarding single-instance OAuth runs (under the rafctl config dir)
+pub const OAUTH_LOCK_FILE: &str = "oauth.lock";
+
+/// Directory (inside the config dir) holding `run --snapshot-config` snapshots
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// Tool settings file copied into config snapshots
+pub const TOOL_SETTINGS_FILE: &str = "settings.json";
+
 // =============================================================================
 // Environment Variables
 // =============================================================================
@@ -38,9 +62,36 @@ pub const ENV_RAFCTL_PROFILE: &str = "RAFCTL_PROFILE";
 /// Active profile tool type (set when running tools)
 pub const ENV_RAFCTL_PROFILE_TOOL: &str = "RAFCTL_PROFILE_TOOL";
 
+/// Active profile auth mode: oauth, api-key or tool-login (set when running tools)
+pub const ENV_RAFCTL_AUTH_MODE: &str = "RAFCTL_AUTH_MODE";
+
+/// Active profile directory (set when running tools)
+pub const ENV_RAFCTL_PROFILE_DIR: &str = "RAFCTL_PROFILE_DIR";
+
 /// rafctl version (set when running tools)
 pub const ENV_RAFCTL_VERSION: &str = "RAFCTL_VERSION";
 
+/// Tool exit code (set for the profile's post_run hook)
+pub const ENV_RAFCTL_EXIT_CODE: &str = "RAFCTL_EXIT_CODE";
+
+/// HUD statusline segment list override (comma-separated)
+pub const ENV_RAFCTL_HUD_SEGMENTS: &str = "RAFCTL_HUD_SEGMENTS";
+
+/// HUD context percentage at which the bar turns yellow (default 70)
+pub const ENV_RAFCTL_HUD_YELLOW: &str = "RAFCTL_HUD_YELLOW";
+
+/// HUD context percentage at which the bar turns red (default 85)
+pub const ENV_RAFCTL_HUD_RED: &str = "RAFCTL_HUD_RED";
+
+/// Tokens the HUD reserves for auto-compaction when computing context usage (default 45000)
+pub const ENV_RAFCTL_HUD_BUFFER: &str = "RAFCTL_HUD_BUFFER";
+
+/// HUD session cost (USD) at which the cost segment turns yellow (default 5)
+pub const ENV_RAFCTL_HUD_COST_YELLOW: &str = "RAFCTL_HUD_COST_YELLOW";
+
+/// HUD session cost (USD) at which the cost segment turns red (default 20)
+pub const ENV_RAFCTL_HUD_COST_RED: &str = "RAFCTL_HUD_COST_RED";
+
 /// Anthropic API key environment variable
 pub const ENV_ANTHROPIC_API_KEY: &str = "ANTHROPIC_API_KEY";
 
@@ -50,6 +101,120 @@ pub const ENV_CLAUDE_CONFIG_DIR: &str = "CLAUDE_CONFIG_DIR";
 /// Codex home environment variable
 pub const ENV_CODEX_HOME: &str = "CODEX_HOME";
 
+/// Gemini CLI home environment variable
+pub const ENV_GEMINI_CLI_HOME: &str = "GEMINI_CLI_HOME";
+
+/// Disables colored output when set (https://no-color.org)
+pub const ENV_NO_COLOR: &str = "NO_COLOR";
+
+/// An environment variable rafctl reads or sets, for `rafctl config env`.
+#[derive(Debug, Clone, Copy)]
+pub struct EnvVarInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Value must be masked when displayed
+    pub secret: bool,
+}
+
+/// All environment variables recognized by rafctl
+pub const RECOGNIZED_ENV_VARS: &[EnvVarInfo] = &[
+    EnvVarInfo {
+        name: ENV_RAFCTL_CONFIG_DIR,
+        description: "Override the rafctl config directory (default: ~/.rafctl)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_DEFAULT_PROFILE,
+        description: "Profile to use when none is given (overrides config.yaml)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_PROFILE,
+        description: "Active profile name (set by rafctl run)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_PROFILE_TOOL,
+        description: "Active profile tool type (set by rafctl run)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_AUTH_MODE,
+        description: "Active profile auth mode: oauth, api-key or tool-login (set by rafctl run)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_PROFILE_DIR,
+        description: "Active profile directory (set by rafctl run)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_VERSION,
+        description: "rafctl version (set by rafctl run)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_EXIT_CODE,
+        description: "Tool exit code (set for post_run hooks)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_HUD_SEGMENTS,
+        description: "Comma-separated HUD segments to show, e.g. profile,context,git",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_HUD_YELLOW,
+        description: "HUD context % where the bar turns yellow (default 70)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_HUD_RED,
+        description: "HUD context % where the bar turns red (default 85)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_HUD_BUFFER,
+        description: "Tokens the HUD counts as reserved for auto-compaction (default 45000)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_HUD_COST_YELLOW,
+        description: "HUD session cost in USD where the cost segment turns yellow (default 5)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_RAFCTL_HUD_COST_RED,
+        description: "HUD session cost in USD where the cost segment turns red (default 20)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_NO_COLOR,
+        description: "Disable colored output",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_ANTHROPIC_API_KEY,
+        description: "Anthropic API key (set by rafctl run for api-key profiles)",
+        secret: true,
+    },
+    EnvVarInfo {
+        name: ENV_CLAUDE_CONFIG_DIR,
+        description: "Claude Code config directory (set by rafctl run)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_CODEX_HOME,
+        description: "Codex CLI home directory (set by rafctl run)",
+        secret: false,
+    },
+    EnvVarInfo {
+        name: ENV_GEMINI_CLI_HOME,
+        description: "Gemini CLI home directory (set by rafctl run)",
+        secret: false,
+    },
+];
+
 // =============================================================================
 // Keychain / Credentials
 // =============================================================================
@@ -67,9 +232,41 @@ pub const CLAUDE_KEYCHAIN_SERVICE: &str = "claude.ai";
 /// Anthropic OAuth usage API endpoint
 pub const ANTHROPIC_USAGE_API: &str = "https://api.anthropic.com/api/oauth/usage";
 
+/// Anthropic models endpoint, used as a lightweight API key check
+pub const ANTHROPIC_MODELS_API: &str = "https://api.anthropic.com/v1/models?limit=1";
+
+/// Anthropic API version header value
+pub const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+
 /// API request timeout in seconds
 pub const API_TIMEOUT_SECS: u64 = 30;
 
+/// How long a cached quota response is considered fresh
+pub const QUOTA_CACHE_TTL_SECS: i64 = 300;
+
+/// File name prefix of cached quota responses (`quota-<profile>.json`)
+pub const QUOTA_CACHE_PREFIX: &str = "quota-";
+
+/// Cache files untouched for this many days are removed by `config prune-cache`
+pub const CACHE_MAX_AGE_DAYS: u64 = 30;
+
+/// 5-hour utilization (percent) at which a profile counts as exhausted
+pub const QUOTA_EXHAUSTED_THRESHOLD: f64 = 90.0;
+
+/// 5-hour utilization (percent) at which the profile is rate-limited
+pub const QUOTA_RATE_LIMIT_THRESHOLD: f64 = 100.0;
+
+/// Launches kept in each profile's history file
+pub const HISTORY_MAX_ENTRIES: usize = 500;
+
+/// Exit code when `rafctl run --timeout-idle` stops an idle session
+/// (matches coreutils `timeout`)
+pub const IDLE_TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// Exit code when `rafctl quota --exit-on-error` could not fetch some quota
+/// (distinct from ordinary command errors, which exit with 1)
+pub const QUOTA_FETCH_FAILED_EXIT_CODE: i32 = 2;
+
 // =============================================================================
 // Tool Commands
 // =============================================================================
@@ -80,6 +277,9 @@ pub const CLAUDE_COMMAND: &str = "claude";
 /// Codex CLI command name
 pub const CODEX_COMMAND: &str = "codex";
 
+/// Gemini CLI command name
+pub const GEMINI_COMMAND: &str = "gemini";
+
 // =============================================================================
 // Reserved Names
 // =============================================================================
@@ -107,4 +307,12 @@ mod tests {
     fn test_reserved_names_not_empty() {
         assert!(!RESERVED_PROFILE_NAMES.is_empty());
     }
+
+    #[test]
+    fn test_recognized_env_vars_unique() {
+        let mut names: Vec<_> = RECOGNIZED_ENV_VARS.iter().map(|v| v.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), RECOGNIZED_ENV_VARS.len());
+    }
 }
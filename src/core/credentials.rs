@@ -5,6 +5,11 @@
 //! - Linux: secret-service (libsecret)
 //! - Windows: Windows Credential Manager
 
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
 use crate::error::RafctlError;
 
 const SERVICE_PREFIX: &str = "rafctl";
@@ -32,6 +37,24 @@ fn build_service_name(profile_name: &str, cred_type: CredentialType) -> String {
     format!("{}-{}-{}", SERVICE_PREFIX, profile_name, cred_type.as_str())
 }
 
+/// Service name for the `index`-th credential of a type. Index 0 is the
+/// unsuffixed name so a profile's first key keeps its pre-rotation entry.
+fn build_indexed_service_name(
+    profile_name: &str,
+    cred_type: CredentialType,
+    index: usize,
+) -> String {
+    match index {
+        0 => build_service_name(profile_name, cred_type),
+        _ => format!("{}-{}", build_service_name(profile_name, cred_type), index),
+    }
+}
+
+fn keyring_entry(service: &str) -> Result<keyring::Entry, RafctlError> {
+    keyring::Entry::new(service, &get_username())
+        .map_err(|e| RafctlError::KeychainError(format!("Failed to create keyring entry: {}", e)))
+}
+
 /// Get the username for keyring (consistent across platforms)
 fn get_username() -> String {
     whoami::username()
@@ -43,12 +66,17 @@ pub fn store_credential(
     cred_type: CredentialType,
     secret: &str,
 ) -> Result<(), RafctlError> {
-    let service = build_service_name(profile_name, cred_type);
-    let username = get_username();
+    store_indexed_credential(profile_name, cred_type, 0, secret)
+}
 
-    let entry = keyring::Entry::new(&service, &username).map_err(|e| {
-        RafctlError::KeychainError(format!("Failed to create keyring entry: {}", e))
-    })?;
+/// Store the `index`-th credential of a type (see [`count_credentials`])
+pub fn store_indexed_credential(
+    profile_name: &str,
+    cred_type: CredentialType,
+    index: usize,
+    secret: &str,
+) -> Result<(), RafctlError> {
+    let entry = keyring_entry(&build_indexed_service_name(profile_name, cred_type, index))?;
 
     entry
         .set_password(secret)
@@ -62,12 +90,16 @@ pub fn get_credential(
     profile_name: &str,
     cred_type: CredentialType,
 ) -> Result<Option<String>, RafctlError> {
-    let service = build_service_name(profile_name, cred_type);
-    let username = get_username();
+    get_indexed_credential(profile_name, cred_type, 0)
+}
 
-    let entry = keyring::Entry::new(&service, &username).map_err(|e| {
-        RafctlError::KeychainError(format!("Failed to create keyring entry: {}", e))
-    })?;
+/// Retrieve the `index`-th credential of a type
+pub fn get_indexed_credential(
+    profile_name: &str,
+    cred_type: CredentialType,
+    index: usize,
+) -> Result<Option<String>, RafctlError> {
+    let entry = keyring_entry(&build_indexed_service_name(profile_name, cred_type, index))?;
 
     match entry.get_password() {
         Ok(password) => Ok(Some(password)),
@@ -81,12 +113,15 @@ pub fn get_credential(
 
 /// Delete a credential from secure storage
 pub fn delete_credential(profile_name: &str, cred_type: CredentialType) -> Result<(), RafctlError> {
-    let service = build_service_name(profile_name, cred_type);
-    let username = get_username();
+    delete_indexed_credential(profile_name, cred_type, 0)
+}
 
-    let entry = keyring::Entry::new(&service, &username).map_err(|e| {
-        RafctlError::KeychainError(format!("Failed to create keyring entry: {}", e))
-    })?;
+fn delete_indexed_credential(
+    profile_name: &str,
+    cred_type: CredentialType,
+    index: usize,
+) -> Result<(), RafctlError> {
+    let entry = keyring_entry(&build_indexed_service_name(profile_name, cred_type, index))?;
 
     match entry.delete_credential() {
         Ok(()) => Ok(()),
@@ -103,12 +138,43 @@ pub fn has_credential(profile_name: &str, cred_type: CredentialType) -> Result<b
     Ok(get_credential(profile_name, cred_type)?.is_some())
 }
 
+/// Number of credentials of a type stored for a profile.
+///
+/// The keyring cannot be listed, so entries are probed from index 0 upwards
+/// and counting stops at the first gap.
+pub fn count_credentials(
+    profile_name: &str,
+    cred_type: CredentialType,
+) -> Result<usize, RafctlError> {
+    let mut count = 0;
+    while get_indexed_credential(profile_name, cred_type, count)?.is_some() {
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Delete every stored credential of a type and return how many were removed
+pub fn delete_all_credentials(
+    profile_name: &str,
+    cred_type: CredentialType,
+) -> Result<usize, RafctlError> {
+    let count = count_credentials(profile_name, cred_type)?;
+    for index in 0..count {
+        delete_indexed_credential(profile_name, cred_type, index)?;
+    }
+    Ok(count)
+}
+
 // ============================================================================
 // Claude-specific OAuth token handling (for token swapping)
 // ============================================================================
 
 const CLAUDE_KEYCHAIN_SERVICE: &str = "Claude Code-credentials";
 
+/// rafctl-managed entry holding the system Claude token from before an OAuth
+/// launch swapped it out
+const SYSTEM_TOKEN_BACKUP_SERVICE: &str = "rafctl-claude-system-token-backup";
+
 /// Read the current Claude Code OAuth token from system keychain
 /// This is the token that Claude Code itself uses
 pub fn read_claude_system_token() -> Result<Option<String>, RafctlError> {
@@ -153,6 +219,57 @@ pub fn write_claude_system_token(token: &str) -> Result<(), RafctlError> {
     Ok(())
 }
 
+/// Read the system token saved by [`backup_claude_system_token`], if any
+pub fn read_system_token_backup() -> Result<Option<String>, RafctlError> {
+    match keyring_entry(SYSTEM_TOKEN_BACKUP_SERVICE)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(RafctlError::KeychainError(format!(
+            "Failed to read system token backup: {}",
+            e
+        ))),
+    }
+}
+
+/// Save the current system Claude token before an OAuth launch overwrites it.
+///
+/// An existing backup is kept: if an earlier run crashed mid-swap the system
+/// keychain now holds a profile's token, and the backup is still the user's
+/// own. Returns whether a new backup was written.
+pub fn backup_claude_system_token() -> Result<bool, RafctlError> {
+    if read_system_token_backup()?.is_some() {
+        return Ok(false);
+    }
+    let Some(token) = read_claude_system_token()? else {
+        return Ok(false);
+    };
+
+    keyring_entry(SYSTEM_TOKEN_BACKUP_SERVICE)?
+        .set_password(&token)
+        .map_err(|e| {
+            RafctlError::KeychainError(format!("Failed to back up system token: {}", e))
+        })?;
+    Ok(true)
+}
+
+/// Put the backed-up system token back and drop the backup.
+/// Returns `false` if there was no backup to restore.
+pub fn restore_claude_system_token() -> Result<bool, RafctlError> {
+    let Some(token) = read_system_token_backup()? else {
+        return Ok(false);
+    };
+
+    write_claude_system_token(&token)?;
+
+    match keyring_entry(SYSTEM_TOKEN_BACKUP_SERVICE)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(true),
+        Err(e) => Err(RafctlError::KeychainError(format!(
+            "Failed to delete system token backup: {}",
+            e
+        ))),
+    }
+}
+
 // ============================================================================
 // Migration helpers
 // ============================================================================
@@ -171,6 +288,39 @@ pub fn has_api_key_configured(profile_name: &str, legacy_api_key: &Option<String
     has_credential(profile_name, CredentialType::ApiKey).unwrap_or(false)
 }
 
+/// Extract the expiry time from a stored OAuth token.
+///
+/// Understands Claude Code's credentials blob (`claudeAiOauth.expiresAt`, in
+/// milliseconds) and bare JWTs (`exp` claim, in seconds). Returns `None` for
+/// anything else.
+pub fn oauth_token_expiry(token: &str) -> Option<DateTime<Utc>> {
+    if let Ok(blob) = serde_json::from_str::<Value>(token.trim()) {
+        let oauth = blob.get("claudeAiOauth").unwrap_or(&blob);
+        let expires_at = oauth.get("expiresAt")?.as_i64()?;
+        return DateTime::from_timestamp_millis(expires_at);
+    }
+
+    let mut parts = token.trim().split('.');
+    let (_, payload, _) = (parts.next()?, parts.next()?, parts.next()?);
+    let claims: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+    DateTime::from_timestamp(claims.get("exp")?.as_i64()?, 0)
+}
+
+/// Whether a stored OAuth token carries a refresh token, so Claude Code can
+/// renew the access token on its own once `expiresAt` passes.
+pub fn oauth_token_refreshable(token: &str) -> bool {
+    serde_json::from_str::<Value>(token.trim())
+        .ok()
+        .and_then(|blob| {
+            let oauth = blob.get("claudeAiOauth").unwrap_or(&blob);
+            oauth
+                .get("refreshToken")?
+                .as_str()
+                .map(|refresh| !refresh.is_empty())
+        })
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,9 +337,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_indexed_service_name() {
+        assert_eq!(
+            build_indexed_service_name("work", CredentialType::ApiKey, 0),
+            "rafctl-work-api-key"
+        );
+        assert_eq!(
+            build_indexed_service_name("work", CredentialType::ApiKey, 2),
+            "rafctl-work-api-key-2"
+        );
+    }
+
     #[test]
     fn test_credential_type_as_str() {
         assert_eq!(CredentialType::OAuthToken.as_str(), "oauth-token");
         assert_eq!(CredentialType::ApiKey.as_str(), "api-key");
     }
+
+    #[test]
+    fn test_oauth_token_expiry_claude_blob() {
+        let token = r#"{"claudeAiOauth":{"accessToken":"sk-ant-oat","expiresAt":1767225600000}}"#;
+        assert_eq!(
+            oauth_token_expiry(token),
+            DateTime::from_timestamp(1_767_225_600, 0)
+        );
+    }
+
+    #[test]
+    fn test_oauth_token_expiry_jwt() {
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"sub":"me","exp":1767225600}"#);
+        let token = format!("eyJhbGciOiJIUzI1NiJ9.{}.sig", payload);
+        assert_eq!(
+            oauth_token_expiry(&token),
+            DateTime::from_timestamp(1_767_225_600, 0)
+        );
+    }
+
+    #[test]
+    fn test_oauth_token_refreshable() {
+        let token = r#"{"claudeAiOauth":{"accessToken":"a","refreshToken":"r","expiresAt":1}}"#;
+        assert!(oauth_token_refreshable(token));
+        assert!(!oauth_token_refreshable(
+            r#"{"claudeAiOauth":{"accessToken":"a","expiresAt":1}}"#
+        ));
+        assert!(!oauth_token_refreshable("sk-ant-oat01-opaque"));
+    }
+
+    #[test]
+    fn test_oauth_token_expiry_opaque() {
+        assert_eq!(oauth_token_expiry("sk-ant-oat01-opaque"), None);
+        assert_eq!(oauth_token_expiry(r#"{"accessToken":"x"}"#), None);
+    }
 }
@@ -0,0 +1,112 @@
+//! `KEY=VALUE` env files for `rafctl run --env-file`.
+//!
+//! Supports the common dotenv subset: blank lines, `#` comments, an optional
+//! `export ` prefix and values wrapped in matching single or double quotes.
+//! No variable interpolation or multi-line values.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::RafctlError;
+
+/// Read and parse an env file, in file order
+pub fn load_env_file(path: &Path) -> Result<Vec<(String, String)>, RafctlError> {
+    let content = fs::read_to_string(path).map_err(|e| RafctlError::ConfigRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    parse_env_file(&content).map_err(|(line, message)| RafctlError::InvalidEnvFile {
+        path: path.to_path_buf(),
+        line,
+        message,
+    })
+}
+
+/// Parse env file content; errors carry the 1-based line number
+fn parse_env_file(content: &str) -> Result<Vec<(String, String)>, (usize, String)> {
+    let mut vars = Vec::new();
+
+    for (index, raw) in content.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err((index + 1, "expected KEY=VALUE".to_string()));
+        };
+        let key = key.trim();
+        if !is_valid_key(key) {
+            return Err((index + 1, format!("invalid variable name '{}'", key)));
+        }
+
+        vars.push((key.to_string(), unquote(value.trim()).to_string()));
+    }
+
+    Ok(vars)
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let content = "\
+# project settings
+FOO=bar
+
+export PATH_EXTRA = /opt/bin
+QUOTED=\"hello world\"
+SINGLE='a=b'
+EMPTY=
+";
+        let vars = parse_env_file(content).unwrap();
+
+        assert_eq!(
+            vars,
+            vec![
+                ("FOO".to_string(), "bar".to_string()),
+                ("PATH_EXTRA".to_string(), "/opt/bin".to_string()),
+                ("QUOTED".to_string(), "hello world".to_string()),
+                ("SINGLE".to_string(), "a=b".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_env_file_missing_equals() {
+        let err = parse_env_file("FOO=bar\n\nNOT A VAR\n").unwrap_err();
+        assert_eq!(err, (3, "expected KEY=VALUE".to_string()));
+    }
+
+    #[test]
+    fn test_parse_env_file_invalid_key() {
+        let err = parse_env_file("1FOO=bar").unwrap_err();
+        assert_eq!(err.0, 1);
+        assert!(err.1.contains("'1FOO'"));
+    }
+}
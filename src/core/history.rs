@@ -0,0 +1,191 @@
+//! Per-profile launch history.
+//!
+//! Every `rafctl run` appends one JSON line to
+//! `~/.rafctl/profiles/<name>/history.jsonl`. Only the most recent
+//! `HISTORY_MAX_ENTRIES` launches are kept.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::constants::{HISTORY_FILE, HISTORY_MAX_ENTRIES};
+use crate::core::profile::get_profile_dir;
+use crate::error::RafctlError;
+
+/// One `rafctl run` of a profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub exit_code: i32,
+    /// Config snapshot taken for this launch (`run --snapshot-config`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<PathBuf>,
+}
+
+pub fn history_path(profile_name: &str) -> Result<PathBuf, RafctlError> {
+    Ok(get_profile_dir(profile_name)?.join(HISTORY_FILE))
+}
+
+pub fn append_history(profile_name: &str, entry: &HistoryEntry) -> Result<(), RafctlError> {
+    append_history_to(&history_path(profile_name)?, entry, HISTORY_MAX_ENTRIES)
+}
+
+/// Most recent launches first, at most `limit`.
+pub fn load_history(profile_name: &str, limit: usize) -> Result<Vec<HistoryEntry>, RafctlError> {
+    let mut entries = read_history(&history_path(profile_name)?)?;
+    entries.reverse();
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+/// Fold one profile's history into another's, keeping launch order and the
+/// entry cap. Returns how many entries were added.
+pub fn merge_history(from_profile: &str, into_profile: &str) -> Result<usize, RafctlError> {
+    merge_history_files(
+        &history_path(from_profile)?,
+        &history_path(into_profile)?,
+        HISTORY_MAX_ENTRIES,
+    )
+}
+
+fn merge_history_files(from: &Path, into: &Path, max_entries: usize) -> Result<usize, RafctlError> {
+    let added = read_history(from)?;
+    if added.is_empty() {
+        return Ok(0);
+    }
+
+    let mut entries = read_history(into)?;
+    entries.extend(added.iter().cloned());
+    entries.sort_by_key(|e| e.started_at);
+    let skip = entries.len().saturating_sub(max_entries);
+
+    let content: String = entries[skip..]
+        .iter()
+        .filter_map(|e| serde_json::to_string(e).ok())
+        .map(|line| line + "\n")
+        .collect();
+    fs::write(into, content).map_err(|e| RafctlError::ConfigWrite {
+        path: into.to_path_buf(),
+        source: e,
+    })?;
+
+    Ok(added.len())
+}
+
+fn append_history_to(
+    path: &Path,
+    entry: &HistoryEntry,
+    max_entries: usize,
+) -> Result<(), RafctlError> {
+    let write_err = |e| RafctlError::ConfigWrite {
+        path: path.to_path_buf(),
+        source: e,
+    };
+
+    let line = serde_json::to_string(entry)
+        .map_err(|e| write_err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(write_err)?;
+    writeln!(file, "{}", line).map_err(write_err)?;
+    drop(file);
+
+    let entries = read_history(path)?;
+    if entries.len() > max_entries {
+        let kept: String = entries[entries.len() - max_entries..]
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .map(|line| line + "\n")
+            .collect();
+        fs::write(path, kept).map_err(write_err)?;
+    }
+
+    Ok(())
+}
+
+/// Oldest first. Lines that fail to parse are skipped.
+fn read_history(path: &Path) -> Result<Vec<HistoryEntry>, RafctlError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| RafctlError::ConfigRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(arg: &str) -> HistoryEntry {
+        HistoryEntry {
+            started_at: Utc::now(),
+            args: vec![arg.to_string()],
+            exit_code: 0,
+            snapshot: None,
+        }
+    }
+
+    #[test]
+    fn test_history_is_capped_and_skips_bad_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HISTORY_FILE);
+
+        append_history_to(&path, &entry("one"), 2).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+        append_history_to(&path, &entry("two"), 2).unwrap();
+        append_history_to(&path, &entry("three"), 2).unwrap();
+
+        let args: Vec<String> = read_history(&path)
+            .unwrap()
+            .into_iter()
+            .flat_map(|e| e.args)
+            .collect();
+        assert_eq!(args, vec!["two", "three"]);
+    }
+
+    #[test]
+    fn test_merge_history_interleaves_by_start_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("from.jsonl");
+        let into = dir.path().join("into.jsonl");
+
+        let at = |secs: i64, arg: &str| HistoryEntry {
+            started_at: DateTime::from_timestamp(secs, 0).unwrap(),
+            ..entry(arg)
+        };
+        append_history_to(&into, &at(10, "into-1"), 10).unwrap();
+        append_history_to(&into, &at(30, "into-2"), 10).unwrap();
+        append_history_to(&from, &at(20, "from-1"), 10).unwrap();
+        append_history_to(&from, &at(40, "from-2"), 10).unwrap();
+
+        assert_eq!(merge_history_files(&from, &into, 3).unwrap(), 2);
+
+        let args: Vec<String> = read_history(&into)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.args[0].clone())
+            .collect();
+        assert_eq!(args, vec!["from-1", "into-2", "from-2"]);
+    }
+}
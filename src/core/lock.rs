@@ -0,0 +1,180 @@
+//! OAuth single-identity lock.
+//!
+//! `rafctl run` holds an advisory `flock` on `~/.rafctl/oauth.lock` while an
+//! OAuth profile is running and writes the profile name into the file. Only
+//! one OAuth identity can own the system token at a time, but several
+//! instances of that same profile share it: every running instance holds a
+//! shared lock, and another profile is refused while any of them is alive.
+//!
+//! The advisory lock is released when the process exits, but the file and its
+//! contents remain, so the file existing does not mean a profile is running.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use fs2::FileExt;
+
+use crate::core::constants::OAUTH_LOCK_FILE;
+use crate::core::profile::get_config_dir;
+use crate::error::RafctlError;
+
+/// State of the OAuth lock file on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockState {
+    /// No lock file.
+    Absent,
+    /// The file exists but no process holds the advisory lock.
+    Stale { holder: Option<String> },
+    /// A running instance holds the advisory lock.
+    Held { holder: Option<String> },
+}
+
+pub fn oauth_lock_path() -> Result<PathBuf, RafctlError> {
+    Ok(get_config_dir()?.join(OAUTH_LOCK_FILE))
+}
+
+/// Profile name last written into the lock file, if any.
+pub fn lock_holder(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Check whether the lock file exists and whether another process holds it.
+pub fn inspect_lock(path: &Path) -> LockState {
+    let Ok(file) = File::open(path) else {
+        return LockState::Absent;
+    };
+    let holder = lock_holder(path);
+
+    if file.try_lock_exclusive().is_ok() {
+        let _ = FileExt::unlock(&file);
+        LockState::Stale { holder }
+    } else {
+        LockState::Held { holder }
+    }
+}
+
+/// A held OAuth lock; the lock is released when this is dropped.
+#[derive(Debug)]
+pub struct OAuthLock {
+    file: File,
+    /// Another instance of the same profile already held the lock, so the
+    /// system token is already in place
+    pub joined: bool,
+}
+
+impl OAuthLock {
+    /// Whether no other instance shares the lock, i.e. this one is the last
+    /// to leave and should restore the system token. Meant to be called once,
+    /// right before exiting: a failed check may give up this instance's lock.
+    pub fn is_last_holder(&self) -> bool {
+        self.file.try_lock_exclusive().is_ok()
+    }
+}
+
+/// Take the lock for `profile_name`, or `None` if a different profile holds it.
+/// When the same profile already holds it, the lock is shared with that
+/// instance (`joined`). The returned lock must be kept alive for as long as
+/// it should be held.
+pub fn acquire_lock(path: &Path, profile_name: &str) -> Result<Option<OAuthLock>, RafctlError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| RafctlError::ConfigWrite {
+            path: parent.to_path_buf(),
+            source: e,
+        })?;
+    }
+
+    // Do not truncate before locking, or a losing attempt would erase the holder's name
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|e| RafctlError::ConfigWrite {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+    if file.try_lock_exclusive().is_err() {
+        // Held: join only an instance of the same profile
+        if lock_holder(path).as_deref() != Some(profile_name) || file.try_lock_shared().is_err() {
+            return Ok(None);
+        }
+        return Ok(Some(OAuthLock { file, joined: true }));
+    }
+
+    let _ = file.set_len(0);
+    let _ = file.seek(SeekFrom::Start(0));
+    let _ = writeln!(file, "{}", profile_name);
+    let _ = file.flush();
+
+    // Let later instances of this profile share the lock now that the name is written
+    if file.try_lock_shared().is_err() {
+        return Ok(None);
+    }
+
+    Ok(Some(OAuthLock {
+        file,
+        joined: false,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_states() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(OAUTH_LOCK_FILE);
+
+        assert_eq!(inspect_lock(&path), LockState::Absent);
+
+        let held = acquire_lock(&path, "work").unwrap().unwrap();
+        assert!(!held.joined);
+        assert_eq!(
+            inspect_lock(&path),
+            LockState::Held {
+                holder: Some("work".to_string())
+            }
+        );
+
+        // A losing attempt fails without erasing the holder's name
+        assert!(acquire_lock(&path, "personal").unwrap().is_none());
+        assert_eq!(lock_holder(&path), Some("work".to_string()));
+
+        drop(held);
+        assert_eq!(
+            inspect_lock(&path),
+            LockState::Stale {
+                holder: Some("work".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_same_profile_shares_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(OAUTH_LOCK_FILE);
+
+        let first = acquire_lock(&path, "work").unwrap().unwrap();
+        let second = acquire_lock(&path, "work").unwrap().unwrap();
+        assert!(second.joined);
+
+        // Another identity stays locked out while either instance runs
+        assert!(acquire_lock(&path, "personal").unwrap().is_none());
+
+        // The first to leave is not the last holder
+        assert!(!first.is_last_holder());
+        drop(first);
+        assert!(acquire_lock(&path, "personal").unwrap().is_none());
+        assert!(second.is_last_holder());
+        drop(second);
+
+        assert!(acquire_lock(&path, "personal").unwrap().is_some());
+    }
+}
@@ -1,6 +1,14 @@
+pub mod backup;
+pub mod bundle;
+pub mod cache;
 pub mod config;
 pub mod constants;
 pub mod credentials;
+pub mod env_file;
+pub mod history;
+pub mod lock;
+pub mod pricing;
 pub mod profile;
+pub mod snapshot;
 pub mod stats;
 pub mod transcript;
@@ -0,0 +1,235 @@
+//! Model pricing used for `rafctl analytics --cost`.
+//!
+//! Built-in prices can be overridden or extended in `~/.rafctl/pricing.toml`:
+//!
+//! ```toml
+//! ["claude-opus-4-1"]
+//! input_per_million = 15.0
+//! output_per_million = 75.0
+//! ```
+//!
+//! Each table name is a pattern matched against the model id as a substring.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::constants::PRICING_FILE;
+use crate::core::profile::get_config_dir;
+use crate::error::RafctlError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+impl ModelPricing {
+    /// Estimated cost in USD for the given token counts
+    pub fn estimate_cost(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 / 1_000_000.0) * self.input_per_million
+            + (output_tokens as f64 / 1_000_000.0) * self.output_per_million
+    }
+}
+
+/// Where a pricing entry came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PricingSource {
+    Builtin,
+    Override,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PricingEntry {
+    pub pattern: String,
+    #[serde(flatten)]
+    pub pricing: ModelPricing,
+    pub source: PricingSource,
+}
+
+const BUILTIN_PRICING: &[(&str, ModelPricing)] = &[
+    (
+        "claude-sonnet-4-5",
+        ModelPricing {
+            input_per_million: 3.0,
+            output_per_million: 15.0,
+        },
+    ),
+    (
+        "claude-opus-4-5",
+        ModelPricing {
+            input_per_million: 15.0,
+            output_per_million: 75.0,
+        },
+    ),
+    (
+        "claude-haiku-4-5",
+        ModelPricing {
+            input_per_million: 0.80,
+            output_per_million: 4.0,
+        },
+    ),
+    (
+        "claude-haiku-3-5",
+        ModelPricing {
+            input_per_million: 0.25,
+            output_per_million: 1.25,
+        },
+    ),
+];
+
+/// Price for models that match no entry (Sonnet rates)
+pub const FALLBACK_PRICING: ModelPricing = ModelPricing {
+    input_per_million: 3.0,
+    output_per_million: 15.0,
+};
+
+/// Built-in prices merged with user overrides. Overrides are matched first,
+/// longest pattern first, so a specific override wins over a broader one.
+#[derive(Debug, Clone)]
+pub struct PricingTable {
+    entries: Vec<PricingEntry>,
+}
+
+impl PricingTable {
+    pub fn builtin() -> Self {
+        Self::with_overrides(BTreeMap::new())
+    }
+
+    pub fn with_overrides(overrides: BTreeMap<String, ModelPricing>) -> Self {
+        let mut entries: Vec<PricingEntry> = overrides
+            .into_iter()
+            .map(|(pattern, pricing)| PricingEntry {
+                pattern,
+                pricing,
+                source: PricingSource::Override,
+            })
+            .collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.pattern.len()));
+
+        for (pattern, pricing) in BUILTIN_PRICING {
+            if !entries.iter().any(|e| e.pattern == *pattern) {
+                entries.push(PricingEntry {
+                    pattern: pattern.to_string(),
+                    pricing: *pricing,
+                    source: PricingSource::Builtin,
+                });
+            }
+        }
+
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[PricingEntry] {
+        &self.entries
+    }
+
+    pub fn lookup(&self, model_name: &str) -> ModelPricing {
+        self.entries
+            .iter()
+            .find(|e| model_name.contains(&e.pattern))
+            .map(|e| e.pricing)
+            .unwrap_or(FALLBACK_PRICING)
+    }
+}
+
+pub fn get_pricing_path() -> Result<PathBuf, RafctlError> {
+    Ok(get_config_dir()?.join(PRICING_FILE))
+}
+
+/// Load the pricing table, applying `pricing.toml` overrides if the file exists.
+pub fn load_pricing() -> Result<PricingTable, RafctlError> {
+    load_pricing_from(&get_pricing_path()?)
+}
+
+fn load_pricing_from(path: &Path) -> Result<PricingTable, RafctlError> {
+    if !path.exists() {
+        return Ok(PricingTable::builtin());
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| RafctlError::ConfigRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    let overrides: BTreeMap<String, ModelPricing> =
+        toml::from_str(&content).map_err(|e| RafctlError::ConfigRead {
+            path: path.to_path_buf(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        })?;
+
+    Ok(PricingTable::with_overrides(overrides))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_lookup() {
+        let table = PricingTable::builtin();
+
+        assert_eq!(
+            table.lookup("claude-opus-4-5-20251101").input_per_million,
+            15.0
+        );
+        assert_eq!(table.lookup("some-new-model"), FALLBACK_PRICING);
+    }
+
+    #[test]
+    fn test_estimate_cost() {
+        let pricing = ModelPricing {
+            input_per_million: 3.0,
+            output_per_million: 15.0,
+        };
+        assert_eq!(pricing.estimate_cost(1_000_000, 100_000), 4.5);
+    }
+
+    #[test]
+    fn test_overrides_merge_over_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PRICING_FILE);
+        fs::write(
+            &path,
+            r#"
+["claude-opus-4-5"]
+input_per_million = 5.0
+output_per_million = 25.0
+
+["claude-opus"]
+input_per_million = 15.0
+output_per_million = 75.0
+"#,
+        )
+        .unwrap();
+
+        let table = load_pricing_from(&path).unwrap();
+
+        assert_eq!(table.lookup("claude-opus-4-5").input_per_million, 5.0);
+        assert_eq!(table.lookup("claude-opus-4-1").input_per_million, 15.0);
+        assert_eq!(table.lookup("claude-sonnet-4-5").input_per_million, 3.0);
+        assert_eq!(
+            table
+                .entries()
+                .iter()
+                .filter(|e| e.pattern == "claude-opus-4-5")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_invalid_pricing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PRICING_FILE);
+        fs::write(&path, "[\"claude-opus\"]\ninput_per_million = \"cheap\"\n").unwrap();
+
+        assert!(matches!(
+            load_pricing_from(&path),
+            Err(RafctlError::ConfigRead { .. })
+        ));
+    }
+}
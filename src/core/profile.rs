@@ -1,5 +1,6 @@
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -9,6 +10,9 @@ use crate::error::RafctlError;
 const MAX_PROFILE_NAME_LENGTH: usize = 64;
 const RESERVED_NAMES: &[&str] = &["default", "config", "cache", "profiles", "oauth"];
 
+/// Config dir from the global `--config-dir` flag, set once at startup
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
 /// Authentication mode for Claude Code profiles
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -46,11 +50,47 @@ impl std::str::FromStr for AuthMode {
     }
 }
 
+/// Which config directory `rafctl run` points the tool at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigMode {
+    /// Own config dir under the profile (via `CLAUDE_CONFIG_DIR` etc.)
+    #[default]
+    Isolated,
+    /// The tool's real config dir (e.g. `~/.claude`); rafctl only manages auth
+    Shared,
+}
+
+impl std::fmt::Display for ConfigMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigMode::Isolated => write!(f, "isolated"),
+            ConfigMode::Shared => write!(f, "shared"),
+        }
+    }
+}
+
+impl std::str::FromStr for ConfigMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "isolated" => Ok(ConfigMode::Isolated),
+            "shared" => Ok(ConfigMode::Shared),
+            _ => Err(format!(
+                "Invalid config dir mode '{}'. Valid options: isolated, shared",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ToolType {
     Claude,
     Codex,
+    Gemini,
 }
 
 impl std::fmt::Display for ToolType {
@@ -58,6 +98,7 @@ impl std::fmt::Display for ToolType {
         match self {
             ToolType::Claude => write!(f, "claude"),
             ToolType::Codex => write!(f, "codex"),
+            ToolType::Gemini => write!(f, "gemini"),
         }
     }
 }
@@ -69,8 +110,9 @@ impl std::str::FromStr for ToolType {
         match s.to_lowercase().as_str() {
             "claude" => Ok(ToolType::Claude),
             "codex" => Ok(ToolType::Codex),
+            "gemini" => Ok(ToolType::Gemini),
             _ => Err(format!(
-                "Invalid tool type '{}'. Valid options: claude, codex",
+                "Invalid tool type '{}'. Valid options: claude, codex, gemini",
                 s
             )),
         }
@@ -82,7 +124,7 @@ pub struct Profile {
     pub name: String,
     pub tool: ToolType,
     /// Authentication mode (OAuth or API Key)
-    /// Only applicable for Claude - Codex always uses OAuth
+    /// Only applicable for Claude - Codex and Gemini always use OAuth
     #[serde(default)]
     pub auth_mode: AuthMode,
     /// DEPRECATED: API key is now stored in secure keyring
@@ -91,6 +133,27 @@ pub struct Profile {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[deprecated(note = "Use credentials module for API key storage")]
     pub api_key: Option<String>,
+    /// Profile to launch instead when this one's quota is exhausted
+    /// (used by `rafctl run --on-quota-exhausted switch-to`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_profile: Option<String>,
+    /// Index of the keyring API key `rafctl run` uses (see `rafctl auth rotate-key`);
+    /// unset means the first key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_api_key: Option<usize>,
+    /// Shell command `rafctl run` executes before launching the tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_run: Option<String>,
+    /// Shell command `rafctl run` executes after the tool exits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_run: Option<String>,
+    /// Labels for grouping profiles (`rafctl profile tag`), stored lowercase
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Whether `rafctl run` points the tool at the profile's own config dir
+    /// or leaves the tool's default config dir in place
+    #[serde(default)]
+    pub config_mode: ConfigMode,
     pub created_at: DateTime<Utc>,
     pub last_used: Option<DateTime<Utc>>,
 }
@@ -103,11 +166,35 @@ impl Profile {
             tool,
             auth_mode: AuthMode::default(),
             api_key: None,
+            fallback_profile: None,
+            active_api_key: None,
+            pre_run: None,
+            post_run: None,
+            tags: Vec::new(),
+            config_mode: ConfigMode::Isolated,
             created_at: Utc::now(),
             last_used: None,
         }
     }
 
+    /// How the profile signs in: `oauth` or `api-key` for Claude,
+    /// `tool-login` for tools that keep their own login in the profile dir.
+    pub fn auth_label(&self) -> String {
+        match self.tool {
+            ToolType::Claude => self.auth_mode.to_string(),
+            ToolType::Codex | ToolType::Gemini => "tool-login".to_string(),
+        }
+    }
+
+    /// Whether the tool gets its own config dir under the profile
+    pub fn is_isolated(&self) -> bool {
+        self.config_mode == ConfigMode::Isolated
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
     #[allow(deprecated)]
     pub fn new_with_auth(name: String, tool: ToolType, auth_mode: AuthMode) -> Self {
         Self {
@@ -115,16 +202,23 @@ impl Profile {
             tool,
             auth_mode,
             api_key: None,
+            fallback_profile: None,
+            active_api_key: None,
+            pre_run: None,
+            post_run: None,
+            tags: Vec::new(),
+            config_mode: ConfigMode::Isolated,
             created_at: Utc::now(),
             last_used: None,
         }
     }
 
-    /// Check if this profile supports parallel instances
+    /// Check if this profile supports parallel instances.
+    /// Profiles that don't are serialized by the OAuth lock in `rafctl run`.
     pub fn supports_parallel(&self) -> bool {
         matches!(
             (&self.tool, &self.auth_mode),
-            (ToolType::Claude, AuthMode::ApiKey) | (ToolType::Codex, _)
+            (ToolType::Claude, AuthMode::ApiKey) | (ToolType::Codex, _) | (ToolType::Gemini, _)
         )
     }
 }
@@ -150,7 +244,33 @@ pub fn validate_profile_name(name: &str) -> Result<(), RafctlError> {
     Ok(())
 }
 
+/// Tags follow the same character rules as profile names.
+pub fn validate_tag(tag: &str) -> Result<(), RafctlError> {
+    if tag.is_empty()
+        || tag.len() > MAX_PROFILE_NAME_LENGTH
+        || !tag.chars().all(is_valid_profile_char)
+    {
+        return Err(RafctlError::InvalidTag(tag.to_string()));
+    }
+    Ok(())
+}
+
+/// Use `dir` as the config dir for the rest of the process (`--config-dir`).
+/// Relative paths are resolved against the current directory.
+pub fn set_config_dir_override(dir: PathBuf) {
+    let dir = std::path::absolute(&dir).unwrap_or(dir);
+    let _ = CONFIG_DIR_OVERRIDE.set(dir);
+}
+
+/// The `--config-dir` value, if one was given.
+pub fn config_dir_override() -> Option<&'static Path> {
+    CONFIG_DIR_OVERRIDE.get().map(PathBuf::as_path)
+}
+
 pub fn get_config_dir() -> Result<PathBuf, RafctlError> {
+    if let Some(dir) = CONFIG_DIR_OVERRIDE.get() {
+        return Ok(dir.clone());
+    }
     // Allow override via RAFCTL_CONFIG_DIR for testing and custom installations
     if let Ok(dir) = std::env::var("RAFCTL_CONFIG_DIR") {
         return Ok(PathBuf::from(dir));
@@ -171,7 +291,7 @@ pub fn get_profile_meta_path(name: &str) -> Result<PathBuf, RafctlError> {
     Ok(get_profile_dir(name)?.join("meta.yaml"))
 }
 
-fn ensure_dir_with_permissions(path: &Path) -> Result<(), RafctlError> {
+pub fn ensure_dir_with_permissions(path: &Path) -> Result<(), RafctlError> {
     if !path.exists() {
         fs::create_dir_all(path).map_err(|e| RafctlError::ConfigWrite {
             path: path.to_path_buf(),
@@ -278,6 +398,13 @@ pub fn list_profiles() -> Result<Vec<String>, RafctlError> {
     Ok(profiles)
 }
 
+/// Names of the profiles carrying `tag` (case-insensitive), sorted.
+pub fn list_profiles_with_tag(tag: &str) -> Result<Vec<String>, RafctlError> {
+    let mut profiles = list_profiles()?;
+    profiles.retain(|name| load_profile(name).is_ok_and(|p| p.has_tag(tag)));
+    Ok(profiles)
+}
+
 pub fn delete_profile(name: &str) -> Result<(), RafctlError> {
     let profile_dir = get_profile_dir(name)?;
 
@@ -353,6 +480,10 @@ mod tests {
         let tool = ToolType::Codex;
         let yaml = serde_yaml::to_string(&tool).unwrap();
         assert_eq!(yaml.trim(), "codex");
+
+        let tool = ToolType::Gemini;
+        let yaml = serde_yaml::to_string(&tool).unwrap();
+        assert_eq!(yaml.trim(), "gemini");
     }
 
     #[test]
@@ -362,6 +493,9 @@ mod tests {
 
         let tool: ToolType = serde_yaml::from_str("codex").unwrap();
         assert_eq!(tool, ToolType::Codex);
+
+        let tool: ToolType = serde_yaml::from_str("gemini").unwrap();
+        assert_eq!(tool, ToolType::Gemini);
     }
 
     #[test]
@@ -369,6 +503,7 @@ mod tests {
         assert_eq!("claude".parse::<ToolType>().unwrap(), ToolType::Claude);
         assert_eq!("Claude".parse::<ToolType>().unwrap(), ToolType::Claude);
         assert_eq!("CODEX".parse::<ToolType>().unwrap(), ToolType::Codex);
+        assert_eq!("gemini".parse::<ToolType>().unwrap(), ToolType::Gemini);
         assert!("invalid".parse::<ToolType>().is_err());
     }
 
@@ -406,6 +541,9 @@ mod tests {
 
         let codex = Profile::new("codex".to_string(), ToolType::Codex);
         assert!(codex.supports_parallel());
+
+        let gemini = Profile::new("gemini".to_string(), ToolType::Gemini);
+        assert!(gemini.supports_parallel());
     }
 
     #[test]
@@ -419,6 +557,18 @@ mod tests {
         assert_eq!(restored.auth_mode, profile.auth_mode);
     }
 
+    #[test]
+    fn test_gemini_profile_serialization_roundtrip() {
+        let profile = Profile::new("gemini-profile".to_string(), ToolType::Gemini);
+        let yaml = serde_yaml::to_string(&profile).unwrap();
+        assert!(yaml.contains("tool: gemini"));
+
+        let restored: Profile = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(restored.name, profile.name);
+        assert_eq!(restored.tool, ToolType::Gemini);
+        assert_eq!(restored.auth_mode, AuthMode::OAuth);
+    }
+
     #[test]
     #[allow(deprecated)]
     fn test_profile_backwards_compatibility() {
@@ -431,6 +581,41 @@ last_used: null
         let profile: Profile = serde_yaml::from_str(old_yaml).unwrap();
         assert_eq!(profile.auth_mode, AuthMode::OAuth);
         assert!(profile.api_key.is_none());
+        assert!(profile.fallback_profile.is_none());
+        assert!(profile.tags.is_empty());
+        assert_eq!(
+            profile.config_mode,
+            ConfigMode::Isolated,
+            "existing profiles stay isolated"
+        );
+    }
+
+    #[test]
+    fn test_profile_fallback_roundtrip() {
+        let mut profile = Profile::new("main".to_string(), ToolType::Claude);
+        let yaml = serde_yaml::to_string(&profile).unwrap();
+        assert!(!yaml.contains("fallback_profile"));
+
+        profile.fallback_profile = Some("backup".to_string());
+        let yaml = serde_yaml::to_string(&profile).unwrap();
+        let restored: Profile = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(restored.fallback_profile, Some("backup".to_string()));
+    }
+
+    #[test]
+    fn test_profile_tags() {
+        let mut profile = Profile::new("main".to_string(), ToolType::Claude);
+        assert!(!serde_yaml::to_string(&profile).unwrap().contains("tags"));
+
+        profile.tags = vec!["clients".to_string()];
+        let yaml = serde_yaml::to_string(&profile).unwrap();
+        let restored: Profile = serde_yaml::from_str(&yaml).unwrap();
+        assert!(restored.has_tag("Clients"));
+        assert!(!restored.has_tag("personal"));
+
+        assert!(validate_tag("client-a").is_ok());
+        assert!(validate_tag("").is_err());
+        assert!(validate_tag("two words").is_err());
     }
 
     #[test]
@@ -0,0 +1,62 @@
+//! Config snapshots taken by `rafctl run --snapshot-config`.
+//!
+//! Each snapshot is a directory `~/.rafctl/snapshots/<profile>/<timestamp>/`
+//! holding copies of the profile's `meta.yaml` and the tool's `settings.json`
+//! as they were at launch.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use crate::core::constants::{PROFILE_META_FILE, SNAPSHOTS_DIR, TOOL_SETTINGS_FILE};
+use crate::core::profile::{get_config_dir, get_profile_meta_path, Profile};
+use crate::error::RafctlError;
+
+pub fn snapshots_dir(profile_name: &str) -> Result<PathBuf, RafctlError> {
+    Ok(get_config_dir()?
+        .join(SNAPSHOTS_DIR)
+        .join(profile_name.to_lowercase()))
+}
+
+/// Settings file the tool reads for this profile (may not exist).
+fn tool_settings_path(profile: &Profile) -> Result<PathBuf, RafctlError> {
+    let config_dir = if profile.is_isolated() {
+        profile.tool.config_dir_for_profile(&profile.name)?
+    } else {
+        dirs::home_dir()
+            .ok_or(RafctlError::NoHomeDir)?
+            .join(format!(".{}", profile.tool.command_name()))
+    };
+    Ok(config_dir.join(TOOL_SETTINGS_FILE))
+}
+
+/// Copy the profile's config files into a new snapshot directory and return its path.
+pub fn snapshot_config(profile: &Profile, at: DateTime<Utc>) -> Result<PathBuf, RafctlError> {
+    let dir = snapshots_dir(&profile.name)?.join(at.format("%Y%m%dT%H%M%SZ").to_string());
+    fs::create_dir_all(&dir).map_err(|e| RafctlError::ConfigWrite {
+        path: dir.clone(),
+        source: e,
+    })?;
+
+    copy_into(
+        &get_profile_meta_path(&profile.name)?,
+        &dir,
+        PROFILE_META_FILE,
+    )?;
+    let settings = tool_settings_path(profile)?;
+    if settings.exists() {
+        copy_into(&settings, &dir, TOOL_SETTINGS_FILE)?;
+    }
+
+    Ok(dir)
+}
+
+fn copy_into(source: &Path, dir: &Path, file_name: &str) -> Result<(), RafctlError> {
+    let target = dir.join(file_name);
+    fs::copy(source, &target).map_err(|e| RafctlError::ConfigWrite {
+        path: target,
+        source: e,
+    })?;
+    Ok(())
+}
@@ -4,12 +4,15 @@
 //! historical usage data like daily activity, token counts by model, etc.
 
 use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-use serde::Deserialize;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
 
-use crate::core::profile::{get_profile_dir, ToolType};
+use crate::core::constants::COSTS_FILE;
+use crate::core::profile::{atomic_write, get_profile_dir, ToolType};
 use crate::error::RafctlError;
 
 /// Current schema version of stats-cache.json
@@ -19,12 +22,14 @@ const EXPECTED_SCHEMA_VERSION: u32 = 1;
 ///
 /// Location: `~/.claude/stats-cache.json` (global)
 ///           or `~/.rafctl/profiles/<name>/claude/stats-cache.json` (per-profile)
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StatsCache {
     /// Schema version (currently 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<u32>,
     /// Date when stats were last computed (YYYY-MM-DD)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub last_computed_date: Option<String>,
     /// Daily activity metrics
     #[serde(default)]
@@ -33,16 +38,21 @@ pub struct StatsCache {
     #[serde(default)]
     pub daily_model_tokens: Vec<DailyModelTokens>,
     /// Total session count across all time
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub total_sessions: Option<u64>,
     /// Total message count across all time
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub total_messages: Option<u64>,
     /// Model usage summary (alternative to daily_model_tokens)
     #[serde(default)]
     pub model_usage: HashMap<String, ModelUsage>,
+    /// Fields rafctl does not use, kept so a rewritten cache loses nothing
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Daily activity metrics
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyActivity {
     /// Date in YYYY-MM-DD format
@@ -59,7 +69,7 @@ pub struct DailyActivity {
 }
 
 /// Daily token usage by model
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyModelTokens {
     /// Date in YYYY-MM-DD format
@@ -70,7 +80,7 @@ pub struct DailyModelTokens {
 }
 
 /// Model usage summary
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelUsage {
     /// Input tokens used
@@ -84,6 +94,114 @@ pub struct ModelUsage {
     pub cost_usd: f64,
 }
 
+/// Bucket size for [`StatsCache::aggregate_by_period`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl Granularity {
+    /// Period label for a `YYYY-MM-DD` date: the date itself, its ISO week
+    /// (`2026-W02`) or its month (`2026-01`). Unparseable dates are kept as-is.
+    pub fn period_of(&self, date: &str) -> String {
+        let Ok(parsed) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            return date.to_string();
+        };
+        match self {
+            Granularity::Day => date.to_string(),
+            Granularity::Week => {
+                let week = parsed.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            Granularity::Month => parsed.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for Granularity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Granularity::Day => write!(f, "day"),
+            Granularity::Week => write!(f, "week"),
+            Granularity::Month => write!(f, "month"),
+        }
+    }
+}
+
+impl std::str::FromStr for Granularity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "day" => Ok(Granularity::Day),
+            "week" => Ok(Granularity::Week),
+            "month" => Ok(Granularity::Month),
+            _ => Err(format!(
+                "Invalid grouping '{}'. Valid options: day, week, month",
+                s
+            )),
+        }
+    }
+}
+
+/// How [`StatsCache::merge_with`] combines a day present in both caches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// Add the counts; for caches covering different sessions
+    #[default]
+    Sum,
+    /// Keep the larger count; safe when the same usage may be imported twice
+    Max,
+}
+
+impl std::fmt::Display for MergeStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeStrategy::Sum => write!(f, "sum"),
+            MergeStrategy::Max => write!(f, "max"),
+        }
+    }
+}
+
+impl std::str::FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sum" => Ok(MergeStrategy::Sum),
+            "max" => Ok(MergeStrategy::Max),
+            _ => Err(format!(
+                "Invalid merge strategy '{}'. Valid options: sum, max",
+                s
+            )),
+        }
+    }
+}
+
+impl MergeStrategy {
+    fn combine(&self, a: u64, b: u64) -> u64 {
+        match self {
+            MergeStrategy::Sum => a + b,
+            MergeStrategy::Max => a.max(b),
+        }
+    }
+}
+
+/// Activity and token totals for one period
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeriodActivity {
+    pub period: String,
+    pub message_count: u64,
+    pub session_count: u64,
+    pub tool_call_count: u64,
+    pub tokens: u64,
+}
+
 impl StatsCache {
     /// Check if this stats cache is empty (no data)
     pub fn is_empty(&self) -> bool {
@@ -136,12 +254,178 @@ impl StatsCache {
         result
     }
 
+    /// Bucket the last N days of activity (and their tokens) by period, most recent first
+    pub fn aggregate_by_period(
+        &self,
+        days: usize,
+        granularity: Granularity,
+    ) -> Vec<PeriodActivity> {
+        let mut buckets: Vec<PeriodActivity> = Vec::new();
+
+        for activity in self.recent_activity(days) {
+            let period = granularity.period_of(&activity.date);
+            let index = match buckets.iter().position(|b| b.period == period) {
+                Some(index) => index,
+                None => {
+                    buckets.push(PeriodActivity {
+                        period,
+                        ..Default::default()
+                    });
+                    buckets.len() - 1
+                }
+            };
+
+            let bucket = &mut buckets[index];
+            bucket.message_count += activity.message_count;
+            bucket.session_count += activity.session_count;
+            bucket.tool_call_count += activity.tool_call_count;
+            bucket.tokens += self.tokens_for_date(&activity.date);
+        }
+
+        buckets
+    }
+
+    /// Keep only token data for models whose id contains `pattern` (case-insensitive).
+    /// Activity counts are not recorded per model and are left as-is.
+    pub fn filter_models(&self, pattern: &str) -> StatsCache {
+        let pattern = pattern.to_lowercase();
+        let matches = |model: &str| model.to_lowercase().contains(&pattern);
+
+        StatsCache {
+            daily_model_tokens: self
+                .daily_model_tokens
+                .iter()
+                .map(|daily| DailyModelTokens {
+                    date: daily.date.clone(),
+                    tokens_by_model: daily
+                        .tokens_by_model
+                        .iter()
+                        .filter(|(model, _)| matches(model))
+                        .map(|(model, count)| (model.clone(), *count))
+                        .collect(),
+                })
+                .collect(),
+            model_usage: self
+                .model_usage
+                .iter()
+                .filter(|(model, _)| matches(model))
+                .map(|(model, usage)| (model.clone(), usage.clone()))
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Merge token data of dated model snapshots (`claude-sonnet-4-5-20250929`)
+    /// into their base id (`claude-sonnet-4-5`), see [`base_model_id`].
+    pub fn aggregate_models(&self) -> StatsCache {
+        let mut model_usage: HashMap<String, ModelUsage> = HashMap::new();
+        for (model, usage) in &self.model_usage {
+            let merged = model_usage.entry(base_model_id(model)).or_default();
+            merged.input_tokens += usage.input_tokens;
+            merged.output_tokens += usage.output_tokens;
+            merged.cost_usd += usage.cost_usd;
+        }
+
+        StatsCache {
+            daily_model_tokens: self
+                .daily_model_tokens
+                .iter()
+                .map(|daily| {
+                    let mut tokens_by_model = HashMap::new();
+                    for (model, count) in &daily.tokens_by_model {
+                        *tokens_by_model.entry(base_model_id(model)).or_insert(0) += count;
+                    }
+                    DailyModelTokens {
+                        date: daily.date.clone(),
+                        tokens_by_model,
+                    }
+                })
+                .collect(),
+            model_usage,
+            ..self.clone()
+        }
+    }
+
+    /// Add another cache's counts into this one (used by `rafctl profile merge`).
+    /// Days and models present in both are summed.
+    pub fn merge(&mut self, other: &StatsCache) {
+        self.merge_with(other, MergeStrategy::Sum);
+    }
+
+    /// Fold another cache into this one, combining days, models and totals
+    /// present in both according to `strategy`.
+    pub fn merge_with(&mut self, other: &StatsCache, strategy: MergeStrategy) {
+        let combine = |a: u64, b: u64| strategy.combine(a, b);
+
+        for day in &other.daily_activity {
+            match self.daily_activity.iter_mut().find(|d| d.date == day.date) {
+                Some(existing) => {
+                    existing.message_count = combine(existing.message_count, day.message_count);
+                    existing.session_count = combine(existing.session_count, day.session_count);
+                    existing.tool_call_count =
+                        combine(existing.tool_call_count, day.tool_call_count);
+                }
+                None => self.daily_activity.push(day.clone()),
+            }
+        }
+        self.daily_activity.sort_by(|a, b| a.date.cmp(&b.date));
+
+        for day in &other.daily_model_tokens {
+            match self
+                .daily_model_tokens
+                .iter_mut()
+                .find(|d| d.date == day.date)
+            {
+                Some(existing) => {
+                    for (model, count) in &day.tokens_by_model {
+                        let merged = existing.tokens_by_model.entry(model.clone()).or_insert(0);
+                        *merged = combine(*merged, *count);
+                    }
+                }
+                None => self.daily_model_tokens.push(day.clone()),
+            }
+        }
+        self.daily_model_tokens.sort_by(|a, b| a.date.cmp(&b.date));
+
+        for (model, usage) in &other.model_usage {
+            let merged = self.model_usage.entry(model.clone()).or_default();
+            merged.input_tokens = combine(merged.input_tokens, usage.input_tokens);
+            merged.output_tokens = combine(merged.output_tokens, usage.output_tokens);
+            merged.cost_usd = match strategy {
+                MergeStrategy::Sum => merged.cost_usd + usage.cost_usd,
+                MergeStrategy::Max => merged.cost_usd.max(usage.cost_usd),
+            };
+        }
+
+        let total = |a: Option<u64>, b: Option<u64>| match (a, b) {
+            (None, None) => None,
+            _ => Some(combine(a.unwrap_or(0), b.unwrap_or(0))),
+        };
+        self.total_sessions = total(self.total_sessions, other.total_sessions);
+        self.total_messages = total(self.total_messages, other.total_messages);
+        self.last_computed_date = self
+            .last_computed_date
+            .clone()
+            .max(other.last_computed_date.clone());
+        self.version = self.version.or(other.version);
+    }
+
     /// Calculate total tokens across all models for specified days
     pub fn total_tokens(&self, days: Option<usize>) -> u64 {
         self.aggregate_tokens_by_model(days).values().sum()
     }
 }
 
+/// Model id without a trailing `-YYYYMMDD` snapshot date.
+pub fn base_model_id(model: &str) -> String {
+    match model.rsplit_once('-') {
+        Some((base, date)) if date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()) => {
+            base.to_string()
+        }
+        _ => model.to_string(),
+    }
+}
+
 /// Get the global Claude stats cache path (~/.claude/stats-cache.json)
 pub fn get_global_stats_path() -> Result<PathBuf, RafctlError> {
     let home = dirs::home_dir().ok_or(RafctlError::NoHomeDir)?;
@@ -154,6 +438,7 @@ pub fn get_profile_stats_path(profile_name: &str, tool: ToolType) -> Result<Path
     let tool_dir = match tool {
         ToolType::Claude => "claude",
         ToolType::Codex => "codex",
+        ToolType::Gemini => "gemini",
     };
     Ok(profile_dir.join(tool_dir).join("stats-cache.json"))
 }
@@ -177,19 +462,22 @@ pub fn load_stats_cache(path: &PathBuf) -> StatsCache {
         }
     };
 
-    match serde_json::from_str::<StatsCache>(&content) {
-        Ok(stats) => {
-            // Warn if schema version is unexpected
-            if let Some(version) = stats.version {
-                if version != EXPECTED_SCHEMA_VERSION {
-                    eprintln!(
-                        "Warning: stats-cache.json has version {}, expected {}. Parsing anyway.",
-                        version, EXPECTED_SCHEMA_VERSION
-                    );
-                }
-            }
-            stats
+    let parsed = serde_json::from_str::<serde_json::Value>(&content).and_then(|value| {
+        let version = schema_version(&value);
+        if version.is_some_and(|v| v > EXPECTED_SCHEMA_VERSION) {
+            eprintln!(
+                "Warning: {} uses stats schema version {}, newer than this rafctl supports ({}). \
+                 Analytics may be incomplete; upgrade rafctl.",
+                path.display(),
+                version.unwrap_or_default(),
+                EXPECTED_SCHEMA_VERSION
+            );
         }
+        serde_json::from_value::<StatsCache>(migrate_stats(value))
+    });
+
+    match parsed {
+        Ok(stats) => stats,
         Err(e) => {
             eprintln!(
                 "Warning: Failed to parse stats cache at {}: {}",
@@ -201,6 +489,95 @@ pub fn load_stats_cache(path: &PathBuf) -> StatsCache {
     }
 }
 
+fn schema_version(value: &serde_json::Value) -> Option<u32> {
+    value
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+}
+
+/// Bring an older stats-cache layout up to the current schema.
+///
+/// Version 0 (and unversioned) caches wrote snake_case keys
+/// (`daily_activity`, `tokens_by_model`, ...); they are renamed to the
+/// camelCase keys of version 1. Current and newer layouts pass through
+/// unchanged, newer ones parsed on a best-effort basis.
+pub fn migrate_stats(mut value: serde_json::Value) -> serde_json::Value {
+    match schema_version(&value) {
+        None | Some(0) => {
+            camel_case_keys(&mut value);
+            if let Some(obj) = value.as_object_mut() {
+                obj.insert("version".to_string(), EXPECTED_SCHEMA_VERSION.into());
+            }
+            value
+        }
+        Some(_) => value,
+    }
+}
+
+/// Rename snake_case object keys to camelCase, recursively. Keys without an
+/// underscore (including model ids such as `claude-sonnet-4-5`) are untouched.
+fn camel_case_keys(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let entries = std::mem::take(map);
+            for (key, mut child) in entries {
+                camel_case_keys(&mut child);
+                map.insert(snake_to_camel(&key), child);
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(camel_case_keys),
+        _ => {}
+    }
+}
+
+fn snake_to_camel(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' && !out.is_empty() {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Read a stats cache that must exist and parse, e.g. one given on the
+/// command line. Older layouts are migrated like in [`load_stats_cache`].
+pub fn read_stats_cache(path: &Path) -> Result<StatsCache, RafctlError> {
+    let content = fs::read_to_string(path).map_err(|e| RafctlError::ConfigRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    serde_json::from_str::<serde_json::Value>(&content)
+        .and_then(|value| serde_json::from_value(migrate_stats(value)))
+        .map_err(|e| RafctlError::ConfigRead {
+            path: path.to_path_buf(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        })
+}
+
+/// Write a stats cache, creating its parent directory if needed
+pub fn save_stats_cache(path: &Path, stats: &StatsCache) -> Result<(), RafctlError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| RafctlError::ConfigWrite {
+            path: parent.to_path_buf(),
+            source: e,
+        })?;
+    }
+
+    let content = serde_json::to_string_pretty(stats).map_err(|e| RafctlError::ConfigWrite {
+        path: path.to_path_buf(),
+        source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+    })?;
+    atomic_write(path, &content)
+}
+
 /// Load stats cache for a profile, falling back to global if not found
 pub fn load_profile_stats(profile_name: &str, tool: ToolType) -> StatsCache {
     // Try profile-specific first
@@ -226,6 +603,109 @@ pub fn load_global_stats() -> StatsCache {
     }
 }
 
+/// Cost of one session, recorded by `rafctl run --capture-cost` from the
+/// session transcript's token usage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostEntry {
+    pub recorded_at: DateTime<Utc>,
+    pub session_id: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub input_cost: f64,
+    pub output_cost: f64,
+}
+
+impl CostEntry {
+    pub fn total_cost(&self) -> f64 {
+        self.input_cost + self.output_cost
+    }
+}
+
+pub fn cost_ledger_path(profile_name: &str) -> Result<PathBuf, RafctlError> {
+    Ok(get_profile_dir(profile_name)?.join(COSTS_FILE))
+}
+
+pub fn append_cost_entry(profile_name: &str, entry: &CostEntry) -> Result<(), RafctlError> {
+    let path = cost_ledger_path(profile_name)?;
+    let line = serde_json::to_string(entry).map_err(|e| RafctlError::ConfigWrite {
+        path: path.clone(),
+        source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+    })?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| RafctlError::ConfigWrite {
+            path: path.clone(),
+            source: e,
+        })?;
+    writeln!(file, "{}", line).map_err(|e| RafctlError::ConfigWrite { path, source: e })
+}
+
+/// Move `from`'s cost ledger entries into `into`'s ledger (used by
+/// `rafctl profile merge`), keeping it ordered by time. Returns how many
+/// entries were added.
+pub fn merge_cost_ledger(from_profile: &str, into_profile: &str) -> Result<usize, RafctlError> {
+    merge_cost_ledger_files(
+        &cost_ledger_path(from_profile)?,
+        &cost_ledger_path(into_profile)?,
+    )
+}
+
+fn merge_cost_ledger_files(from: &Path, into: &Path) -> Result<usize, RafctlError> {
+    let read = |path: &Path| -> Vec<CostEntry> {
+        fs::read_to_string(path)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let added = read(from);
+    if added.is_empty() {
+        return Ok(0);
+    }
+
+    let mut entries = read(into);
+    entries.extend(added.iter().cloned());
+    entries.sort_by_key(|e| e.recorded_at);
+
+    let content: String = entries
+        .iter()
+        .filter_map(|e| serde_json::to_string(e).ok())
+        .map(|line| line + "\n")
+        .collect();
+    fs::write(into, content).map_err(|e| RafctlError::ConfigWrite {
+        path: into.to_path_buf(),
+        source: e,
+    })?;
+
+    Ok(added.len())
+}
+
+/// Ledger entries recorded in the last `days` days, oldest first.
+/// Unparseable lines are skipped.
+pub fn load_cost_entries(profile_name: &str, days: usize) -> Vec<CostEntry> {
+    let Ok(path) = cost_ledger_path(profile_name) else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+
+    let cutoff = Utc::now() - Duration::days(days as i64);
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CostEntry>(line).ok())
+        .filter(|entry| entry.recorded_at >= cutoff)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -297,6 +777,101 @@ mod tests {
         assert_eq!(aggregated.get("claude-opus-4-5"), Some(&50000));
     }
 
+    #[test]
+    fn test_filter_models() {
+        let stats: StatsCache = serde_json::from_str(SAMPLE_STATS_JSON).unwrap();
+        let opus = stats.filter_models("OPUS");
+
+        let aggregated = opus.aggregate_tokens_by_model(None);
+        assert_eq!(aggregated.len(), 1);
+        assert_eq!(aggregated.get("claude-opus-4-5"), Some(&50000));
+        assert_eq!(opus.tokens_for_date("2026-01-06"), 50000);
+        assert_eq!(opus.tokens_for_date("2026-01-05"), 0);
+        assert!(opus.model_usage.is_empty());
+        assert_eq!(opus.daily_activity.len(), 2);
+    }
+
+    #[test]
+    fn test_aggregate_models() {
+        let stats: StatsCache = serde_json::from_str(
+            r#"{
+                "dailyModelTokens": [
+                    {"date": "2026-01-06", "tokensByModel": {"claude-sonnet-4-5-20250929": 1000, "claude-sonnet-4-5": 200}},
+                    {"date": "2026-01-05", "tokensByModel": {"claude-opus-4-5-20251101": 50}}
+                ],
+                "modelUsage": {
+                    "claude-sonnet-4-5-20250929": {"inputTokens": 10, "outputTokens": 1},
+                    "claude-sonnet-4-5": {"inputTokens": 5, "outputTokens": 2}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let merged = stats.aggregate_models();
+        let tokens = merged.aggregate_tokens_by_model(None);
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens.get("claude-sonnet-4-5"), Some(&1200));
+        assert_eq!(tokens.get("claude-opus-4-5"), Some(&50));
+        assert_eq!(merged.model_usage["claude-sonnet-4-5"].input_tokens, 15);
+    }
+
+    #[test]
+    fn test_base_model_id() {
+        assert_eq!(
+            base_model_id("claude-sonnet-4-5-20250929"),
+            "claude-sonnet-4-5"
+        );
+        assert_eq!(base_model_id("claude-sonnet-4-5"), "claude-sonnet-4-5");
+        assert_eq!(
+            base_model_id("claude-3-5-haiku-latest"),
+            "claude-3-5-haiku-latest"
+        );
+        assert_eq!(base_model_id("model-2025"), "model-2025");
+    }
+
+    #[test]
+    fn test_period_of() {
+        assert_eq!(Granularity::Day.period_of("2026-01-06"), "2026-01-06");
+        assert_eq!(Granularity::Week.period_of("2026-01-06"), "2026-W02");
+        assert_eq!(Granularity::Week.period_of("2026-01-01"), "2026-W01");
+        assert_eq!(Granularity::Week.period_of("2027-01-01"), "2026-W53");
+        assert_eq!(Granularity::Month.period_of("2026-01-06"), "2026-01");
+        assert_eq!(Granularity::Month.period_of("garbage"), "garbage");
+    }
+
+    #[test]
+    fn test_aggregate_by_period() {
+        let stats: StatsCache = serde_json::from_str(
+            r#"{
+                "dailyActivity": [
+                    {"date": "2026-01-06", "messageCount": 10, "sessionCount": 2, "toolCallCount": 5},
+                    {"date": "2026-01-05", "messageCount": 4, "sessionCount": 1, "toolCallCount": 1},
+                    {"date": "2026-01-02", "messageCount": 7, "sessionCount": 3, "toolCallCount": 2},
+                    {"date": "2025-12-30", "messageCount": 1, "sessionCount": 1, "toolCallCount": 0}
+                ],
+                "dailyModelTokens": [
+                    {"date": "2026-01-06", "tokensByModel": {"claude-sonnet-4-5": 1000}},
+                    {"date": "2026-01-05", "tokensByModel": {"claude-sonnet-4-5": 500}},
+                    {"date": "2025-12-30", "tokensByModel": {"claude-opus-4-5": 200}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let weeks = stats.aggregate_by_period(30, Granularity::Week);
+        let periods: Vec<&str> = weeks.iter().map(|w| w.period.as_str()).collect();
+        assert_eq!(periods, vec!["2026-W02", "2026-W01"]);
+        assert_eq!(weeks[0].message_count, 14);
+        assert_eq!(weeks[0].tokens, 1500);
+        assert_eq!(weeks[1].session_count, 4);
+        assert_eq!(weeks[1].tokens, 200);
+
+        let months = stats.aggregate_by_period(30, Granularity::Month);
+        let periods: Vec<&str> = months.iter().map(|m| m.period.as_str()).collect();
+        assert_eq!(periods, vec!["2026-01", "2025-12"]);
+        assert_eq!(months[0].tool_call_count, 8);
+    }
+
     #[test]
     fn test_total_tokens() {
         let stats: StatsCache = serde_json::from_str(SAMPLE_STATS_JSON).unwrap();
@@ -314,6 +889,64 @@ mod tests {
         assert!(!stats.is_empty());
     }
 
+    #[test]
+    fn test_migrate_stats_v0_snake_case() {
+        let v0 = serde_json::json!({
+            "version": 0,
+            "last_computed_date": "2026-01-06",
+            "daily_activity": [{"date": "2026-01-06", "message_count": 7, "session_count": 2}],
+            "daily_model_tokens": [
+                {"date": "2026-01-06", "tokens_by_model": {"claude-sonnet-4-5": 1200}}
+            ],
+            "model_usage": {"claude-sonnet-4-5": {"input_tokens": 1000, "output_tokens": 200}}
+        });
+
+        let stats: StatsCache = serde_json::from_value(migrate_stats(v0)).unwrap();
+        assert_eq!(stats.version, Some(EXPECTED_SCHEMA_VERSION));
+        assert_eq!(stats.last_computed_date.as_deref(), Some("2026-01-06"));
+        assert_eq!(stats.daily_activity[0].message_count, 7);
+        assert_eq!(stats.tokens_for_date("2026-01-06"), 1200);
+        assert_eq!(stats.model_usage["claude-sonnet-4-5"].output_tokens, 200);
+        assert!(stats.extra.is_empty());
+    }
+
+    #[test]
+    fn test_stats_cache_round_trips() {
+        let mut stats: StatsCache = serde_json::from_str(SAMPLE_STATS_JSON).unwrap();
+        stats.model_usage.insert(
+            "claude-opus-4-5".to_string(),
+            ModelUsage {
+                input_tokens: 1000,
+                output_tokens: 200,
+                cost_usd: 0.5,
+            },
+        );
+        stats
+            .extra
+            .insert("futureField".to_string(), serde_json::json!({"a": 1}));
+
+        let json = serde_json::to_string_pretty(&stats).unwrap();
+        let parsed: StatsCache = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, stats);
+    }
+
+    #[test]
+    fn test_migrate_stats_keeps_current_and_newer() {
+        let current: serde_json::Value = serde_json::from_str(SAMPLE_STATS_JSON).unwrap();
+        assert_eq!(migrate_stats(current.clone()), current);
+
+        let newer = serde_json::json!({"version": 2, "some_new_field": 1});
+        assert_eq!(migrate_stats(newer.clone()), newer);
+    }
+
+    #[test]
+    fn test_snake_to_camel() {
+        assert_eq!(snake_to_camel("tokens_by_model"), "tokensByModel");
+        assert_eq!(snake_to_camel("date"), "date");
+        assert_eq!(snake_to_camel("claude-opus-4-5"), "claude-opus-4-5");
+        assert_eq!(snake_to_camel("_private"), "_private");
+    }
+
     #[test]
     fn test_parse_empty_json() {
         let empty: StatsCache = serde_json::from_str("{}").unwrap();
@@ -339,4 +972,83 @@ mod tests {
         assert_eq!(usage.input_tokens, 2508205);
         assert_eq!(usage.output_tokens, 15554917);
     }
+
+    #[test]
+    fn test_merge_with_max_does_not_double_count() {
+        let cache: StatsCache = serde_json::from_str(
+            r#"{
+                "totalSessions": 3,
+                "dailyActivity": [{"date": "2026-01-05", "messageCount": 10, "sessionCount": 1}],
+                "dailyModelTokens": [{"date": "2026-01-05", "tokensByModel": {"claude-opus-4-5": 100}}]
+            }"#,
+        )
+        .unwrap();
+        let newer: StatsCache = serde_json::from_str(
+            r#"{
+                "totalSessions": 4,
+                "dailyActivity": [
+                    {"date": "2026-01-05", "messageCount": 12, "sessionCount": 1},
+                    {"date": "2026-01-06", "messageCount": 2}
+                ],
+                "dailyModelTokens": [{"date": "2026-01-05", "tokensByModel": {"claude-opus-4-5": 90}}]
+            }"#,
+        )
+        .unwrap();
+
+        let mut into = cache.clone();
+        into.merge_with(&newer, MergeStrategy::Max);
+        // Importing the same file again changes nothing
+        into.merge_with(&newer, MergeStrategy::Max);
+
+        assert_eq!(into.total_sessions, Some(4));
+        assert_eq!(into.daily_activity.len(), 2);
+        assert_eq!(into.daily_activity[0].message_count, 12);
+        assert_eq!(into.tokens_for_date("2026-01-05"), 100);
+
+        assert_eq!("MAX".parse::<MergeStrategy>(), Ok(MergeStrategy::Max));
+        assert!("avg".parse::<MergeStrategy>().is_err());
+    }
+
+    #[test]
+    fn test_merge_sums_overlapping_days() {
+        let mut into: StatsCache = serde_json::from_str(
+            r#"{
+                "totalSessions": 2,
+                "lastComputedDate": "2026-01-05",
+                "longestSession": {"duration": 10},
+                "dailyActivity": [{"date": "2026-01-05", "messageCount": 10, "sessionCount": 1}],
+                "dailyModelTokens": [{"date": "2026-01-05", "tokensByModel": {"claude-opus-4-5": 100}}],
+                "modelUsage": {"claude-opus-4-5": {"inputTokens": 5, "outputTokens": 7}}
+            }"#,
+        )
+        .unwrap();
+        let from: StatsCache = serde_json::from_str(
+            r#"{
+                "totalSessions": 3,
+                "lastComputedDate": "2026-01-06",
+                "dailyActivity": [
+                    {"date": "2026-01-04", "messageCount": 1},
+                    {"date": "2026-01-05", "messageCount": 5, "sessionCount": 2}
+                ],
+                "dailyModelTokens": [{"date": "2026-01-05", "tokensByModel": {"claude-opus-4-5": 50, "claude-sonnet-4-5": 20}}],
+                "modelUsage": {"claude-opus-4-5": {"inputTokens": 1, "outputTokens": 1}}
+            }"#,
+        )
+        .unwrap();
+
+        into.merge(&from);
+
+        assert_eq!(into.total_sessions, Some(5));
+        assert_eq!(into.last_computed_date.as_deref(), Some("2026-01-06"));
+        assert_eq!(into.daily_activity.len(), 2);
+        assert_eq!(into.daily_activity[0].date, "2026-01-04");
+        assert_eq!(into.daily_activity[1].message_count, 15);
+        assert_eq!(into.daily_activity[1].session_count, 3);
+        assert_eq!(into.tokens_for_date("2026-01-05"), 170);
+        assert_eq!(into.model_usage["claude-opus-4-5"].input_tokens, 6);
+
+        let json = serde_json::to_value(&into).unwrap();
+        assert_eq!(json["longestSession"]["duration"], 10);
+        assert_eq!(json["dailyActivity"][1]["messageCount"], 15);
+    }
 }
@@ -5,8 +5,10 @@
 //! - Tool usage (name, target, status, duration)
 //! - Agent calls (subagent_type, description)
 //! - Error counts
+//! - Token usage (from assistant message `usage` blocks)
+//! - Ordered conversation events (for replay)
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
@@ -15,6 +17,9 @@ use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use serde_json::Value;
 
+use crate::core::profile::{get_profile_dir, Profile};
+use crate::error::RafctlError;
+
 #[derive(Debug, Clone)]
 pub struct SessionSummary {
     pub session_id: String,
@@ -28,6 +33,20 @@ pub struct SessionSummary {
     pub tool_errors: u64,
     pub agent_calls: u64,
     pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// Whether any assistant message carried a `usage` block; older
+    /// transcripts have none, so zero token counts are unknown rather than free
+    pub has_usage: bool,
+}
+
+impl SessionSummary {
+    /// All tokens billed for the session, including cache reads and writes
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -55,6 +74,26 @@ pub struct SessionDetail {
     pub tool_breakdown: HashMap<String, u64>,
 }
 
+/// Kind of a single step in a session transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEventKind {
+    UserMessage,
+    AssistantMessage,
+    /// Tool invocation (tool name)
+    ToolCall(String),
+    /// Tool output (tool name, if the matching call was seen)
+    ToolResult(Option<String>),
+}
+
+/// One ordered step of a session, as produced by [`parse_transcript_full`].
+#[derive(Debug, Clone)]
+pub struct SessionEvent {
+    pub kind: SessionEventKind,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub content: String,
+    pub is_error: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct TranscriptEntry {
     #[serde(rename = "type")]
@@ -70,10 +109,24 @@ struct TranscriptEntry {
 
 #[derive(Debug, Deserialize)]
 struct TranscriptMessage {
+    id: Option<String>,
     #[allow(dead_code)]
     role: Option<String>,
     model: Option<String>,
     content: Option<Value>,
+    usage: Option<MessageUsage>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MessageUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -105,12 +158,21 @@ pub fn parse_transcript(path: &Path) -> Option<SessionDetail> {
         tool_errors: 0,
         agent_calls: 0,
         model: None,
+        input_tokens: 0,
+        output_tokens: 0,
+        cache_creation_tokens: 0,
+        cache_read_tokens: 0,
+        has_usage: false,
     };
 
     let mut tool_calls: Vec<ToolCall> = Vec::new();
+    // One API response can be split over several entries that repeat its usage
+    let mut counted_messages: HashSet<String> = HashSet::new();
     let mut agent_calls: Vec<AgentCall> = Vec::new();
     let mut tool_breakdown: HashMap<String, u64> = HashMap::new();
-    let mut pending_tools: HashMap<String, ToolCall> = HashMap::new();
+    // Index into `tool_calls` of calls still waiting for their result, so
+    // calls stay in transcript order
+    let mut pending_tools: HashMap<String, usize> = HashMap::new();
 
     for line in reader.lines() {
         let line = match line {
@@ -163,6 +225,20 @@ pub fn parse_transcript(path: &Path) -> Option<SessionDetail> {
                 summary.model = msg.model.clone();
             }
 
+            if let Some(usage) = &msg.usage {
+                summary.has_usage = true;
+                let first_seen = match &msg.id {
+                    Some(id) => counted_messages.insert(id.clone()),
+                    None => true,
+                };
+                if first_seen {
+                    summary.input_tokens += usage.input_tokens;
+                    summary.output_tokens += usage.output_tokens;
+                    summary.cache_creation_tokens += usage.cache_creation_input_tokens;
+                    summary.cache_read_tokens += usage.cache_read_input_tokens;
+                }
+            }
+
             if let Some(content) = &msg.content {
                 if let Some(blocks) = content.as_array() {
                     for block in blocks {
@@ -199,15 +275,15 @@ pub fn parse_transcript(path: &Path) -> Option<SessionDetail> {
                                         summary.tool_calls += 1;
                                         *tool_breakdown.entry(name.clone()).or_insert(0) += 1;
 
-                                        let tool_call = ToolCall {
-                                            id: id.clone(),
+                                        pending_tools.insert(id.clone(), tool_calls.len());
+                                        tool_calls.push(ToolCall {
+                                            id,
                                             name,
                                             target,
                                             timestamp,
                                             is_error: false,
                                             duration_ms: None,
-                                        };
-                                        pending_tools.insert(id, tool_call);
+                                        });
                                     }
                                 }
                             }
@@ -216,7 +292,9 @@ pub fn parse_transcript(path: &Path) -> Option<SessionDetail> {
                                     serde_json::from_value::<ToolResultBlock>(block.clone())
                                 {
                                     if let Some(tool_id) = result.tool_use_id {
-                                        if let Some(mut tool_call) = pending_tools.remove(&tool_id)
+                                        if let Some(tool_call) = pending_tools
+                                            .remove(&tool_id)
+                                            .and_then(|i| tool_calls.get_mut(i))
                                         {
                                             let is_error = result.is_error.unwrap_or(false);
                                             tool_call.is_error = is_error;
@@ -230,7 +308,6 @@ pub fn parse_transcript(path: &Path) -> Option<SessionDetail> {
                                                     Some((end - start).num_milliseconds().max(0)
                                                         as u64);
                                             }
-                                            tool_calls.push(tool_call);
                                         }
                                     }
                                 }
@@ -243,10 +320,6 @@ pub fn parse_transcript(path: &Path) -> Option<SessionDetail> {
         }
     }
 
-    for (_, tool_call) in pending_tools {
-        tool_calls.push(tool_call);
-    }
-
     if summary.session_id.is_empty() {
         return None;
     }
@@ -259,6 +332,134 @@ pub fn parse_transcript(path: &Path) -> Option<SessionDetail> {
     })
 }
 
+/// Parse a transcript into its ordered events: user turns, assistant text,
+/// tool calls and tool results, in the order they were written.
+pub fn parse_transcript_full(path: &Path) -> Option<Vec<SessionEvent>> {
+    let file = File::open(path).ok()?;
+    let reader = BufReader::new(file);
+
+    let mut events: Vec<SessionEvent> = Vec::new();
+    let mut tool_names: HashMap<String, String> = HashMap::new();
+
+    for line in reader.lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => continue,
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let entry: TranscriptEntry = match serde_json::from_str(&line) {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+
+        let timestamp = entry
+            .timestamp
+            .as_ref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+
+        let is_user = match entry.entry_type.as_deref() {
+            Some("user") => true,
+            Some("assistant") => false,
+            _ => continue,
+        };
+
+        let Some(content) = entry.message.and_then(|m| m.content) else {
+            continue;
+        };
+
+        let text_kind = if is_user {
+            SessionEventKind::UserMessage
+        } else {
+            SessionEventKind::AssistantMessage
+        };
+
+        if let Some(text) = content.as_str() {
+            events.push(SessionEvent {
+                kind: text_kind,
+                timestamp,
+                content: text.to_string(),
+                is_error: false,
+            });
+            continue;
+        }
+
+        let Some(blocks) = content.as_array() else {
+            continue;
+        };
+
+        for block in blocks {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => {
+                    let text = block.get("text").and_then(|t| t.as_str()).unwrap_or("");
+                    if !text.trim().is_empty() {
+                        events.push(SessionEvent {
+                            kind: text_kind.clone(),
+                            timestamp,
+                            content: text.to_string(),
+                            is_error: false,
+                        });
+                    }
+                }
+                Some("tool_use") => {
+                    if let Ok(tool_use) = serde_json::from_value::<ToolUseBlock>(block.clone()) {
+                        let name = tool_use.name.unwrap_or_default();
+                        if let Some(id) = tool_use.id {
+                            tool_names.insert(id, name.clone());
+                        }
+                        let content = tool_use
+                            .input
+                            .as_ref()
+                            .and_then(|i| serde_json::to_string_pretty(i).ok())
+                            .unwrap_or_default();
+                        events.push(SessionEvent {
+                            kind: SessionEventKind::ToolCall(name),
+                            timestamp,
+                            content,
+                            is_error: false,
+                        });
+                    }
+                }
+                Some("tool_result") => {
+                    let name = block
+                        .get("tool_use_id")
+                        .and_then(|id| id.as_str())
+                        .and_then(|id| tool_names.get(id).cloned());
+                    events.push(SessionEvent {
+                        kind: SessionEventKind::ToolResult(name),
+                        timestamp,
+                        content: block.get("content").map(content_text).unwrap_or_default(),
+                        is_error: block
+                            .get("is_error")
+                            .and_then(|e| e.as_bool())
+                            .unwrap_or(false),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Some(events)
+}
+
+/// Flatten tool result content, which is either a string or a list of text blocks.
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(s) => s.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
 fn extract_tool_target(tool_name: &str, input: &Option<Value>) -> Option<String> {
     let input = input.as_ref()?;
 
@@ -293,7 +494,8 @@ fn truncate_path(path: &str) -> String {
     }
 }
 
-fn truncate_str(s: &str, max_len: usize) -> String {
+/// Cut `s` to at most `max_len` characters, ending in `...` when shortened.
+pub fn truncate_str(s: &str, max_len: usize) -> String {
     if s.chars().count() <= max_len {
         s.to_string()
     } else {
@@ -326,18 +528,164 @@ pub fn list_sessions(project_dir: &Path) -> Vec<PathBuf> {
     sessions
 }
 
+/// List session transcripts across every project under a transcripts dir.
+pub fn list_all_sessions(transcripts_dir: &Path) -> Vec<PathBuf> {
+    let mut sessions: Vec<PathBuf> = Vec::new();
+
+    if let Ok(projects) = std::fs::read_dir(transcripts_dir) {
+        for project in projects.flatten() {
+            let project_path = project.path();
+            if project_path.is_dir() {
+                sessions.extend(list_sessions(&project_path));
+            }
+        }
+    }
+
+    sessions
+}
+
+/// The most recently modified session transcript under a transcripts dir.
+pub fn newest_session(transcripts_dir: &Path) -> Option<PathBuf> {
+    list_all_sessions(transcripts_dir)
+        .into_iter()
+        .max_by_key(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+}
+
+/// Newest session transcript modified at or after `since`, e.g. the one a run just wrote.
+pub fn newest_session_since(transcripts_dir: &Path, since: DateTime<Utc>) -> Option<PathBuf> {
+    newest_session(transcripts_dir).filter(|path| {
+        std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map(|modified| DateTime::<Utc>::from(modified) >= since)
+            .unwrap_or(false)
+    })
+}
+
+/// Session transcripts whose id contains `query`, across every project.
+pub fn find_sessions_matching(transcripts_dir: &Path, query: &str) -> Vec<PathBuf> {
+    list_all_sessions(transcripts_dir)
+        .into_iter()
+        .filter(|path| {
+            path.file_stem()
+                .and_then(|s| s.to_str())
+                .map(|id| id.contains(query))
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
 pub fn get_global_transcripts_dir() -> Option<PathBuf> {
     dirs::home_dir().map(|h| h.join(".claude").join("projects"))
 }
 
+/// Transcripts dir of an isolated profile (`CLAUDE_CONFIG_DIR` is the profile dir).
 pub fn get_profile_transcripts_dir(profile_name: &str) -> Option<PathBuf> {
-    dirs::home_dir().map(|h| {
-        h.join(".rafctl")
-            .join("profiles")
-            .join(profile_name)
-            .join("claude")
-            .join("projects")
-    })
+    get_profile_dir(profile_name)
+        .ok()
+        .map(|dir| dir.join("projects"))
+}
+
+/// Transcripts dir Claude Code writes to for this profile: its own `projects/`
+/// when isolated, the global one when sharing the tool's config dir.
+pub fn transcripts_dir_for(profile: &Profile) -> Result<PathBuf, RafctlError> {
+    let dir = if profile.is_isolated() {
+        get_profile_transcripts_dir(&profile.name)
+    } else {
+        get_global_transcripts_dir()
+    };
+    dir.ok_or(RafctlError::NoHomeDir)
+}
+
+/// Directory name Claude Code uses for a project path under `projects/`:
+/// every non-alphanumeric character becomes `-`.
+pub fn encode_project_path(project: &Path) -> String {
+    project
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Move every `.jsonl` transcript from `src_dir` into `dest_dir`.
+///
+/// Files that already exist in `dest_dir` are left in place. Returns the
+/// destination paths of the moved files.
+pub fn move_transcripts(src_dir: &Path, dest_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dest_dir)?;
+    let mut moved = Vec::new();
+
+    for entry in std::fs::read_dir(src_dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().map(|e| e != "jsonl").unwrap_or(true) {
+            continue;
+        }
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        let dest = dest_dir.join(file_name);
+        if dest.exists() {
+            continue;
+        }
+
+        // rename fails across filesystems (e.g. RAFCTL_CONFIG_DIR on another mount)
+        if std::fs::rename(&path, &dest).is_err() {
+            std::fs::copy(&path, &dest)?;
+            std::fs::remove_file(&path)?;
+        }
+        moved.push(dest);
+    }
+
+    moved.sort();
+    Ok(moved)
+}
+
+/// Move the transcripts of every project under `src_root` into the same
+/// project dir under `dest_root`. Returns `(moved, left_behind)`, where
+/// left-behind files already existed at the destination.
+pub fn move_all_transcripts(src_root: &Path, dest_root: &Path) -> std::io::Result<(usize, usize)> {
+    let mut moved = 0;
+    let mut left_behind = 0;
+
+    let Ok(projects) = std::fs::read_dir(src_root) else {
+        return Ok((0, 0));
+    };
+    for project in projects.flatten() {
+        let project_dir = project.path();
+        if !project_dir.is_dir() {
+            continue;
+        }
+        moved += move_transcripts(&project_dir, &dest_root.join(project.file_name()))?.len();
+        left_behind += std::fs::read_dir(&project_dir)?
+            .flatten()
+            .filter(|e| e.path().extension().is_some_and(|x| x == "jsonl"))
+            .count();
+    }
+
+    Ok((moved, left_behind))
+}
+
+/// Count transcripts under `src_root` whose file already exists in the same
+/// project dir under `dest_root`, i.e. the ones [`move_all_transcripts`] would
+/// leave behind.
+pub fn count_conflicting_transcripts(src_root: &Path, dest_root: &Path) -> usize {
+    let Ok(projects) = std::fs::read_dir(src_root) else {
+        return 0;
+    };
+    projects
+        .flatten()
+        .filter(|project| project.path().is_dir())
+        .flat_map(|project| {
+            let dest_dir = dest_root.join(project.file_name());
+            std::fs::read_dir(project.path())
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(move |e| {
+                    e.path().extension().is_some_and(|x| x == "jsonl")
+                        && dest_dir.join(e.file_name()).exists()
+                })
+        })
+        .count()
 }
 
 #[cfg(test)]
@@ -388,6 +736,119 @@ mod tests {
         assert_eq!(target, Some("fn main".to_string()));
     }
 
+    #[test]
+    fn test_parse_transcript_full_orders_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let lines = [
+            r#"{"type":"user","sessionId":"s1","timestamp":"2026-01-06T10:00:00Z","message":{"role":"user","content":"fix the build"}}"#,
+            r#"{"type":"assistant","sessionId":"s1","timestamp":"2026-01-06T10:00:05Z","message":{"role":"assistant","content":[{"type":"text","text":"Running cargo"},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo build"}}]}}"#,
+            r#"{"type":"user","sessionId":"s1","timestamp":"2026-01-06T10:00:09Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","is_error":true,"content":[{"type":"text","text":"error[E0425]"}]}]}}"#,
+            r#"{"type":"summary","summary":"ignored"}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let events = parse_transcript_full(&path).unwrap();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].kind, SessionEventKind::UserMessage);
+        assert_eq!(events[0].content, "fix the build");
+        assert_eq!(events[1].kind, SessionEventKind::AssistantMessage);
+        assert_eq!(
+            events[2].kind,
+            SessionEventKind::ToolCall("Bash".to_string())
+        );
+        assert!(events[2].content.contains("cargo build"));
+        assert_eq!(
+            events[3].kind,
+            SessionEventKind::ToolResult(Some("Bash".to_string()))
+        );
+        assert!(events[3].is_error);
+        assert_eq!(events[3].content, "error[E0425]");
+    }
+
+    #[test]
+    fn test_find_sessions_matching() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_a = dir.path().join("-Users-me-a");
+        let project_b = dir.path().join("-Users-me-b");
+        std::fs::create_dir_all(&project_a).unwrap();
+        std::fs::create_dir_all(&project_b).unwrap();
+        std::fs::write(project_a.join("abc123-0001.jsonl"), "").unwrap();
+        std::fs::write(project_b.join("abc456-0002.jsonl"), "").unwrap();
+        std::fs::write(project_b.join("agent-abc789.jsonl"), "").unwrap();
+
+        assert_eq!(find_sessions_matching(dir.path(), "abc").len(), 2);
+        let exact = find_sessions_matching(dir.path(), "0002");
+        assert_eq!(exact, vec![project_b.join("abc456-0002.jsonl")]);
+        assert!(find_sessions_matching(dir.path(), "zzz").is_empty());
+    }
+
+    #[test]
+    fn test_newest_session_since() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("-Users-me-a");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join("s1.jsonl"), "").unwrap();
+
+        let before = Utc::now() - chrono::Duration::minutes(1);
+        let after = Utc::now() + chrono::Duration::minutes(1);
+
+        assert_eq!(
+            newest_session_since(dir.path(), before),
+            Some(project.join("s1.jsonl"))
+        );
+        assert!(newest_session_since(dir.path(), after).is_none());
+    }
+
+    #[test]
+    fn test_encode_project_path() {
+        assert_eq!(
+            encode_project_path(Path::new("/Users/me/my.app")),
+            "-Users-me-my-app"
+        );
+    }
+
+    #[test]
+    fn test_move_transcripts_skips_existing() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        let dest = dir.path().join("dest");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::create_dir_all(&dest).unwrap();
+        std::fs::write(src.join("a.jsonl"), "a").unwrap();
+        std::fs::write(src.join("b.jsonl"), "new").unwrap();
+        std::fs::write(src.join("notes.txt"), "").unwrap();
+        std::fs::write(dest.join("b.jsonl"), "old").unwrap();
+
+        let moved = move_transcripts(&src, &dest).unwrap();
+        assert_eq!(moved, vec![dest.join("a.jsonl")]);
+        assert!(!src.join("a.jsonl").exists());
+        assert!(src.join("b.jsonl").exists());
+        assert_eq!(
+            std::fs::read_to_string(dest.join("b.jsonl")).unwrap(),
+            "old"
+        );
+    }
+
+    #[test]
+    fn test_count_conflicting_transcripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        let dest = dir.path().join("dest");
+        std::fs::create_dir_all(src.join("-app")).unwrap();
+        std::fs::create_dir_all(dest.join("-app")).unwrap();
+        std::fs::write(src.join("-app/a.jsonl"), "a").unwrap();
+        std::fs::write(src.join("-app/b.jsonl"), "new").unwrap();
+        std::fs::write(dest.join("-app/b.jsonl"), "old").unwrap();
+
+        assert_eq!(count_conflicting_transcripts(&src, &dest), 1);
+        assert_eq!(count_conflicting_transcripts(&dest, &src), 1);
+        assert_eq!(
+            count_conflicting_transcripts(&dir.path().join("x"), &dest),
+            0
+        );
+    }
+
     #[test]
     fn test_session_summary_default() {
         let summary = SessionSummary {
@@ -402,10 +863,52 @@ mod tests {
             tool_errors: 1,
             agent_calls: 2,
             model: Some("claude-sonnet".to_string()),
+            input_tokens: 100,
+            output_tokens: 50,
+            cache_creation_tokens: 10,
+            cache_read_tokens: 1000,
+            has_usage: true,
         };
 
         assert_eq!(summary.session_id, "test-123");
         assert_eq!(summary.message_count, 10);
         assert_eq!(summary.tool_errors, 1);
+        assert_eq!(summary.total_tokens(), 1160);
+    }
+
+    #[test]
+    fn test_parse_transcript_token_usage_deduplicates_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let lines = [
+            r#"{"type":"user","sessionId":"s1","message":{"role":"user","content":"hi"}}"#,
+            r#"{"type":"assistant","sessionId":"s1","message":{"id":"msg_1","role":"assistant","content":[{"type":"text","text":"a"}],"usage":{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":100}}}"#,
+            r#"{"type":"assistant","sessionId":"s1","message":{"id":"msg_1","role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Read","input":{}}],"usage":{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":100}}}"#,
+            r#"{"type":"assistant","sessionId":"s1","message":{"id":"msg_2","role":"assistant","content":"done","usage":{"input_tokens":20,"output_tokens":7,"cache_creation_input_tokens":3}}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let summary = parse_transcript(&path).unwrap().summary;
+        assert_eq!(summary.input_tokens, 30);
+        assert_eq!(summary.output_tokens, 12);
+        assert_eq!(summary.cache_creation_tokens, 3);
+        assert_eq!(summary.cache_read_tokens, 100);
+        assert_eq!(summary.total_tokens(), 145);
+        assert!(summary.has_usage);
+    }
+
+    #[test]
+    fn test_parse_transcript_without_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        std::fs::write(
+            &path,
+            r#"{"type":"assistant","sessionId":"s1","message":{"role":"assistant","content":"hi"}}"#,
+        )
+        .unwrap();
+
+        let summary = parse_transcript(&path).unwrap().summary;
+        assert!(!summary.has_usage);
+        assert_eq!(summary.total_tokens(), 0);
     }
 }
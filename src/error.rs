@@ -12,15 +12,27 @@ pub enum RafctlError {
     #[error("Invalid profile name '{0}': must match [a-zA-Z0-9_-]+")]
     InvalidProfileName(String),
 
+    #[error("Invalid tag '{0}': must match [a-zA-Z0-9_-]+")]
+    InvalidTag(String),
+
     #[error("Reserved profile name '{0}': cannot use system names (default, config, cache)")]
     ReservedProfileName(String),
 
     #[error("Home directory not found")]
     NoHomeDir,
 
+    #[error("Missing required option: {0}")]
+    MissingArgument(String),
+
     #[error("No default profile configured. Set one with: rafctl config set-default <profile>")]
     NoDefaultProfile,
 
+    #[error("No profile has been run yet, so there is no last profile")]
+    NoLastUsedProfile,
+
+    #[error("No previous default profile to switch back to")]
+    NoPreviousDefaultProfile,
+
     #[error("Failed to read config '{path}'")]
     ConfigRead {
         path: PathBuf,
@@ -50,6 +62,107 @@ pub enum RafctlError {
     #[error("API key not configured for profile '{0}'")]
     NoApiKey(String),
 
-    #[error("OAuth mode conflict: another OAuth instance is already running")]
-    OAuthConflict,
+    #[error("API key was rejected by the Anthropic API and was not stored")]
+    ApiKeyRejected,
+
+    #[error("Profile '{0}' does not use OAuth")]
+    NotOAuthProfile(String),
+
+    #[error(
+        "OAuth mode conflict: another OAuth instance is already running{}",
+        holder.as_ref().map(|h| format!(" (profile '{}')", h)).unwrap_or_default()
+    )]
+    OAuthConflict { holder: Option<String> },
+
+    #[error(
+        "{hook} hook failed{}",
+        code.map(|c| format!(" with exit code {}", c)).unwrap_or_default()
+    )]
+    HookFailed {
+        hook: &'static str,
+        code: Option<i32>,
+    },
+
+    #[error("Cannot merge profile '{from}' into '{into}': {reason}")]
+    CannotMerge {
+        from: String,
+        into: String,
+        reason: String,
+    },
+
+    #[error("Invalid env file '{path}' line {line}: {message}")]
+    InvalidEnvFile {
+        path: std::path::PathBuf,
+        line: usize,
+        message: String,
+    },
+
+    #[error("Invalid date '{0}': expected YYYY-MM-DD")]
+    InvalidDate(String),
+
+    #[error("Session '{0}' not found")]
+    SessionNotFound(String),
+
+    #[error("Session '{id}' is ambiguous: matches {} sessions", candidates.len())]
+    AmbiguousSession { id: String, candidates: Vec<String> },
+
+    #[error("Profile '{profile}' has used {utilization:.0}% of its 5-hour quota")]
+    QuotaExhausted { profile: String, utilization: f64 },
+
+    #[error("{option} is only supported for {supported} profiles, '{profile}' uses {tool}")]
+    UnsupportedForTool {
+        option: &'static str,
+        supported: &'static str,
+        profile: String,
+        tool: String,
+    },
+}
+
+impl RafctlError {
+    /// Suggested next step to show under the error message, if there is one.
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            RafctlError::ProfileNotFound(_) => {
+                Some("Run 'rafctl profile list' to see available profiles".to_string())
+            }
+            RafctlError::NoLastUsedProfile => {
+                Some("Run a profile once with 'rafctl run <profile>'".to_string())
+            }
+            RafctlError::NoPreviousDefaultProfile => Some(
+                "The previous default is recorded once you switch between two profiles".to_string(),
+            ),
+            RafctlError::NotAuthenticated(name) => {
+                Some(format!("Run 'rafctl auth login {}' to authenticate", name))
+            }
+            RafctlError::NoApiKey(name) => Some(format!(
+                "Run 'rafctl auth set-key {}' to configure API key",
+                name
+            )),
+            RafctlError::ApiKeyRejected => Some(
+                "Check the key, or pass --no-verify to store it without checking".to_string(),
+            ),
+            RafctlError::NotOAuthProfile(name) => Some(format!(
+                "Only Claude OAuth profiles have a stored token; API keys are managed with 'rafctl auth set-key {}'",
+                name
+            )),
+            RafctlError::ToolNotFound { tool, install_url } => {
+                Some(format!("Install {}: {}", tool, install_url))
+            }
+            RafctlError::OAuthConflict { .. } => Some(
+                "Another OAuth profile is already running (more instances of that same profile are allowed).\n\
+                 Close the other instance first, or use API key mode for parallel execution."
+                    .to_string(),
+            ),
+            RafctlError::QuotaExhausted { .. } => {
+                Some("Set a fallback profile and use '--on-quota-exhausted switch-to'.".to_string())
+            }
+            RafctlError::SessionNotFound(_) => {
+                Some("Run 'rafctl sessions' to see recent session ids".to_string())
+            }
+            RafctlError::AmbiguousSession { .. } => {
+                Some("Pass a longer session id to pick one.".to_string())
+            }
+            _ => None,
+        }
+    }
 }
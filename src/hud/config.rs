@@ -0,0 +1,79 @@
+//! Optional HUD settings read from `~/.rafctl/hud.toml`.
+//!
+//! ```toml
+//! # How the dir segment names the project: "dir" (default) or "repo"
+//! project_name = "repo"
+//! # Trailing path components shown in "dir" mode, e.g. 2 -> "api/src"
+//! path_depth = 2
+//! ```
+//!
+//! The HUD runs on every statusline refresh, so a missing or invalid file
+//! silently means the defaults.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::core::constants::HUD_CONFIG_FILE;
+use crate::core::profile::get_config_dir;
+
+/// What the dir segment shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectNameMode {
+    /// Trailing component(s) of the working directory
+    #[default]
+    Dir,
+    /// Name of the git repository root, falling back to `Dir` outside a repo
+    Repo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct HudConfig {
+    pub project_name: ProjectNameMode,
+    pub path_depth: usize,
+}
+
+impl Default for HudConfig {
+    fn default() -> Self {
+        Self {
+            project_name: ProjectNameMode::Dir,
+            path_depth: 1,
+        }
+    }
+}
+
+pub fn load_hud_config() -> HudConfig {
+    get_config_dir()
+        .ok()
+        .and_then(|dir| load_hud_config_from(&dir.join(HUD_CONFIG_FILE)))
+        .unwrap_or_default()
+}
+
+fn load_hud_config_from(path: &Path) -> Option<HudConfig> {
+    let content = fs::read_to_string(path).ok()?;
+    toml::from_str(&content).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_hud_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HUD_CONFIG_FILE);
+
+        assert_eq!(load_hud_config_from(&path), None);
+
+        fs::write(&path, "project_name = \"repo\"\n").unwrap();
+        let config = load_hud_config_from(&path).unwrap();
+        assert_eq!(config.project_name, ProjectNameMode::Repo);
+        assert_eq!(config.path_depth, 1);
+
+        fs::write(&path, "project_name = \"bogus\"\n").unwrap();
+        assert_eq!(load_hud_config_from(&path), None);
+    }
+}
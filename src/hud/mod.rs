@@ -3,21 +3,133 @@
 //! This module provides a native Rust implementation of the Claude Code statusline protocol,
 //! designed to be a drop-in replacement for Node.js-based HUD plugins.
 
+mod config;
 mod renderer;
 mod stdin;
 
-pub use renderer::render_statusline;
+pub use config::{load_hud_config, HudConfig, ProjectNameMode};
+pub use renderer::{parse_segments, render_statusline, Segment, StatuslineData};
 pub use stdin::{parse_stdin, StdinPayload};
 
 use std::io::{self, Read};
 use std::path::Path;
 use std::process::Command;
 
-use crate::core::transcript::parse_transcript;
+use crate::core::constants::{
+    ENV_RAFCTL_HUD_BUFFER, ENV_RAFCTL_HUD_COST_RED, ENV_RAFCTL_HUD_COST_YELLOW, ENV_RAFCTL_HUD_RED,
+    ENV_RAFCTL_HUD_SEGMENTS, ENV_RAFCTL_HUD_YELLOW, ENV_RAFCTL_PROFILE,
+};
+use crate::core::pricing::{load_pricing, PricingTable};
+use crate::core::transcript::{parse_transcript, SessionSummary};
 
 const AUTOCOMPACT_BUFFER: u64 = 45_000;
+/// Context window assumed when the payload does not state one
+const DEFAULT_CONTEXT_WINDOW: u64 = 200_000;
 const THRESHOLD_YELLOW: u8 = 70;
 const THRESHOLD_RED: u8 = 85;
+const COST_THRESHOLD_YELLOW: f64 = 5.0;
+const COST_THRESHOLD_RED: f64 = 20.0;
+
+/// When the context bar changes color, and how many tokens count as
+/// reserved for auto-compaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextThresholds {
+    pub yellow: u8,
+    pub red: u8,
+    pub buffer: u64,
+}
+
+impl Default for ContextThresholds {
+    fn default() -> Self {
+        Self {
+            yellow: THRESHOLD_YELLOW,
+            red: THRESHOLD_RED,
+            buffer: AUTOCOMPACT_BUFFER,
+        }
+    }
+}
+
+impl ContextThresholds {
+    /// Read `RAFCTL_HUD_YELLOW`, `RAFCTL_HUD_RED` and `RAFCTL_HUD_BUFFER`.
+    pub fn from_env() -> Self {
+        let var = |name| std::env::var(name).ok();
+        Self::parse(
+            var(ENV_RAFCTL_HUD_YELLOW).as_deref(),
+            var(ENV_RAFCTL_HUD_RED).as_deref(),
+            var(ENV_RAFCTL_HUD_BUFFER).as_deref(),
+        )
+    }
+
+    /// Unset or invalid values keep their default. The color thresholds must
+    /// be percentages with yellow below red, otherwise both fall back.
+    fn parse(yellow: Option<&str>, red: Option<&str>, buffer: Option<&str>) -> Self {
+        let defaults = Self::default();
+        let percent = |value: Option<&str>, default: u8| {
+            value
+                .and_then(|v| v.trim().parse::<u8>().ok())
+                .filter(|p| *p <= 100)
+                .unwrap_or(default)
+        };
+
+        let (yellow, red) = match (percent(yellow, defaults.yellow), percent(red, defaults.red)) {
+            (y, r) if y < r => (y, r),
+            _ => (defaults.yellow, defaults.red),
+        };
+        let buffer = buffer
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(defaults.buffer);
+
+        Self {
+            yellow,
+            red,
+            buffer,
+        }
+    }
+}
+
+/// Session cost (USD) at which the cost segment turns yellow or red.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostThresholds {
+    pub yellow: f64,
+    pub red: f64,
+}
+
+impl Default for CostThresholds {
+    fn default() -> Self {
+        Self {
+            yellow: COST_THRESHOLD_YELLOW,
+            red: COST_THRESHOLD_RED,
+        }
+    }
+}
+
+impl CostThresholds {
+    /// Read `RAFCTL_HUD_COST_YELLOW` and `RAFCTL_HUD_COST_RED`.
+    pub fn from_env() -> Self {
+        let var = |name| std::env::var(name).ok();
+        Self::parse(
+            var(ENV_RAFCTL_HUD_COST_YELLOW).as_deref(),
+            var(ENV_RAFCTL_HUD_COST_RED).as_deref(),
+        )
+    }
+
+    /// Same rules as [`ContextThresholds`]: non-negative amounts with yellow
+    /// below red, otherwise both fall back to the defaults.
+    fn parse(yellow: Option<&str>, red: Option<&str>) -> Self {
+        let defaults = Self::default();
+        let amount = |value: Option<&str>, default: f64| {
+            value
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|a| a.is_finite() && *a >= 0.0)
+                .unwrap_or(default)
+        };
+
+        match (amount(yellow, defaults.yellow), amount(red, defaults.red)) {
+            (yellow, red) if yellow < red => Self { yellow, red },
+            _ => defaults,
+        }
+    }
+}
 
 pub fn run_hud() -> Result<(), Box<dyn std::error::Error>> {
     let mut input = String::new();
@@ -29,58 +141,251 @@ pub fn run_hud() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let payload = parse_stdin(&input)?;
-    let context_percent = calculate_context_percent(&payload);
-    let git_branch = get_git_branch(payload.cwd.as_deref());
-    let config_count = count_configs(payload.cwd.as_deref());
+    let flags = HudFlags::from_args(std::env::args().skip(1));
+    let mut segments = resolve_segments();
+    flags.apply(&mut segments);
+    let thresholds = ContextThresholds::from_env();
+    let context_percent = calculate_context_percent(&payload, thresholds.buffer);
     let model_name = extract_model_name(&payload);
-    let profile = std::env::var("RAFCTL_PROFILE").ok();
+    let profile = std::env::var(ENV_RAFCTL_PROFILE).ok();
 
-    let session_summary = payload
-        .transcript_path
-        .as_ref()
-        .and_then(|p| parse_transcript(p))
-        .map(|d| d.summary);
+    // Skip git lookups and filesystem scans for segments that are not shown
+    let git_branch = if segments.contains(&Segment::Git) {
+        get_git_branch(payload.cwd.as_deref())
+    } else {
+        None
+    };
+    let project = if segments.contains(&Segment::Dir) {
+        payload
+            .cwd
+            .as_deref()
+            .map(|cwd| project_name(cwd, &load_hud_config()))
+    } else {
+        None
+    };
+    let config_count = if segments.contains(&Segment::Configs) {
+        count_configs(payload.cwd.as_deref())
+    } else {
+        0
+    };
+    let session_summary = if segments.contains(&Segment::Tools) || segments.contains(&Segment::Cost)
+    {
+        payload
+            .transcript_path
+            .as_ref()
+            .and_then(|p| parse_transcript(p))
+            .map(|d| d.summary)
+    } else {
+        None
+    };
 
-    let output = render_statusline(
-        profile.as_deref(),
-        payload.cwd.as_deref(),
-        model_name.as_deref(),
+    // Prefer the cost Claude Code reports over an estimate from the transcript
+    let session_cost = if segments.contains(&Segment::Cost) {
+        payload.reported_cost().or_else(|| {
+            let pricing = load_pricing().unwrap_or_else(|_| PricingTable::builtin());
+            session_summary
+                .as_ref()
+                .and_then(|s| estimate_session_cost(s, &pricing))
+        })
+    } else {
+        None
+    };
+
+    let data = StatuslineData {
+        profile: profile.as_deref(),
+        project: project.as_deref(),
+        model: model_name.as_deref(),
         context_percent,
-        git_branch.as_deref(),
+        git_branch: git_branch.as_deref(),
         config_count,
-        session_summary.as_ref(),
-    );
+        session: session_summary.as_ref(),
+        session_cost,
+    };
+    let output = render_statusline(&segments, &data, &thresholds, &CostThresholds::from_env());
 
     println!("{}", output);
     Ok(())
 }
 
-fn calculate_context_percent(payload: &StdinPayload) -> u8 {
-    let context = match &payload.context_window {
-        Some(c) => c,
-        None => return 0,
-    };
+/// Command-line switches of `rafctl-hud`, meant for quick toggling in the
+/// `statusLine.command` string. Unknown arguments are ignored so a typo never
+/// breaks the statusline.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct HudFlags {
+    no_git: bool,
+    no_config_count: bool,
+}
+
+impl HudFlags {
+    fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut flags = Self::default();
+        for arg in args {
+            match arg.as_str() {
+                "--no-git" => flags.no_git = true,
+                "--no-config-count" => flags.no_config_count = true,
+                _ => {}
+            }
+        }
+        flags
+    }
+
+    /// Drop the segments the flags turn off, so their data is never computed.
+    fn apply(&self, segments: &mut Vec<Segment>) {
+        segments.retain(|segment| match segment {
+            Segment::Git => !self.no_git,
+            Segment::Configs => !self.no_config_count,
+            _ => true,
+        });
+    }
+}
+
+/// Segment list for this invocation: `RAFCTL_HUD_SEGMENTS` if set and valid,
+/// otherwise the default segments.
+fn resolve_segments() -> Vec<Segment> {
+    std::env::var(ENV_RAFCTL_HUD_SEGMENTS)
+        .ok()
+        .and_then(|spec| parse_segments(&spec))
+        .unwrap_or_else(|| Segment::DEFAULT.to_vec())
+}
+
+/// Input/output token cost of the session so far; `None` without usage data.
+fn estimate_session_cost(summary: &SessionSummary, pricing: &PricingTable) -> Option<f64> {
+    summary.has_usage.then(|| {
+        pricing
+            .lookup(summary.model.as_deref().unwrap_or_default())
+            .estimate_cost(summary.input_tokens, summary.output_tokens)
+    })
+}
 
-    let size = context.context_window_size;
-    if size <= AUTOCOMPACT_BUFFER {
+fn calculate_context_percent(payload: &StdinPayload, buffer: u64) -> u8 {
+    let size = payload
+        .context_window
+        .as_ref()
+        .and_then(|c| c.context_window_size)
+        .unwrap_or(DEFAULT_CONTEXT_WINDOW);
+    if size <= buffer {
         return 0;
     }
 
-    let usage = match &context.current_usage {
+    let usage = match payload.token_usage() {
         Some(u) => u,
+        // Without a usage breakdown the flag is the only signal of a full window
+        None if payload.exceeds_200k_tokens == Some(true) && size <= DEFAULT_CONTEXT_WINDOW => {
+            return 100
+        }
         None => return 0,
     };
 
     let total_tokens =
         usage.input_tokens + usage.cache_creation_input_tokens + usage.cache_read_input_tokens;
 
-    let percent = ((total_tokens + AUTOCOMPACT_BUFFER) as f64 / size as f64) * 100.0;
+    let percent = ((total_tokens + buffer) as f64 / size as f64) * 100.0;
     percent.round().min(100.0) as u8
 }
 
+/// Name shown by the dir segment, following `hud.toml`.
+fn project_name(cwd: &Path, config: &HudConfig) -> String {
+    let repo_root = match config.project_name {
+        ProjectNameMode::Repo => get_git_toplevel(cwd),
+        ProjectNameMode::Dir => None,
+    };
+    let name = match &repo_root {
+        Some(root) => trailing_components(root, 1),
+        None => trailing_components(cwd, config.path_depth),
+    };
+    name.unwrap_or_else(|| "project".to_string())
+}
+
+/// The last `depth` (at least one) normal components of `path`, joined with `/`.
+fn trailing_components(path: &Path, depth: usize) -> Option<String> {
+    let names: Vec<&str> = path
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect();
+    let start = names.len().saturating_sub(depth.max(1));
+    let tail = &names[start..];
+    (!tail.is_empty()).then(|| tail.join("/"))
+}
+
+/// Root of the repository containing `cwd`: the directory holding `.git`,
+/// falling back to `git rev-parse --show-toplevel` only if none is found.
+fn get_git_toplevel(cwd: &Path) -> Option<std::path::PathBuf> {
+    if let Some(root) = find_dot_git(cwd).and_then(|p| p.parent().map(Path::to_path_buf)) {
+        return Some(root);
+    }
+
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(cwd)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!root.is_empty()).then(|| root.into())
+}
+
+/// What `.git/HEAD` points at.
+#[derive(Debug, PartialEq, Eq)]
+enum GitHead {
+    Branch(String),
+    Detached,
+}
+
+fn parse_git_head(content: &str) -> Option<GitHead> {
+    let content = content.trim();
+    if let Some(reference) = content.strip_prefix("ref:") {
+        let reference = reference.trim();
+        let branch = reference.strip_prefix("refs/heads/").unwrap_or(reference);
+        return (!branch.is_empty()).then(|| GitHead::Branch(branch.to_string()));
+    }
+    let is_object_id = content.len() >= 40 && content.chars().all(|c| c.is_ascii_hexdigit());
+    is_object_id.then_some(GitHead::Detached)
+}
+
+/// The nearest `.git` directory (or worktree/submodule `.git` file) from
+/// `cwd` upwards.
+fn find_dot_git(cwd: &Path) -> Option<std::path::PathBuf> {
+    cwd.ancestors()
+        .map(|dir| dir.join(".git"))
+        .find(|p| p.exists())
+}
+
+/// Find the repository's HEAD file from `cwd` upwards, following the
+/// `gitdir:` pointer that worktrees and submodules use.
+fn find_git_head(cwd: &Path) -> Option<std::path::PathBuf> {
+    let dot_git = find_dot_git(cwd)?;
+    if dot_git.is_dir() {
+        return Some(dot_git.join("HEAD"));
+    }
+
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let git_dir = Path::new(content.trim().strip_prefix("gitdir:")?.trim());
+    let git_dir = dot_git.parent()?.join(git_dir);
+    Some(git_dir.join("HEAD"))
+}
+
+/// Branch checked out in `cwd`, or `None` outside a repo and on a detached HEAD.
+///
+/// The statusline is re-rendered constantly, so this reads `.git/HEAD` instead
+/// of spawning git, which is only used when the HEAD file can't be read.
 fn get_git_branch(cwd: Option<&Path>) -> Option<String> {
     let dir = cwd?;
 
+    let head = find_git_head(dir)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| parse_git_head(&content));
+    match head {
+        Some(GitHead::Branch(branch)) => return Some(branch),
+        Some(GitHead::Detached) => return None,
+        None => {}
+    }
+
     let output = Command::new("git")
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .current_dir(dir)
@@ -148,10 +453,20 @@ fn extract_model_name(payload: &StdinPayload) -> Option<String> {
     })
 }
 
-pub fn context_color(percent: u8) -> &'static str {
-    if percent >= THRESHOLD_RED {
+pub fn context_color(percent: u8, thresholds: &ContextThresholds) -> &'static str {
+    if percent >= thresholds.red {
+        "red"
+    } else if percent >= thresholds.yellow {
+        "yellow"
+    } else {
+        "green"
+    }
+}
+
+pub fn cost_color(cost: f64, thresholds: &CostThresholds) -> &'static str {
+    if cost >= thresholds.red {
         "red"
-    } else if percent >= THRESHOLD_YELLOW {
+    } else if cost >= thresholds.yellow {
         "yellow"
     } else {
         "green"
@@ -162,25 +477,242 @@ pub fn context_color(percent: u8) -> &'static str {
 mod tests {
     use super::*;
 
+    fn color(percent: u8) -> &'static str {
+        context_color(percent, &ContextThresholds::default())
+    }
+
     #[test]
     fn test_context_color_green() {
-        assert_eq!(context_color(0), "green");
-        assert_eq!(context_color(50), "green");
-        assert_eq!(context_color(69), "green");
+        assert_eq!(color(0), "green");
+        assert_eq!(color(50), "green");
+        assert_eq!(color(69), "green");
     }
 
     #[test]
     fn test_context_color_yellow() {
-        assert_eq!(context_color(70), "yellow");
-        assert_eq!(context_color(80), "yellow");
-        assert_eq!(context_color(84), "yellow");
+        assert_eq!(color(70), "yellow");
+        assert_eq!(color(80), "yellow");
+        assert_eq!(color(84), "yellow");
     }
 
     #[test]
     fn test_context_color_red() {
-        assert_eq!(context_color(85), "red");
-        assert_eq!(context_color(90), "red");
-        assert_eq!(context_color(100), "red");
+        assert_eq!(color(85), "red");
+        assert_eq!(color(90), "red");
+        assert_eq!(color(100), "red");
+    }
+
+    #[test]
+    fn test_context_color_custom_thresholds() {
+        let thresholds = ContextThresholds::parse(Some("50"), Some("60"), None);
+
+        assert_eq!(context_color(49, &thresholds), "green");
+        assert_eq!(context_color(55, &thresholds), "yellow");
+        assert_eq!(context_color(60, &thresholds), "red");
+    }
+
+    #[test]
+    fn test_thresholds_fall_back_on_invalid_input() {
+        let defaults = ContextThresholds::default();
+
+        // yellow must stay below red
+        assert_eq!(ContextThresholds::parse(Some("90"), None, None), defaults);
+        assert_eq!(
+            ContextThresholds::parse(Some("80"), Some("80"), None),
+            defaults
+        );
+        assert_eq!(ContextThresholds::parse(None, Some("150"), None), defaults);
+        assert_eq!(
+            ContextThresholds::parse(Some("abc"), None, Some("-5")),
+            defaults
+        );
+
+        let custom = ContextThresholds::parse(None, Some("95"), Some("20000"));
+        assert_eq!(custom.yellow, 70);
+        assert_eq!(custom.red, 95);
+        assert_eq!(custom.buffer, 20_000);
+    }
+
+    #[test]
+    fn test_cost_color() {
+        let thresholds = CostThresholds::default();
+        assert_eq!(cost_color(0.5, &thresholds), "green");
+        assert_eq!(cost_color(5.0, &thresholds), "yellow");
+        assert_eq!(cost_color(25.0, &thresholds), "red");
+
+        let custom = CostThresholds::parse(Some("1"), Some("2.5"));
+        assert_eq!(cost_color(1.5, &custom), "yellow");
+        assert_eq!(cost_color(2.5, &custom), "red");
+    }
+
+    #[test]
+    fn test_cost_thresholds_fall_back_on_invalid_input() {
+        let defaults = CostThresholds::default();
+        assert_eq!(CostThresholds::parse(Some("30"), None), defaults);
+        assert_eq!(CostThresholds::parse(Some("-1"), Some("2")), defaults);
+        assert_eq!(CostThresholds::parse(Some("cheap"), Some("NaN")), defaults);
+    }
+
+    #[test]
+    fn test_estimate_session_cost_requires_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        std::fs::write(
+            &path,
+            r#"{"type":"assistant","sessionId":"s1","message":{"role":"assistant","model":"claude-sonnet-4-5","content":"hi","usage":{"input_tokens":1000000,"output_tokens":0}}}"#,
+        )
+        .unwrap();
+        let mut summary = parse_transcript(&path).unwrap().summary;
+        let pricing = PricingTable::builtin();
+
+        assert_eq!(estimate_session_cost(&summary, &pricing), Some(3.0));
+        summary.has_usage = false;
+        assert_eq!(estimate_session_cost(&summary, &pricing), None);
+    }
+
+    #[test]
+    fn test_context_percent_payload_variants() {
+        let percent = |json: &str| calculate_context_percent(&parse_stdin(json).unwrap(), 0);
+
+        assert_eq!(percent("{}"), 0);
+        assert_eq!(
+            percent(
+                r#"{"context_window": {"context_window_size": 100000,
+                    "current_usage": {"input_tokens": 50000}}}"#
+            ),
+            50
+        );
+        // Top-level usage against the default 200k window
+        assert_eq!(percent(r#"{"usage": {"input_tokens": 50000}}"#), 25);
+        assert_eq!(percent(r#"{"exceeds_200k_tokens": true}"#), 100);
+    }
+
+    #[test]
+    fn test_trailing_components() {
+        let path = Path::new("/home/user/mono/services/api");
+        assert_eq!(trailing_components(path, 1).as_deref(), Some("api"));
+        assert_eq!(
+            trailing_components(path, 2).as_deref(),
+            Some("services/api")
+        );
+        assert_eq!(trailing_components(path, 0).as_deref(), Some("api"));
+        assert_eq!(
+            trailing_components(path, 10).as_deref(),
+            Some("home/user/mono/services/api")
+        );
+        assert_eq!(trailing_components(Path::new("/"), 1), None);
+    }
+
+    #[test]
+    fn test_project_name_falls_back_outside_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let cwd = dir.path().join("outside").join("src");
+        std::fs::create_dir_all(&cwd).unwrap();
+        let config = HudConfig {
+            project_name: ProjectNameMode::Repo,
+            path_depth: 2,
+        };
+
+        // The temp dir is not a git repo, so "repo" mode uses the path depth
+        assert_eq!(project_name(&cwd, &config), "outside/src");
+        assert_eq!(project_name(&cwd, &HudConfig::default()), "src");
+    }
+
+    #[test]
+    fn test_project_name_uses_repo_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("mono");
+        let cwd = repo.join("services").join("api");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(&cwd).unwrap();
+        let config = HudConfig {
+            project_name: ProjectNameMode::Repo,
+            path_depth: 2,
+        };
+
+        assert_eq!(get_git_toplevel(&cwd), Some(repo));
+        assert_eq!(project_name(&cwd, &config), "mono");
+    }
+
+    #[test]
+    fn test_hud_flags() {
+        let args = ["--no-git", "--bogus", "--no-config-count"].map(String::from);
+        let flags = HudFlags::from_args(args);
+        assert_eq!(
+            flags,
+            HudFlags {
+                no_git: true,
+                no_config_count: true
+            }
+        );
+
+        let mut segments = Segment::DEFAULT.to_vec();
+        flags.apply(&mut segments);
+        assert!(!segments.contains(&Segment::Git));
+        assert!(!segments.contains(&Segment::Configs));
+        assert!(segments.contains(&Segment::Context));
+
+        let mut segments = Segment::DEFAULT.to_vec();
+        HudFlags::from_args(Vec::new()).apply(&mut segments);
+        assert_eq!(segments, Segment::DEFAULT.to_vec());
+    }
+
+    #[test]
+    fn test_parse_git_head() {
+        assert_eq!(
+            parse_git_head("ref: refs/heads/main\n"),
+            Some(GitHead::Branch("main".to_string()))
+        );
+        assert_eq!(
+            parse_git_head("ref: refs/heads/feature/hud-cache"),
+            Some(GitHead::Branch("feature/hud-cache".to_string()))
+        );
+        assert_eq!(
+            parse_git_head("1f0c2b6a9d8e7f5a4b3c2d1e0f9a8b7c6d5e4f3a\n"),
+            Some(GitHead::Detached)
+        );
+        assert_eq!(parse_git_head("ref: "), None);
+        assert_eq!(parse_git_head("garbage"), None);
+        assert_eq!(parse_git_head(""), None);
+    }
+
+    #[test]
+    fn test_get_git_branch_reads_head_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let git_dir = dir.path().join(".git");
+        let nested = dir.path().join("src").join("hud");
+        std::fs::create_dir_all(&git_dir).unwrap();
+        std::fs::create_dir_all(&nested).unwrap();
+
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/develop\n").unwrap();
+        assert_eq!(get_git_branch(Some(&nested)).as_deref(), Some("develop"));
+
+        std::fs::write(
+            git_dir.join("HEAD"),
+            "1f0c2b6a9d8e7f5a4b3c2d1e0f9a8b7c6d5e4f3a\n",
+        )
+        .unwrap();
+        assert_eq!(get_git_branch(Some(&nested)), None);
+    }
+
+    #[test]
+    fn test_get_git_branch_follows_gitdir_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let worktree = dir.path().join("worktree");
+        let git_dir = dir.path().join("main.git").join("worktrees").join("wt");
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::create_dir_all(&git_dir).unwrap();
+
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", git_dir.display()),
+        )
+        .unwrap();
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/wt-branch\n").unwrap();
+        assert_eq!(
+            get_git_branch(Some(&worktree)).as_deref(),
+            Some("wt-branch")
+        );
     }
 
     #[test]
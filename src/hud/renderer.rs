@@ -1,68 +1,158 @@
 //! Statusline renderer for Claude Code HUD.
 
-use std::path::Path;
-
 use colored::Colorize;
 
-use super::context_color;
+use super::{context_color, cost_color, ContextThresholds, CostThresholds};
 use crate::core::transcript::SessionSummary;
 
 const BAR_FILLED: char = '█';
 const BAR_EMPTY: char = '░';
 const BAR_WIDTH: usize = 10;
 
-pub fn render_statusline(
-    profile: Option<&str>,
-    cwd: Option<&Path>,
-    model: Option<&str>,
-    context_percent: u8,
-    git_branch: Option<&str>,
-    config_count: usize,
-    session: Option<&SessionSummary>,
-) -> String {
-    let mut parts: Vec<String> = Vec::new();
+/// A single statusline segment, in the order it should be rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment {
+    Profile,
+    Dir,
+    Model,
+    Context,
+    Git,
+    Configs,
+    Tools,
+    /// Estimated session cost; opt-in via `RAFCTL_HUD_SEGMENTS`
+    Cost,
+}
 
-    if let Some(p) = profile {
-        parts.push(format!("[{}]", p.cyan()));
-    }
+impl Segment {
+    pub const ALL: [Segment; 8] = [
+        Segment::Profile,
+        Segment::Dir,
+        Segment::Model,
+        Segment::Context,
+        Segment::Git,
+        Segment::Configs,
+        Segment::Tools,
+        Segment::Cost,
+    ];
 
-    if let Some(dir) = cwd {
-        let name = dir
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("project");
-        parts.push(format!("📁 {}", name));
-    }
+    /// Segments shown when no layout is configured
+    pub const DEFAULT: [Segment; 7] = [
+        Segment::Profile,
+        Segment::Dir,
+        Segment::Model,
+        Segment::Context,
+        Segment::Git,
+        Segment::Configs,
+        Segment::Tools,
+    ];
 
-    if let Some(m) = model {
-        parts.push(format!("[{}]", m.bold()));
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "profile" => Some(Segment::Profile),
+            "dir" | "cwd" => Some(Segment::Dir),
+            "model" => Some(Segment::Model),
+            "context" => Some(Segment::Context),
+            "git" => Some(Segment::Git),
+            "configs" => Some(Segment::Configs),
+            "tools" => Some(Segment::Tools),
+            "cost" => Some(Segment::Cost),
+            _ => None,
+        }
     }
+}
 
-    let bar = render_progress_bar(context_percent);
-    let color = context_color(context_percent);
-    let colored_bar = match color {
-        "red" => bar.red().to_string(),
-        "yellow" => bar.yellow().to_string(),
-        _ => bar.green().to_string(),
-    };
-    parts.push(format!("{} {}%", colored_bar, context_percent));
-
-    if let Some(branch) = git_branch {
-        parts.push(format!("git:({})", branch.magenta()));
+/// Parse a comma-separated segment list such as `profile,context,git`.
+/// Unknown names are ignored; returns `None` if nothing usable remains.
+pub fn parse_segments(spec: &str) -> Option<Vec<Segment>> {
+    let segments: Vec<Segment> = spec.split(',').filter_map(Segment::from_name).collect();
+    if segments.is_empty() {
+        None
+    } else {
+        Some(segments)
     }
+}
 
-    if config_count > 0 {
-        parts.push(format!("⚙️{}", config_count));
-    }
+/// What the statusline shows; segments skip any value that is missing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StatuslineData<'a> {
+    pub profile: Option<&'a str>,
+    pub project: Option<&'a str>,
+    pub model: Option<&'a str>,
+    pub context_percent: u8,
+    pub git_branch: Option<&'a str>,
+    pub config_count: usize,
+    pub session: Option<&'a SessionSummary>,
+    pub session_cost: Option<f64>,
+}
+
+pub fn render_statusline(
+    segments: &[Segment],
+    data: &StatuslineData,
+    thresholds: &ContextThresholds,
+    cost_thresholds: &CostThresholds,
+) -> String {
+    let mut parts: Vec<String> = Vec::new();
 
-    if let Some(s) = session {
-        if s.tool_calls > 0 {
-            let error_str = if s.tool_errors > 0 {
-                format!(" {}", format!("({}!)", s.tool_errors).red())
-            } else {
-                String::new()
-            };
-            parts.push(format!("🔧{}{}", s.tool_calls, error_str));
+    for segment in segments {
+        match segment {
+            Segment::Profile => {
+                if let Some(p) = data.profile {
+                    parts.push(format!("[{}]", p.cyan()));
+                }
+            }
+            Segment::Dir => {
+                if let Some(name) = data.project {
+                    parts.push(format!("📁 {}", name));
+                }
+            }
+            Segment::Model => {
+                if let Some(m) = data.model {
+                    parts.push(format!("[{}]", m.bold()));
+                }
+            }
+            Segment::Context => {
+                let bar = render_progress_bar(data.context_percent);
+                let color = context_color(data.context_percent, thresholds);
+                let colored_bar = match color {
+                    "red" => bar.red().to_string(),
+                    "yellow" => bar.yellow().to_string(),
+                    _ => bar.green().to_string(),
+                };
+                parts.push(format!("{} {}%", colored_bar, data.context_percent));
+            }
+            Segment::Git => {
+                if let Some(branch) = data.git_branch {
+                    parts.push(format!("git:({})", branch.magenta()));
+                }
+            }
+            Segment::Configs => {
+                if data.config_count > 0 {
+                    parts.push(format!("⚙️{}", data.config_count));
+                }
+            }
+            Segment::Tools => {
+                if let Some(s) = data.session {
+                    if s.tool_calls > 0 {
+                        let error_str = if s.tool_errors > 0 {
+                            format!(" {}", format!("({}!)", s.tool_errors).red())
+                        } else {
+                            String::new()
+                        };
+                        parts.push(format!("🔧{}{}", s.tool_calls, error_str));
+                    }
+                }
+            }
+            Segment::Cost => {
+                if let Some(cost) = data.session_cost {
+                    let text = format!("${:.2}", cost);
+                    let colored_text = match cost_color(cost, cost_thresholds) {
+                        "red" => text.red().to_string(),
+                        "yellow" => text.yellow().to_string(),
+                        _ => text.green().to_string(),
+                    };
+                    parts.push(colored_text);
+                }
+            }
         }
     }
 
@@ -101,24 +191,88 @@ mod tests {
 
     #[test]
     fn test_render_statusline_minimal() {
-        let output = render_statusline(None, None, None, 45, None, 0, None);
+        let data = StatuslineData {
+            context_percent: 45,
+            ..Default::default()
+        };
+        let output = render_statusline(
+            &Segment::ALL,
+            &data,
+            &ContextThresholds::default(),
+            &CostThresholds::default(),
+        );
         assert!(output.contains("45%"));
     }
 
     #[test]
     fn test_render_statusline_with_profile() {
         let output = render_statusline(
-            Some("work"),
-            None,
-            Some("sonnet-4-5"),
-            70,
-            Some("main"),
-            2,
-            None,
+            &Segment::ALL,
+            &StatuslineData {
+                profile: Some("work"),
+                model: Some("sonnet-4-5"),
+                context_percent: 70,
+                git_branch: Some("main"),
+                config_count: 2,
+                ..Default::default()
+            },
+            &ContextThresholds::default(),
+            &CostThresholds::default(),
         );
         assert!(output.contains("work"));
         assert!(output.contains("sonnet-4-5"));
         assert!(output.contains("70%"));
         assert!(output.contains("main"));
     }
+
+    #[test]
+    fn test_render_statusline_cost_segment() {
+        let render = |cost| {
+            render_statusline(
+                &[Segment::Context, Segment::Cost],
+                &StatuslineData {
+                    context_percent: 10,
+                    session_cost: cost,
+                    ..Default::default()
+                },
+                &ContextThresholds::default(),
+                &CostThresholds::default(),
+            )
+        };
+
+        assert!(render(Some(1.234)).contains("$1.23"));
+        assert!(!render(None).contains('$'));
+    }
+
+    #[test]
+    fn test_parse_segments() {
+        assert_eq!(
+            parse_segments("profile, Context,git"),
+            Some(vec![Segment::Profile, Segment::Context, Segment::Git])
+        );
+        assert_eq!(parse_segments("bogus,tools"), Some(vec![Segment::Tools]));
+        assert_eq!(parse_segments("cost"), Some(vec![Segment::Cost]));
+        assert_eq!(parse_segments(""), None);
+        assert_eq!(parse_segments("bogus"), None);
+    }
+
+    #[test]
+    fn test_render_statusline_respects_segment_order() {
+        let output = render_statusline(
+            &[Segment::Git, Segment::Profile],
+            &StatuslineData {
+                profile: Some("work"),
+                model: Some("sonnet-4-5"),
+                context_percent: 70,
+                git_branch: Some("main"),
+                config_count: 2,
+                ..Default::default()
+            },
+            &ContextThresholds::default(),
+            &CostThresholds::default(),
+        );
+        assert!(!output.contains("70%"));
+        assert!(!output.contains("sonnet-4-5"));
+        assert!(output.find("main").unwrap() < output.find("work").unwrap());
+    }
 }
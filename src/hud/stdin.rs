@@ -1,4 +1,9 @@
 //! Stdin JSON parser for Claude Code HUD protocol.
+//!
+//! Claude Code has changed the payload shape over time, so parsing is
+//! tolerant: unknown fields are ignored, token usage may sit under
+//! `context_window.current_usage` or a top-level `usage`, and newer payloads
+//! carry a `cost` block with the real session cost.
 
 use std::path::PathBuf;
 
@@ -10,19 +15,45 @@ pub struct StdinPayload {
     pub cwd: Option<PathBuf>,
     pub model: Option<ModelInfo>,
     pub context_window: Option<ContextWindow>,
+    /// Token usage in payloads that do not nest it under `context_window`
+    pub usage: Option<TokenUsage>,
+    pub cost: Option<CostInfo>,
+    pub exceeds_200k_tokens: Option<bool>,
+}
+
+impl StdinPayload {
+    /// Current token usage from whichever shape the payload uses
+    pub fn token_usage(&self) -> Option<&TokenUsage> {
+        self.context_window
+            .as_ref()
+            .and_then(|c| c.current_usage.as_ref())
+            .or(self.usage.as_ref())
+    }
+
+    /// Session cost reported by Claude Code, if any
+    pub fn reported_cost(&self) -> Option<f64> {
+        self.cost.as_ref().and_then(|c| c.total_cost_usd)
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ModelInfo {
+    #[serde(alias = "id")]
     pub name: String,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ContextWindow {
-    pub context_window_size: u64,
+    #[serde(default)]
+    pub context_window_size: Option<u64>,
     pub current_usage: Option<TokenUsage>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct CostInfo {
+    pub total_cost_usd: Option<f64>,
+}
+
 #[derive(Debug, Deserialize, Default)]
 pub struct TokenUsage {
     #[serde(default)]
@@ -79,7 +110,7 @@ mod tests {
         );
 
         let ctx = payload.context_window.as_ref().unwrap();
-        assert_eq!(ctx.context_window_size, 200000);
+        assert_eq!(ctx.context_window_size, Some(200000));
 
         let usage = ctx.current_usage.as_ref().unwrap();
         assert_eq!(usage.input_tokens, 50000);
@@ -111,4 +142,57 @@ mod tests {
         assert_eq!(usage.cache_creation_input_tokens, 0);
         assert_eq!(usage.cache_read_input_tokens, 0);
     }
+
+    #[test]
+    fn test_parse_top_level_usage_variant() {
+        let json = r#"{
+            "model": {"id": "claude-opus-4-1", "display_name": "Opus"},
+            "usage": {
+                "input_tokens": 1200,
+                "output_tokens": 300,
+                "cache_read_input_tokens": 800
+            },
+            "exceeds_200k_tokens": false
+        }"#;
+
+        let payload = parse_stdin(json).unwrap();
+        assert!(payload.context_window.is_none());
+        assert_eq!(payload.model.as_ref().unwrap().name, "claude-opus-4-1");
+        assert_eq!(payload.exceeds_200k_tokens, Some(false));
+
+        let usage = payload.token_usage().unwrap();
+        assert_eq!(usage.input_tokens, 1200);
+        assert_eq!(usage.cache_read_input_tokens, 800);
+    }
+
+    #[test]
+    fn test_nested_usage_wins_over_top_level() {
+        let json = r#"{
+            "context_window": {"current_usage": {"input_tokens": 10}},
+            "usage": {"input_tokens": 99}
+        }"#;
+
+        let payload = parse_stdin(json).unwrap();
+        assert_eq!(payload.token_usage().unwrap().input_tokens, 10);
+        assert_eq!(payload.context_window.unwrap().context_window_size, None);
+    }
+
+    #[test]
+    fn test_parse_cost_block() {
+        let json = r#"{
+            "cost": {
+                "total_cost_usd": 1.25,
+                "total_duration_ms": 45000,
+                "total_lines_added": 12
+            }
+        }"#;
+
+        let payload = parse_stdin(json).unwrap();
+        assert_eq!(payload.reported_cost(), Some(1.25));
+        assert_eq!(parse_stdin("{}").unwrap().reported_cost(), None);
+        assert_eq!(
+            parse_stdin(r#"{"cost": {}}"#).unwrap().reported_cost(),
+            None
+        );
+    }
 }
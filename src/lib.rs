@@ -9,34 +9,72 @@ pub mod tools;
 use anyhow::Result;
 use clap::Parser;
 
-use crate::cli::analytics::handle_analytics;
+use crate::cli::analytics::{
+    handle_analytics, handle_compare, handle_show_pricing, AnalyticsOptions,
+};
 use crate::cli::auth::{
-    handle_login, handle_logout, handle_set_key, handle_status as handle_auth_status,
+    handle_add_key, handle_export_token, handle_import_token, handle_login, handle_logout,
+    handle_migrate as handle_auth_migrate, handle_restore_system, handle_rotate_key,
+    handle_set_key, handle_status as handle_auth_status,
 };
 use crate::cli::config::{
-    handle_clear_default, handle_hud as handle_config_hud, handle_path as handle_config_path,
-    handle_set_default, handle_show as handle_config_show,
+    handle_backup as handle_config_backup, handle_clear_default,
+    handle_clear_lock as handle_config_clear_lock, handle_edit as handle_config_edit,
+    handle_env as handle_config_env, handle_export as handle_config_export,
+    handle_hud as handle_config_hud, handle_import as handle_config_import,
+    handle_path as handle_config_path, handle_paths as handle_config_paths,
+    handle_prune_cache as handle_config_prune_cache, handle_reset as handle_config_reset,
+    handle_restore as handle_config_restore, handle_set_default, handle_show as handle_config_show,
 };
-use crate::cli::dashboard::{run_dashboard, DashboardAction};
+use crate::cli::dashboard::{pick_profile, run_dashboard, DashboardAction};
 use crate::cli::debug::enable_verbose;
-use crate::cli::env::handle_env;
+use crate::cli::doctor::handle_doctor;
+use crate::cli::env::{handle_env, EnvSyntax};
 use crate::cli::hud::{handle_hud_install, handle_hud_status, handle_hud_uninstall};
-use crate::cli::profile::{handle_add, handle_list, handle_remove, handle_show};
-use crate::cli::quota::handle_quota;
-use crate::cli::run::handle_run;
-use crate::cli::sessions::handle_sessions;
+use crate::cli::output::set_output_file;
+use crate::cli::profile::{
+    handle_add, handle_add_interactive, handle_export, handle_history, handle_import, handle_list,
+    handle_merge, handle_remove, handle_set_hook, handle_show, handle_tag, handle_validate_name,
+};
+use crate::cli::quota::{handle_quota, handle_quota_watch};
+use crate::cli::run::{handle_run, RunOptions};
+use crate::cli::search::handle_search;
+use crate::cli::sessions::{
+    handle_migrate as handle_sessions_migrate, handle_sessions, SessionsOptions,
+};
+use crate::cli::stats::{
+    handle_export as handle_stats_export, handle_import as handle_stats_import,
+};
 use crate::cli::status::handle_status;
+use crate::cli::version::handle_version;
 use crate::cli::watch::handle_watch;
-use crate::cli::{AuthAction, Cli, Commands, ConfigAction, HudAction, ProfileAction};
+use crate::cli::{
+    AuthAction, Cli, Commands, ConfigAction, HudAction, ProfileAction, SessionsAction, StatsAction,
+};
+use crate::core::config::get_previous_default_profile;
+use crate::core::constants::QUOTA_FETCH_FAILED_EXIT_CODE;
+use crate::core::profile::set_config_dir_override;
 
 /// Main entry point for the CLI application.
 pub fn run() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some(cli::COMPLETE_COMMAND) {
+        cli::print_completion_candidates(args.get(2).map(String::as_str));
+        return Ok(());
+    }
+
     let cli = Cli::parse();
     let format = cli.output_format();
 
     if cli.verbose {
         enable_verbose();
     }
+    if let Some(path) = &cli.report_output {
+        set_output_file(path.clone());
+    }
+    if let Some(dir) = &cli.config_dir {
+        set_config_dir_override(dir.clone());
+    }
 
     match cli.command {
         Commands::Profile { action } => match action {
@@ -44,17 +82,82 @@ pub fn run() -> Result<()> {
                 name,
                 tool,
                 auth_mode,
+                fallback,
+                claude_config_dir_mode,
+                no_dir,
+                prompt_key,
             } => {
-                handle_add(&name, &tool, auth_mode.as_deref())?;
+                handle_add(
+                    &name,
+                    tool.as_deref(),
+                    auth_mode.as_deref(),
+                    fallback.as_deref(),
+                    claude_config_dir_mode.as_deref(),
+                    no_dir,
+                    prompt_key,
+                )?;
             }
-            ProfileAction::List => {
-                handle_list(format)?;
+            ProfileAction::List {
+                authenticated_only,
+                tag,
+                sort,
+                reverse,
+                limit,
+            } => {
+                handle_list(
+                    authenticated_only,
+                    tag.as_deref(),
+                    sort,
+                    reverse,
+                    limit,
+                    format,
+                )?;
             }
             ProfileAction::Remove { name, yes, dry_run } => {
                 handle_remove(&name, yes, dry_run)?;
             }
-            ProfileAction::Show { name } => {
-                handle_show(&name, format)?;
+            ProfileAction::Show { name, secrets } => {
+                handle_show(&name, secrets, format)?;
+            }
+            ProfileAction::History { name, limit } => {
+                handle_history(&name, limit, format)?;
+            }
+            ProfileAction::Export {
+                name,
+                output,
+                include_secrets,
+            } => {
+                handle_export(&name, output.as_deref(), include_secrets, format)?;
+            }
+            ProfileAction::SetHook {
+                profile,
+                pre,
+                post,
+                clear,
+            } => {
+                handle_set_hook(&profile, pre.as_deref(), post.as_deref(), clear)?;
+            }
+            ProfileAction::Merge { from, into, yes } => {
+                handle_merge(&from, &into, yes)?;
+            }
+            ProfileAction::Import {
+                file,
+                force,
+                with_hooks,
+            } => {
+                handle_import(&file, force, with_hooks)?;
+            }
+            ProfileAction::Tag {
+                profile,
+                tag,
+                remove,
+            } => {
+                handle_tag(&profile, &tag, remove)?;
+            }
+            ProfileAction::ValidateName { name } => {
+                if !handle_validate_name(&name, format)? {
+                    std::process::exit(1);
+                }
             }
         },
         Commands::Auth { action } => match action {
@@ -65,26 +168,110 @@ pub fn run() -> Result<()> {
                 handle_logout(&profile, dry_run)?;
             }
             AuthAction::Status { profile } => {
-                handle_auth_status(profile.as_deref())?;
+                handle_auth_status(profile.as_deref(), format)?;
+            }
+            AuthAction::SetKey {
+                profile,
+                key,
+                no_verify,
+            } => {
+                handle_set_key(&profile, key.as_deref(), no_verify)?;
+            }
+            AuthAction::AddKey {
+                profile,
+                key,
+                no_verify,
+            } => {
+                handle_add_key(&profile, key.as_deref(), no_verify)?;
+            }
+            AuthAction::RotateKey { profile } => {
+                handle_rotate_key(&profile)?;
+            }
+            AuthAction::Migrate => {
+                handle_auth_migrate()?;
             }
-            AuthAction::SetKey { profile, key } => {
-                handle_set_key(&profile, key.as_deref())?;
+            AuthAction::ImportToken { profile } => {
+                handle_import_token(&profile)?;
+            }
+            AuthAction::RestoreSystem => {
+                handle_restore_system()?;
+            }
+            AuthAction::Export {
+                profile,
+                confirmed,
+                json_token,
+            } => {
+                handle_export_token(&profile, confirmed, json_token, format)?;
             }
         },
-        Commands::Run { profile, args } => {
-            let exit_code = handle_run(profile.as_deref(), &args)?;
+        Commands::Run {
+            profile,
+            on_quota_exhausted,
+            pre_check_quota,
+            timeout_idle,
+            force,
+            post_summary,
+            snapshot_config,
+            env_file,
+            cwd,
+            capture_cost,
+            pick,
+            args,
+        } => {
+            let options = RunOptions {
+                on_quota_exhausted,
+                pre_check_quota,
+                timeout_idle: timeout_idle.map(std::time::Duration::from_secs),
+                force,
+                post_summary,
+                snapshot_config,
+                env_file,
+                cwd,
+                capture_cost,
+                pick,
+            };
+            let exit_code = handle_run(profile.as_deref(), &args, &options)?;
             if exit_code != 0 {
                 std::process::exit(exit_code);
             }
         }
-        Commands::Status { profile } => {
-            handle_status(profile.as_deref(), format)?;
+        Commands::Status {
+            profile,
+            check,
+            tag,
+        } => {
+            let all_authenticated = handle_status(profile.as_deref(), tag.as_deref(), format)?;
+            if check && !all_authenticated {
+                std::process::exit(1);
+            }
         }
-        Commands::Quota { profile } => {
-            handle_quota(profile.as_deref(), format)?;
+        Commands::Quota {
+            watch: true,
+            profile,
+            interval,
+            ..
+        } => {
+            handle_quota_watch(
+                profile.as_deref(),
+                std::time::Duration::from_secs(interval),
+                format,
+            )?;
+        }
+        Commands::Quota {
+            profile,
+            exit_on_error,
+            ..
+        } => {
+            let all_fetched = handle_quota(profile.as_deref(), format)?;
+            if exit_on_error && !all_fetched {
+                std::process::exit(QUOTA_FETCH_FAILED_EXIT_CODE);
+            }
         }
         Commands::Config { action } => match action {
-            ConfigAction::Show => {
+            ConfigAction::Show { paths: true } => {
+                handle_config_paths(format)?;
+            }
+            ConfigAction::Show { paths: false } => {
                 handle_config_show(format)?;
             }
             ConfigAction::SetDefault { profile } => {
@@ -93,9 +280,36 @@ pub fn run() -> Result<()> {
             ConfigAction::ClearDefault => {
                 handle_clear_default()?;
             }
+            ConfigAction::ClearLock { yes } => {
+                handle_config_clear_lock(yes)?;
+            }
             ConfigAction::Path => {
                 handle_config_path()?;
             }
+            ConfigAction::PruneCache => {
+                handle_config_prune_cache(format)?;
+            }
+            ConfigAction::Reset { yes } => {
+                handle_config_reset(yes)?;
+            }
+            ConfigAction::Env => {
+                handle_config_env(format)?;
+            }
+            ConfigAction::Edit => {
+                handle_config_edit()?;
+            }
+            ConfigAction::Export { output } => {
+                handle_config_export(output.as_deref())?;
+            }
+            ConfigAction::Import { file } => {
+                handle_config_import(&file)?;
+            }
+            ConfigAction::Backup { file } => {
+                handle_config_backup(&file)?;
+            }
+            ConfigAction::Restore { file, merge, yes } => {
+                handle_config_restore(&file, merge, yes)?;
+            }
             ConfigAction::Hud {
                 enable,
                 disable,
@@ -107,42 +321,147 @@ pub fn run() -> Result<()> {
         Commands::Completion { shell } => {
             cli::generate_completions(shell);
         }
-        Commands::Dashboard => {
-            let action = run_dashboard()?;
-            match action {
-                DashboardAction::None => {}
-                DashboardAction::Run(profile) => {
-                    let exit_code = handle_run(Some(&profile), &[])?;
-                    if exit_code != 0 {
-                        std::process::exit(exit_code);
+        Commands::Dashboard { readonly } => {
+            let mut message = None;
+            loop {
+                match run_dashboard(readonly, message.take())? {
+                    DashboardAction::None => break,
+                    DashboardAction::Run(profile) => {
+                        let exit_code = handle_run(Some(&profile), &[], &RunOptions::default())?;
+                        if exit_code != 0 {
+                            std::process::exit(exit_code);
+                        }
+                        break;
+                    }
+                    DashboardAction::Login(profile) => {
+                        handle_login(&profile)?;
+                        break;
+                    }
+                    // Delete and create return to a refreshed dashboard
+                    DashboardAction::Delete(profile) => {
+                        handle_remove(&profile, true, false)?;
+                        message = Some(format!("Profile '{}' removed", profile));
+                    }
+                    DashboardAction::Create => {
+                        message = Some(match handle_add_interactive() {
+                            Ok(profile) => format!("Profile '{}' created", profile),
+                            Err(e) => format!("Profile not created: {}", e),
+                        });
                     }
-                }
-                DashboardAction::Login(profile) => {
-                    handle_login(&profile)?;
                 }
             }
         }
-        Commands::Switch { profile } => {
+        Commands::Switch { profile, previous } => {
+            let profile = match profile {
+                Some(name) if name == "-" => get_previous_default_profile()?
+                    .ok_or(error::RafctlError::NoPreviousDefaultProfile)?,
+                None if previous => get_previous_default_profile()?
+                    .ok_or(error::RafctlError::NoPreviousDefaultProfile)?,
+                Some(profile) => profile,
+                None => pick_profile()?.ok_or_else(|| {
+                    error::RafctlError::MissingArgument(
+                        "<PROFILE> (no terminal to choose one interactively)".to_string(),
+                    )
+                })?,
+            };
             handle_set_default(&profile)?;
-            handle_status(Some(&profile), format)?;
+            handle_status(Some(&profile), None, format)?;
+        }
+        Commands::Analytics {
+            show_pricing: true, ..
+        } => {
+            handle_show_pricing(format)?;
+        }
+        Commands::Analytics {
+            compare,
+            days,
+            model,
+            aggregate_models,
+            fail_if_empty,
+            ..
+        } if !compare.is_empty() => {
+            let found_data =
+                handle_compare(&compare, model.as_deref(), aggregate_models, days, format)?;
+            if fail_if_empty && !found_data {
+                std::process::exit(1);
+            }
         }
         Commands::Analytics {
             profile,
             days,
             all,
+            tag,
             cost,
+            zero_fill,
+            group_by,
+            by_session,
+            errors,
+            fail_if_empty,
+            model,
+            aggregate_models,
+            show_pricing: false,
+            ..
         } => {
-            handle_analytics(profile.as_deref(), days, all, cost, format)?;
+            let options = AnalyticsOptions {
+                model,
+                aggregate_models,
+                days,
+                all,
+                tag,
+                cost,
+                zero_fill,
+                group_by,
+                by_session,
+                errors,
+            };
+            let found_data = handle_analytics(profile.as_deref(), &options, format)?;
+            if fail_if_empty && !found_data {
+                std::process::exit(1);
+            }
         }
         Commands::Sessions {
+            action: Some(SessionsAction::Migrate { project, to, yes }),
+            ..
+        } => {
+            handle_sessions_migrate(&project, &to, yes, format)?;
+        }
+        Commands::Sessions {
+            action: None,
             session_id,
             today,
+            since,
+            until,
             limit,
+            replay,
+            no_agents,
+            used_tool,
+            export,
+            out,
+            summary_cmd,
         } => {
-            handle_sessions(session_id.as_deref(), today, limit, format)?;
+            let options = SessionsOptions {
+                today,
+                since,
+                until,
+                limit,
+                replay,
+                no_agents,
+                used_tool,
+                export,
+                out,
+                summary_cmd,
+            };
+            handle_sessions(session_id.as_deref(), &options, format)?;
         }
-        Commands::Watch { profile } => {
-            handle_watch(profile.as_deref())?;
+        Commands::Search { query, tool, limit } => {
+            handle_search(&query, tool.as_deref(), limit, format)?;
+        }
+        Commands::Watch {
+            profile,
+            session,
+            show_text,
+        } => {
+            handle_watch(profile.as_deref(), session.as_deref(), show_text, format)?;
         }
         Commands::Hud { action } => match action {
             HudAction::Install { profile } => {
@@ -155,9 +474,38 @@ pub fn run() -> Result<()> {
                 handle_hud_status(profile.as_deref())?;
             }
         },
-        Commands::Env { profile } => {
-            handle_env(&profile)?;
+        Commands::Env {
+            profile,
+            fish,
+            powershell,
+        } => {
+            let syntax = if fish {
+                EnvSyntax::Fish
+            } else if powershell {
+                EnvSyntax::PowerShell
+            } else {
+                EnvSyntax::Posix
+            };
+            handle_env(profile.as_deref(), syntax)?;
+        }
+        Commands::Doctor => {
+            if !handle_doctor(format)? {
+                std::process::exit(1);
+            }
         }
+        Commands::Version => handle_version(format)?,
+        Commands::Stats { action } => match action {
+            StatsAction::Export { profile, out } => {
+                handle_stats_export(&profile, out.as_deref())?;
+            }
+            StatsAction::Import {
+                file,
+                profile,
+                strategy,
+            } => {
+                handle_stats_import(&file, &profile, strategy, format)?;
+            }
+        },
     }
 
     Ok(())
@@ -13,49 +13,28 @@ fn main() -> ExitCode {
         if let Some(rafctl_err) = e.downcast_ref::<rafctl::error::RafctlError>() {
             eprintln!("{} {}", "✗".red(), rafctl_err);
 
-            // Provide helpful hints for common errors
-            match rafctl_err {
-                rafctl::error::RafctlError::ProfileNotFound(name) => {
-                    eprintln!(
-                        "{}",
-                        "  Run 'rafctl profile list' to see available profiles".dimmed()
-                    );
-                    // Try to suggest similar profile
-                    if let Ok(profiles) = rafctl::core::profile::list_profiles() {
-                        if let Some(suggestion) =
-                            rafctl::core::profile::find_similar_profile(name, &profiles)
-                        {
-                            eprintln!("{}", format!("  Did you mean '{}'?", suggestion).dimmed());
-                        }
-                    }
-                }
-                rafctl::error::RafctlError::NotAuthenticated(name) => {
-                    eprintln!(
-                        "{}",
-                        format!("  Run 'rafctl auth login {}' to authenticate", name).dimmed()
-                    );
+            if let rafctl::error::RafctlError::AmbiguousSession { candidates, .. } = rafctl_err {
+                for candidate in candidates {
+                    eprintln!("{}", format!("  {}", candidate).dimmed());
                 }
-                rafctl::error::RafctlError::NoApiKey(name) => {
-                    eprintln!(
-                        "{}",
-                        format!("  Run 'rafctl auth set-key {}' to configure API key", name)
-                            .dimmed()
-                    );
-                }
-                rafctl::error::RafctlError::ToolNotFound { tool, install_url } => {
-                    eprintln!(
-                        "{}",
-                        format!("  Install {}: {}", tool, install_url).dimmed()
-                    );
+            }
+
+            // Provide helpful hints for common errors
+            if let Some(hint) = rafctl_err.hint() {
+                for line in hint.lines() {
+                    eprintln!("{}", format!("  {}", line).dimmed());
                 }
-                rafctl::error::RafctlError::OAuthConflict => {
-                    eprintln!("{}", "  Another OAuth profile is already running.".dimmed());
-                    eprintln!(
-                        "{}",
-                        "  Close the other instance first, or use API key mode for parallel execution.".dimmed()
-                    );
+            }
+
+            // Try to suggest similar profile
+            if let rafctl::error::RafctlError::ProfileNotFound(name) = rafctl_err {
+                if let Ok(profiles) = rafctl::core::profile::list_profiles() {
+                    if let Some(suggestion) =
+                        rafctl::core::profile::find_similar_profile(name, &profiles)
+                    {
+                        eprintln!("{}", format!("  Did you mean '{}'?", suggestion).dimmed());
+                    }
                 }
-                _ => {}
             }
         } else {
             // Generic error fallback
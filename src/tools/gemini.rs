@@ -0,0 +1,17 @@
+//! Gemini CLI tool constants and configuration.
+
+/// Environment variable for Gemini CLI home directory override.
+pub const ENV_VAR_NAME: &str = "GEMINI_CLI_HOME";
+
+/// Command name to invoke Gemini CLI.
+pub const COMMAND_NAME: &str = "gemini";
+
+/// Installation URL for Gemini CLI.
+pub const INSTALL_URL: &str = "https://github.com/google-gemini/gemini-cli";
+
+/// Credential file name within the config directory.
+/// Gemini nests its state in a `.gemini` folder under its home.
+pub const CREDENTIAL_FILE: &str = ".gemini/oauth_creds.json";
+
+/// Auth command args for Gemini (empty - just run gemini for auto-auth).
+pub const AUTH_ARGS: &[&str] = &[];
@@ -1,5 +1,6 @@
 pub mod claude;
 pub mod codex;
+pub mod gemini;
 #[cfg(target_os = "macos")]
 pub mod keychain;
 
@@ -14,6 +15,7 @@ impl ToolType {
         match self {
             ToolType::Claude => claude::ENV_VAR_NAME,
             ToolType::Codex => codex::ENV_VAR_NAME,
+            ToolType::Gemini => gemini::ENV_VAR_NAME,
         }
     }
 
@@ -21,6 +23,7 @@ impl ToolType {
         match self {
             ToolType::Claude => claude::COMMAND_NAME,
             ToolType::Codex => codex::COMMAND_NAME,
+            ToolType::Gemini => gemini::COMMAND_NAME,
         }
     }
 
@@ -28,6 +31,7 @@ impl ToolType {
         match self {
             ToolType::Claude => claude::INSTALL_URL,
             ToolType::Codex => codex::INSTALL_URL,
+            ToolType::Gemini => gemini::INSTALL_URL,
         }
     }
 
@@ -35,6 +39,7 @@ impl ToolType {
         match self {
             ToolType::Claude => claude::CREDENTIAL_FILE,
             ToolType::Codex => codex::CREDENTIAL_FILE,
+            ToolType::Gemini => gemini::CREDENTIAL_FILE,
         }
     }
 
@@ -51,6 +56,7 @@ impl ToolType {
         match self {
             ToolType::Claude => claude::AUTH_ARGS,
             ToolType::Codex => codex::AUTH_ARGS,
+            ToolType::Gemini => gemini::AUTH_ARGS,
         }
     }
 }
@@ -87,22 +93,33 @@ mod tests {
         assert_eq!(ToolType::Codex.env_var_name(), "CODEX_HOME");
     }
 
+    #[test]
+    fn test_gemini_env_var() {
+        assert_eq!(ToolType::Gemini.env_var_name(), "GEMINI_CLI_HOME");
+    }
+
     #[test]
     fn test_command_names() {
         assert_eq!(ToolType::Claude.command_name(), "claude");
         assert_eq!(ToolType::Codex.command_name(), "codex");
+        assert_eq!(ToolType::Gemini.command_name(), "gemini");
     }
 
     #[test]
     fn test_install_urls() {
         assert!(ToolType::Claude.install_url().contains("claude"));
         assert!(ToolType::Codex.install_url().contains("codex"));
+        assert!(ToolType::Gemini.install_url().contains("gemini"));
     }
 
     #[test]
     fn test_credential_files() {
         assert_eq!(ToolType::Claude.credential_file(), ".claude.json");
         assert_eq!(ToolType::Codex.credential_file(), "auth.json");
+        assert_eq!(
+            ToolType::Gemini.credential_file(),
+            ".gemini/oauth_creds.json"
+        );
     }
 
     #[test]
@@ -111,5 +128,7 @@ mod tests {
         assert!(ToolType::Claude.auth_args().is_empty());
         // Codex uses "codex login"
         assert_eq!(ToolType::Codex.auth_args(), &["login"]);
+        // Gemini prompts for sign-in on first run
+        assert!(ToolType::Gemini.auth_args().is_empty());
     }
 }
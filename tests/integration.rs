@@ -38,6 +38,20 @@ mod cli_tests {
             .stdout(predicate::str::contains("rafctl"));
     }
 
+    #[test]
+    fn test_version_json() {
+        let output = cargo_bin_cmd!("rafctl")
+            .args(["--json", "version"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert!(!json["target"].as_str().unwrap().is_empty());
+        assert!(json.get("git_sha").is_some());
+    }
+
     #[test]
     fn test_profile_help() {
         cargo_bin_cmd!("rafctl")
@@ -59,7 +73,9 @@ mod cli_tests {
             .stdout(predicate::str::contains("login"))
             .stdout(predicate::str::contains("logout"))
             .stdout(predicate::str::contains("status"))
-            .stdout(predicate::str::contains("set-key"));
+            .stdout(predicate::str::contains("set-key"))
+            .stdout(predicate::str::contains("add-key"))
+            .stdout(predicate::str::contains("rotate-key"));
     }
 
     #[test]
@@ -96,6 +112,85 @@ mod cli_tests {
 mod profile_tests {
     use super::*;
 
+    #[test]
+    fn test_profile_show_secrets() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
+            .assert()
+            .success();
+        fs::write(
+            home.join(".rafctl/profiles/cx/auth.json"),
+            "{\"token\":\"s3cret\"}",
+        )
+        .unwrap();
+
+        rafctl_cmd(home)
+            .args(["profile", "show", "cx", "--json"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("secrets").not());
+
+        let output = rafctl_cmd(home)
+            .args(["profile", "show", "cx", "--secrets", "--json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(!stdout.contains("s3cret"));
+        let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        let secrets = json["secrets"].as_array().unwrap();
+        let file = secrets
+            .iter()
+            .find(|s| s["credential"] == "tool-login")
+            .unwrap();
+        assert_eq!(file["store"], "file");
+        assert_eq!(file["present"], true);
+        assert!(secrets
+            .iter()
+            .any(|s| s["credential"] == "oauth-token" && s["store"] == "keyring"));
+    }
+
+    #[test]
+    fn test_profile_show_paths() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
+            .assert()
+            .success();
+
+        let output = rafctl_cmd(home)
+            .args(["profile", "show", "cx", "--json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let profile_dir = home.join(".rafctl/profiles/cx");
+        assert_eq!(json["config_dir"], profile_dir.display().to_string());
+        assert_eq!(
+            json["credential_path"],
+            profile_dir.join("auth.json").display().to_string()
+        );
+
+        rafctl_cmd(home)
+            .args(["profile", "show", "cx", "--plain"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Profile dir:").not());
+        rafctl_cmd(home)
+            .args(["profile", "show", "cx", "--plain", "--verbose"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "Profile dir: {}",
+                profile_dir.display()
+            )));
+    }
+
     #[test]
     fn test_profile_add_and_list() {
         let temp = TempDir::new().unwrap();
@@ -200,6 +295,65 @@ mod profile_tests {
             .stdout(predicate::str::contains("No profiles found"));
     }
 
+    #[test]
+    fn test_profile_list_authenticated_only() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        for name in ["authed", "fresh"] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", "codex"])
+                .assert()
+                .success();
+        }
+        fs::write(home.join(".rafctl/profiles/authed/auth.json"), "{}").unwrap();
+
+        rafctl_cmd(home)
+            .args(["--plain", "profile", "list", "--authenticated-only"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("authed"))
+            .stdout(predicate::str::contains("fresh").not());
+    }
+
+    #[test]
+    fn test_profile_remove_non_interactive_skips_prompt() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "scripted", "--tool", "codex"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["profile", "remove", "scripted"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Are you sure").not())
+            .stdout(predicate::str::contains("Profile 'scripted' removed"));
+    }
+
+    #[test]
+    fn test_profile_remove_corrupted_meta() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "broken", "--tool", "claude"])
+            .assert()
+            .success();
+        let profile_dir = home.join(".rafctl/profiles/broken");
+        fs::write(profile_dir.join("meta.yaml"), "tool: [unclosed").unwrap();
+
+        rafctl_cmd(home)
+            .args(["profile", "remove", "broken", "--yes"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Profile 'broken' removed"));
+        assert!(!profile_dir.exists());
+    }
+
     #[test]
     fn test_profile_name_case_insensitive() {
         let temp = TempDir::new().unwrap();
@@ -236,6 +390,21 @@ mod profile_tests {
             .stderr(predicate::str::contains("already exists"));
     }
 
+    #[test]
+    fn test_profile_add_without_tool_non_interactive() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        // stdin is not a TTY under the test harness, so no wizard is shown
+        rafctl_cmd(home)
+            .args(["profile", "add", "no-tool"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--tool"));
+
+        assert!(!home.join(".rafctl/profiles/no-tool").exists());
+    }
+
     #[test]
     fn test_profile_invalid_name() {
         let temp = TempDir::new().unwrap();
@@ -270,249 +439,3250 @@ mod profile_tests {
             .success()
             .stdout(predicate::str::contains("codex"));
     }
-}
-
-mod status_tests {
-    use super::*;
 
     #[test]
-    fn test_status_empty() {
+    fn test_gemini_profile() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
 
         rafctl_cmd(home)
-            .args(["status"])
+            .args(["profile", "add", "gemini-test", "--tool", "gemini"])
             .assert()
             .success()
-            .stdout(predicate::str::contains("No profiles found"));
+            .stdout(predicate::str::contains("gemini"));
+
+        rafctl_cmd(home)
+            .args(["--json", "profile", "show", "gemini-test"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"tool\": \"gemini\""));
     }
 
     #[test]
-    fn test_status_with_profiles() {
+    fn test_profile_merge() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
 
+        for name in ["old", "main"] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", "claude"])
+                .assert()
+                .success();
+        }
         rafctl_cmd(home)
-            .args(["profile", "add", "status-test", "--tool", "claude"])
+            .args([
+                "profile",
+                "add",
+                "spare",
+                "--tool",
+                "claude",
+                "--fallback",
+                "old",
+            ])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args(["config", "set-default", "old"])
             .assert()
             .success();
 
+        let profiles = home.join(".rafctl/profiles");
+        let old_project = profiles.join("old/projects/-work-app");
+        fs::create_dir_all(&old_project).unwrap();
+        fs::write(old_project.join("s1.jsonl"), "{}\n").unwrap();
+        for (name, sessions) in [("old", 2), ("main", 3)] {
+            let dir = profiles.join(name).join("claude");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("stats-cache.json"),
+                format!(r#"{{"version":1,"totalSessions":{}}}"#, sessions),
+            )
+            .unwrap();
+        }
+        for (name, session, day) in [("old", "s-old", 5), ("main", "s-main", 6)] {
+            let entry = serde_json::json!({
+                "recorded_at": format!("2026-01-0{}T10:00:00Z", day),
+                "session_id": session,
+                "model": "claude-sonnet-4-5",
+                "input_tokens": 1000,
+                "output_tokens": 200,
+                "input_cost": 0.25,
+                "output_cost": 0.5,
+            });
+            fs::write(
+                profiles.join(name).join("costs.jsonl"),
+                format!("{}\n", entry),
+            )
+            .unwrap();
+        }
+
         rafctl_cmd(home)
-            .args(["status"])
+            .args(["profile", "merge", "old", "main"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--yes"));
+        assert!(profiles.join("old").exists());
+
+        rafctl_cmd(home)
+            .args(["profile", "merge", "old", "main", "--yes"])
             .assert()
             .success()
-            .stdout(predicate::str::contains("status-test"));
+            .stdout(predicate::str::contains("Merged 'old' into 'main'"))
+            .stdout(predicate::str::contains("Moved 1 transcript file(s)"))
+            .stdout(predicate::str::contains("Merged 1 cost ledger entries"));
+
+        assert!(!profiles.join("old").exists());
+        assert!(profiles.join("main/projects/-work-app/s1.jsonl").exists());
+        let stats: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(profiles.join("main/claude/stats-cache.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stats["totalSessions"], 5);
+        let ledger = fs::read_to_string(profiles.join("main/costs.jsonl")).unwrap();
+        let sessions: Vec<String> = ledger
+            .lines()
+            .map(|line| {
+                let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+                entry["session_id"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(sessions, vec!["s-old", "s-main"]);
+
+        let config = fs::read_to_string(home.join(".rafctl/config.yaml")).unwrap();
+        assert!(config.contains("default_profile: main"));
+        let spare = fs::read_to_string(profiles.join("spare/meta.yaml")).unwrap();
+        assert!(spare.contains("fallback_profile: main"));
     }
 
     #[test]
-    fn test_status_json_format() {
+    fn test_profile_merge_refuses_conflicting_transcripts() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
 
-        rafctl_cmd(home)
-            .args(["profile", "add", "json-status", "--tool", "claude"])
-            .assert()
-            .success();
+        for name in ["old", "main"] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", "claude"])
+                .assert()
+                .success();
+        }
+        let profiles = home.join(".rafctl/profiles");
+        for (name, content) in [("old", "old\n"), ("main", "main\n")] {
+            let project = profiles.join(name).join("projects/-work-app");
+            fs::create_dir_all(&project).unwrap();
+            fs::write(project.join("s1.jsonl"), content).unwrap();
+        }
 
         rafctl_cmd(home)
-            .args(["--json", "status"])
+            .args(["profile", "merge", "old", "main", "--yes"])
             .assert()
-            .success()
-            .stdout(predicate::str::contains("\"profiles\""))
-            .stdout(predicate::str::contains("\"name\": \"json-status\""));
+            .failure()
+            .stderr(predicate::str::contains(
+                "1 transcript file(s) exist in both profiles",
+            ));
+        assert_eq!(
+            fs::read_to_string(profiles.join("old/projects/-work-app/s1.jsonl")).unwrap(),
+            "old\n"
+        );
+        assert!(profiles.join("old/meta.yaml").exists());
     }
 
     #[test]
-    fn test_status_plain_format() {
+    fn test_profile_merge_rejects_different_tools() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
 
         rafctl_cmd(home)
-            .args(["profile", "add", "plain-status", "--tool", "claude"])
+            .args(["profile", "add", "cl", "--tool", "claude"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
             .assert()
             .success();
 
         rafctl_cmd(home)
-            .args(["--plain", "status"])
+            .args(["profile", "merge", "cx", "cl", "--yes"])
             .assert()
-            .success()
-            .stdout(predicate::str::contains("NAME\tTOOL"));
+            .failure()
+            .stderr(predicate::str::contains("tools differ"));
+        assert!(home.join(".rafctl/profiles/cx").exists());
     }
 
     #[test]
-    fn test_status_single_profile() {
+    fn test_profile_tags_filter_listings() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
 
+        for name in ["acme", "globex", "home"] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", "codex"])
+                .assert()
+                .success();
+        }
+        for name in ["acme", "globex"] {
+            rafctl_cmd(home)
+                .args(["profile", "tag", name, "Clients"])
+                .assert()
+                .success()
+                .stdout(predicate::str::contains("with 'clients'"));
+        }
+
         rafctl_cmd(home)
-            .args(["profile", "add", "single-status", "--tool", "claude"])
+            .args(["--plain", "profile", "list", "--tag", "clients"])
             .assert()
-            .success();
+            .success()
+            .stdout(predicate::str::contains("acme"))
+            .stdout(predicate::str::contains("globex"))
+            .stdout(predicate::str::contains("home").not());
 
         rafctl_cmd(home)
-            .args(["status", "single-status"])
+            .args(["--plain", "status", "--tag", "clients"])
             .assert()
             .success()
-            .stdout(predicate::str::contains("Profile: single-status"));
-    }
-}
+            .stdout(predicate::str::contains("acme"))
+            .stdout(predicate::str::contains("home").not());
 
-mod config_tests {
-    use super::*;
-
-    #[test]
-    fn test_config_show() {
-        let temp = TempDir::new().unwrap();
-        let home = temp.path();
+        rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["--json", "analytics", "--all", "--tag", "clients"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("globex"))
+            .stdout(predicate::str::contains("\"home\"").not());
 
         rafctl_cmd(home)
-            .args(["config", "show"])
+            .args(["profile", "tag", "acme", "clients", "--remove"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args(["--plain", "profile", "list", "--tag", "clients"])
             .assert()
             .success()
-            .stdout(predicate::str::contains("Configuration"))
-            .stdout(predicate::str::contains("Default profile"));
+            .stdout(predicate::str::contains("acme").not());
+
+        rafctl_cmd(home)
+            .args(["profile", "tag", "home", "not a tag"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid tag"));
     }
 
     #[test]
-    fn test_config_show_json() {
+    fn test_profile_list_sort_and_limit() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
 
+        for name in ["alpha", "bravo", "charlie"] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", "codex"])
+                .assert()
+                .success();
+        }
+
+        let output = rafctl_cmd(home)
+            .args(["--json", "profile", "list", "--reverse", "--limit", "2"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let names: Vec<&str> = json["profiles"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["charlie", "bravo"]);
+
         rafctl_cmd(home)
-            .args(["--json", "config", "show"])
+            .args([
+                "--plain", "profile", "list", "--sort", "tokens", "--limit", "1",
+            ])
             .assert()
             .success()
-            .stdout(predicate::str::contains("\"default_profile\""))
-            .stdout(predicate::str::contains("\"config_directory\""));
+            .stdout(predicate::str::contains("alpha"))
+            .stdout(predicate::str::contains("bravo").not());
     }
 
     #[test]
-    fn test_config_set_default() {
+    fn test_profile_validate_name() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
 
-        // Create profile first
-        rafctl_cmd(home)
-            .args(["profile", "add", "default-test", "--tool", "claude"])
-            .assert()
-            .success();
-
-        // Set as default
         rafctl_cmd(home)
-            .args(["config", "set-default", "default-test"])
+            .args(["profile", "validate-name", "client-a_2"])
             .assert()
             .success()
-            .stdout(predicate::str::contains("Default profile set"));
+            .stdout(predicate::str::contains("is a valid profile name"));
 
-        // Verify in config show
         rafctl_cmd(home)
-            .args(["config", "show"])
+            .args(["profile", "validate-name", "bad name"])
             .assert()
-            .success()
-            .stdout(predicate::str::contains("default-test"));
+            .code(1)
+            .stderr(predicate::str::contains("Invalid profile name 'bad name'"));
+
+        let output = rafctl_cmd(home)
+            .args(["--json", "profile", "validate-name", "config"])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["valid"], false);
+        assert!(json["reason"]
+            .as_str()
+            .unwrap()
+            .contains("Reserved profile name"));
     }
 
     #[test]
-    fn test_config_clear_default() {
+    fn test_profile_picker_needs_terminal() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
 
         rafctl_cmd(home)
-            .args(["profile", "add", "clear-test", "--tool", "claude"])
+            .args(["profile", "add", "work", "--tool", "codex"])
             .assert()
             .success();
 
+        // Without a terminal the picker never opens, so scripts get the old errors
         rafctl_cmd(home)
-            .args(["config", "set-default", "clear-test"])
+            .args(["run"])
             .assert()
-            .success();
-
+            .failure()
+            .stderr(predicate::str::contains("No default profile"));
         rafctl_cmd(home)
-            .args(["config", "clear-default"])
+            .args(["run", "--pick"])
             .assert()
-            .success()
-            .stdout(predicate::str::contains("Default profile cleared"));
+            .failure()
+            .stderr(predicate::str::contains("required by --pick"));
+        rafctl_cmd(home)
+            .args(["switch"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("<PROFILE>"));
     }
 
     #[test]
-    fn test_config_set_default_nonexistent() {
+    fn test_switch_previous() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
 
+        for name in ["work", "home"] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", "codex"])
+                .assert()
+                .success();
+        }
+
         rafctl_cmd(home)
-            .args(["config", "set-default", "nonexistent"])
+            .args(["switch", "-"])
             .assert()
             .failure()
-            .stderr(predicate::str::contains("not found"));
-    }
+            .stderr(predicate::str::contains("No previous default profile"));
 
-    #[test]
-    fn test_config_path() {
-        let temp = TempDir::new().unwrap();
-        let home = temp.path();
+        rafctl_cmd(home).args(["switch", "work"]).assert().success();
+        rafctl_cmd(home).args(["switch", "home"]).assert().success();
+        // Switching to the current default keeps the recorded previous one
+        rafctl_cmd(home).args(["switch", "home"]).assert().success();
+
+        let config = home.join(".rafctl/config.yaml");
+        rafctl_cmd(home).args(["switch", "-"]).assert().success();
+        let content = fs::read_to_string(&config).unwrap();
+        assert!(content.contains("default_profile: work"));
+        assert!(content.contains("previous_default_profile: home"));
 
         rafctl_cmd(home)
-            .args(["config", "path"])
+            .args(["switch", "--previous"])
             .assert()
-            .success()
-            .stdout(predicate::str::contains(".rafctl"));
+            .success();
+        let content = fs::read_to_string(&config).unwrap();
+        assert!(content.contains("default_profile: home"));
+        assert!(content.contains("previous_default_profile: work"));
     }
-}
-
-mod isolation_tests {
-    use super::*;
 
     #[test]
-    fn test_two_profiles_have_separate_directories() {
+    fn test_switch_previous_after_remove_and_merge() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
+        let config = home.join(".rafctl/config.yaml");
 
-        // Create two profiles
+        for name in ["a", "b", "c", "d"] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", "codex"])
+                .assert()
+                .success();
+        }
+
+        rafctl_cmd(home).args(["switch", "a"]).assert().success();
+        rafctl_cmd(home).args(["switch", "b"]).assert().success();
         rafctl_cmd(home)
-            .args(["profile", "add", "work", "--tool", "claude"])
+            .args(["profile", "remove", "a", "--yes"])
             .assert()
             .success();
+        assert!(!fs::read_to_string(&config)
+            .unwrap()
+            .contains("previous_default_profile"));
+        rafctl_cmd(home)
+            .args(["switch", "-"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("No previous default profile"));
 
+        // Merging the previous default retargets it to the merge target
+        rafctl_cmd(home).args(["switch", "c"]).assert().success();
         rafctl_cmd(home)
-            .args(["profile", "add", "personal", "--tool", "claude"])
+            .args(["profile", "merge", "b", "d", "--yes"])
             .assert()
             .success();
+        let content = fs::read_to_string(&config).unwrap();
+        assert!(content.contains("previous_default_profile: d"));
 
-        // Verify separate directories exist
-        let rafctl_dir = home.join(".rafctl").join("profiles");
-        assert!(rafctl_dir.join("work").exists());
-        assert!(rafctl_dir.join("personal").exists());
-
-        // Verify meta.yaml files
-        assert!(rafctl_dir.join("work").join("meta.yaml").exists());
-        assert!(rafctl_dir.join("personal").join("meta.yaml").exists());
+        // ...and removing the default clears it
+        rafctl_cmd(home)
+            .args(["profile", "remove", "c", "--yes"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("It was the default profile"));
+        let content = fs::read_to_string(&config).unwrap();
+        assert!(!content.contains("default_profile: c"));
     }
 
     #[test]
-    fn test_profile_config_isolation() {
+    fn test_env_shell_syntax() {
         let temp = TempDir::new().unwrap();
         let home = temp.path();
+        let config_dir = home.join(".rafctl/profiles/work");
 
         rafctl_cmd(home)
-            .args(["profile", "add", "isolated", "--tool", "claude"])
+            .args(["env"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("No default profile"));
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args(["config", "set-default", "work"])
             .assert()
             .success();
 
-        // The profile should have its own claude subdirectory ready
-        let profile_dir = home.join(".rafctl").join("profiles").join("isolated");
-        assert!(profile_dir.exists());
+        rafctl_cmd(home)
+            .args(["env"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "export CLAUDE_CONFIG_DIR=\"{}\"",
+                config_dir.display()
+            )))
+            .stdout(predicate::str::contains("export RAFCTL_PROFILE=\"work\""));
 
-        // Create a marker file in the profile's claude dir
-        let claude_dir = profile_dir.join("claude");
-        fs::create_dir_all(&claude_dir).unwrap();
-        fs::write(claude_dir.join("marker.txt"), "test").unwrap();
+        rafctl_cmd(home)
+            .args(["env", "work", "--fish"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "set -gx RAFCTL_PROFILE_TOOL \"claude\"",
+            ));
 
-        // Verify the marker exists only in this profile
-        assert!(claude_dir.join("marker.txt").exists());
+        rafctl_cmd(home)
+            .args(["env", "work", "--powershell"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("$env:RAFCTL_PROFILE = \"work\""));
     }
 }
 
-mod completion_tests {
+mod no_dir_tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_add_no_dir() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "external", "--tool", "claude", "--no-dir"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Shared config dir"));
+
+        let meta = fs::read_to_string(home.join(".rafctl/profiles/external/meta.yaml")).unwrap();
+        assert!(meta.contains("config_mode: shared"));
+
+        rafctl_cmd(home)
+            .args(["env", "external"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("CLAUDE_CONFIG_DIR").not())
+            .stdout(predicate::str::contains("RAFCTL_PROFILE=\"external\""));
+    }
+
+    #[test]
+    fn test_profile_add_prompt_key_requirements() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args([
+                "profile",
+                "add",
+                "work",
+                "--tool",
+                "claude",
+                "--auth-mode",
+                "oauth",
+                "--prompt-key",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--auth-mode api-key (required by --prompt-key)",
+            ));
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude", "--prompt-key"])
+            .write_stdin("sk-ant-api-secret\n")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "an interactive terminal (required by --prompt-key)",
+            ));
+
+        assert!(!home.join(".rafctl/profiles/work").exists());
+    }
+
+    #[test]
+    fn test_profile_add_config_dir_mode() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args([
+                "profile",
+                "add",
+                "shared",
+                "--tool",
+                "claude",
+                "--claude-config-dir-mode",
+                "shared",
+            ])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["--json", "profile", "show", "shared"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"config_mode\": \"shared\""));
+
+        rafctl_cmd(home)
+            .args([
+                "profile",
+                "add",
+                "bogus",
+                "--tool",
+                "claude",
+                "--claude-config-dir-mode",
+                "sideways",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid config dir mode"));
+    }
+}
+
+mod export_import_tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_export_with_secrets_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let bundle_path = home.join("api.json");
+
+        rafctl_cmd(home)
+            .args([
+                "profile",
+                "add",
+                "api",
+                "--tool",
+                "claude",
+                "--auth-mode",
+                "api-key",
+            ])
+            .assert()
+            .success();
+        let meta_path = home.join(".rafctl/profiles/api/meta.yaml");
+        let meta = fs::read_to_string(&meta_path).unwrap();
+        fs::write(&meta_path, format!("{}api_key: sk-ant-api-test\n", meta)).unwrap();
+
+        // An existing world-readable file must not keep its mode
+        fs::write(&bundle_path, "old").unwrap();
+        fs::set_permissions(&bundle_path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        rafctl_cmd(home)
+            .args(["profile", "export", "api", "--include-secrets", "--out"])
+            .arg(&bundle_path)
+            .assert()
+            .success();
+
+        let content = fs::read_to_string(&bundle_path).unwrap();
+        assert!(content.contains("sk-ant-api-test"));
+        let mode = fs::metadata(&bundle_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_profile_export_import_roundtrip() {
+        let source = TempDir::new().unwrap();
+        let target = TempDir::new().unwrap();
+        let bundle_path = source.path().join("work.json");
+
+        rafctl_cmd(source.path())
+            .args(["profile", "add", "work", "--tool", "codex"])
+            .assert()
+            .success();
+
+        rafctl_cmd(source.path())
+            .args(["profile", "export", "work", "--out"])
+            .arg(&bundle_path)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("exported"));
+
+        let content = fs::read_to_string(&bundle_path).unwrap();
+        assert!(content.contains("\"format_version\": 1"));
+        assert!(!content.contains("secrets"));
+
+        rafctl_cmd(target.path())
+            .args(["profile", "import"])
+            .arg(&bundle_path)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Profile 'work' imported"));
+
+        rafctl_cmd(target.path())
+            .args(["--json", "profile", "show", "work"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"tool\": \"codex\""));
+
+        // A second import must not silently overwrite
+        rafctl_cmd(target.path())
+            .args(["profile", "import"])
+            .arg(&bundle_path)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("already exists"));
+
+        rafctl_cmd(target.path())
+            .args(["profile", "import", "--force"])
+            .arg(&bundle_path)
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn test_profile_import_drops_hooks_by_default() {
+        let source = TempDir::new().unwrap();
+        let target = TempDir::new().unwrap();
+        let bundle_path = source.path().join("work.json");
+
+        rafctl_cmd(source.path())
+            .args(["profile", "add", "work", "--tool", "codex"])
+            .assert()
+            .success();
+        rafctl_cmd(source.path())
+            .args(["profile", "set-hook", "work", "--pre", "touch pwned"])
+            .assert()
+            .success();
+        rafctl_cmd(source.path())
+            .args(["profile", "export", "work", "--out"])
+            .arg(&bundle_path)
+            .assert()
+            .success();
+
+        let meta_path = target.path().join(".rafctl/profiles/work/meta.yaml");
+        rafctl_cmd(target.path())
+            .args(["profile", "import"])
+            .arg(&bundle_path)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Skipped pre_run hook: touch pwned",
+            ));
+        assert!(!fs::read_to_string(&meta_path).unwrap().contains("pre_run"));
+
+        rafctl_cmd(target.path())
+            .args(["profile", "import", "--force", "--with-hooks"])
+            .arg(&bundle_path)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Installed pre_run hook: touch pwned",
+            ));
+        assert!(fs::read_to_string(&meta_path)
+            .unwrap()
+            .contains("pre_run: touch pwned"));
+    }
+}
+
+#[cfg(unix)]
+mod run_tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_run_timeout_idle_stops_silent_tool() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        // Fake `claude` that writes a few transcript events, then goes quiet
+        let bin_dir = home.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let fake_tool = bin_dir.join("claude");
+        fs::write(
+            &fake_tool,
+            r#"#!/bin/sh
+[ "$1" = "--version" ] && exit 0
+dir="$CLAUDE_CONFIG_DIR/projects/-work"
+mkdir -p "$dir"
+for i in 1 2 3 4 5 6; do
+  echo '{"type":"user"}' >> "$dir/s1.jsonl"
+  sleep 0.5
+done
+exec sleep 30
+"#,
+        )
+        .unwrap();
+        fs::set_permissions(&fake_tool, fs::Permissions::from_mode(0o755)).unwrap();
+
+        rafctl_cmd(home)
+            .args([
+                "profile",
+                "add",
+                "idle",
+                "--tool",
+                "claude",
+                "--auth-mode",
+                "api-key",
+            ])
+            .assert()
+            .success();
+        let meta_path = home.join(".rafctl/profiles/idle/meta.yaml");
+        let meta = fs::read_to_string(&meta_path).unwrap();
+        fs::write(&meta_path, format!("{}api_key: sk-ant-api-test\n", meta)).unwrap();
+
+        let path = format!(
+            "{}:{}",
+            bin_dir.display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+
+        let started = std::time::Instant::now();
+        rafctl_cmd(home)
+            .env("PATH", path)
+            .args(["run", "idle", "--timeout-idle", "2"])
+            .assert()
+            .code(124)
+            .stderr(predicate::str::contains("No session activity for 2s"));
+        // Not stopped while the transcript was still growing
+        assert!(started.elapsed() >= std::time::Duration::from_secs(3));
+        assert!(started.elapsed() < std::time::Duration::from_secs(15));
+    }
+
+    #[test]
+    fn test_run_timeout_idle_rejects_other_tools() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["run", "cx", "--timeout-idle", "1"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "--timeout-idle is only supported for claude profiles",
+            ));
+    }
+
+    #[test]
+    fn test_run_verbose_prints_debug() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let bin_dir = home.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let fake_tool = bin_dir.join("codex");
+        fs::write(
+            &fake_tool,
+            "#!/bin/sh
+exit 0
+",
+        )
+        .unwrap();
+        fs::set_permissions(&fake_tool, fs::Permissions::from_mode(0o755)).unwrap();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "codex"])
+            .assert()
+            .success();
+        fs::write(home.join(".rafctl/profiles/work/auth.json"), "{}").unwrap();
+
+        let path = format!("{}:/bin:/usr/bin", bin_dir.display());
+
+        rafctl_cmd(home)
+            .env("PATH", &path)
+            .args(["-v", "run", "work"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("[debug]"))
+            .stderr(predicate::str::contains("profile"));
+
+        rafctl_cmd(home)
+            .env("PATH", &path)
+            .args(["run", "work"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("[debug]").not());
+    }
+
+    #[test]
+    fn test_run_last_profile() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let bin_dir = home.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let fake_tool = bin_dir.join("codex");
+        fs::write(&fake_tool, "#!/bin/sh\necho \"home=$CODEX_HOME\"\n").unwrap();
+        fs::set_permissions(&fake_tool, fs::Permissions::from_mode(0o755)).unwrap();
+        let path = format!("{}:/bin:/usr/bin", bin_dir.display());
+
+        for name in ["work", "other"] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", "codex"])
+                .assert()
+                .success();
+            fs::write(
+                home.join(format!(".rafctl/profiles/{}/auth.json", name)),
+                "{}",
+            )
+            .unwrap();
+        }
+
+        rafctl_cmd(home)
+            .env("PATH", &path)
+            .args(["run", "-"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("No profile has been run yet"));
+
+        rafctl_cmd(home)
+            .env("PATH", &path)
+            .args(["run", "work"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args(["config", "set-default", "other"])
+            .assert()
+            .success();
+
+        // The default is ignored: `last` and `-` mean the previous run
+        for alias in ["last", "-"] {
+            rafctl_cmd(home)
+                .env("PATH", &path)
+                .args(["run", alias])
+                .assert()
+                .success()
+                .stdout(predicate::str::contains("profiles/work"));
+        }
+
+        // A real profile named `last` takes precedence over the pseudo-profile
+        rafctl_cmd(home)
+            .args(["profile", "add", "last", "--tool", "codex"])
+            .assert()
+            .success();
+        fs::write(home.join(".rafctl/profiles/last/auth.json"), "{}").unwrap();
+        rafctl_cmd(home)
+            .env("PATH", &path)
+            .args(["run", "last"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("profiles/last"));
+    }
+}
+
+mod auth_tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_set_key_no_verify_skips_api_check() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args([
+                "profile",
+                "add",
+                "api",
+                "--tool",
+                "claude",
+                "--auth-mode",
+                "api-key",
+            ])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args([
+                "auth",
+                "set-key",
+                "api",
+                "--key",
+                "sk-ant-api03-test",
+                "--no-verify",
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("not verified"))
+            .stderr(predicate::str::contains("Could not verify").not());
+    }
+
+    #[test]
+    fn test_auth_status_json() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        for name in ["cx", "gm"] {
+            let tool = if name == "cx" { "codex" } else { "gemini" };
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", tool])
+                .assert()
+                .success();
+        }
+        fs::write(home.join(".rafctl/profiles/cx/auth.json"), "{}").unwrap();
+
+        let output = rafctl_cmd(home)
+            .args(["--json", "auth", "status"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let profiles = json["profiles"].as_array().unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0]["profile"], "cx");
+        assert_eq!(profiles[0]["tool"], "codex");
+        assert_eq!(profiles[0]["authenticated"], true);
+        assert!(profiles[0]["staleness_days"].is_null());
+        assert_eq!(profiles[1]["authenticated"], false);
+
+        let output = rafctl_cmd(home)
+            .args(["--json", "auth", "status", "cx"])
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["profile"], "cx");
+        assert_eq!(json["authenticated"], true);
+
+        rafctl_cmd(home)
+            .args(["--plain", "auth", "status"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "PROFILE\tTOOL\tAUTH_MODE\tAUTHENTICATED",
+            ))
+            .stdout(predicate::str::contains("gm\tgemini\t-\tno\tnever\t-"));
+    }
+
+    #[test]
+    fn test_auth_restore_system_without_backup() {
+        let temp = TempDir::new().unwrap();
+
+        rafctl_cmd(temp.path())
+            .args(["auth", "restore-system"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No saved system Claude login"));
+    }
+
+    #[test]
+    fn test_auth_logout_clears_plaintext_api_key() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args([
+                "profile",
+                "add",
+                "legacy",
+                "--tool",
+                "claude",
+                "--auth-mode",
+                "api-key",
+            ])
+            .assert()
+            .success();
+
+        let meta_path = home.join(".rafctl/profiles/legacy/meta.yaml");
+        let meta = fs::read_to_string(&meta_path).unwrap();
+        fs::write(&meta_path, format!("{}api_key: sk-ant-api-legacy\n", meta)).unwrap();
+
+        rafctl_cmd(home)
+            .args(["auth", "logout", "legacy"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Cleared API key (meta.yaml)"));
+
+        assert!(!fs::read_to_string(&meta_path).unwrap().contains("api_key"));
+
+        // Idempotent: a second logout has nothing left to clear
+        rafctl_cmd(home)
+            .args(["auth", "logout", "legacy"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("not authenticated"));
+    }
+
+    #[test]
+    fn test_auth_rotate_key_requires_api_key_mode() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["auth", "rotate-key", "work"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("is in OAuth mode"));
+
+        let meta = fs::read_to_string(home.join(".rafctl/profiles/work/meta.yaml")).unwrap();
+        assert!(!meta.contains("active_api_key"));
+    }
+
+    #[test]
+    fn test_auth_migrate_nothing_to_do() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["auth", "migrate"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No plaintext API keys"));
+    }
+
+    #[test]
+    fn test_auth_capture_validates_profile() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args(["auth", "capture", "cx"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Profile 'cx' does not use OAuth"))
+            .stderr(predicate::str::contains("Only Claude OAuth profiles"));
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        if !cfg!(target_os = "macos") {
+            rafctl_cmd(home)
+                .args(["auth", "capture", "work"])
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains("requires macOS"));
+        }
+    }
+
+    #[test]
+    fn test_auth_export_is_guarded() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args([
+                "profile",
+                "add",
+                "api",
+                "--tool",
+                "claude",
+                "--auth-mode",
+                "api-key",
+            ])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["auth", "export", "work"])
+            .assert()
+            .failure()
+            .stdout(predicate::str::is_empty())
+            .stderr(predicate::str::contains(
+                "--yes-i-understand-this-is-secret",
+            ));
+
+        rafctl_cmd(home)
+            .args([
+                "--json",
+                "auth",
+                "export",
+                "work",
+                "--yes-i-understand-this-is-secret",
+            ])
+            .assert()
+            .failure()
+            .stdout(predicate::str::is_empty())
+            .stderr(predicate::str::contains("--json-token"));
+
+        rafctl_cmd(home)
+            .args(["auth", "export", "api", "--yes-i-understand-this-is-secret"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("does not use OAuth"))
+            .stderr(predicate::str::contains("rafctl auth set-key api"));
+    }
+}
+
+mod status_tests {
+    use super::*;
+
+    #[test]
+    fn test_json_output_file() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let report = home.join("status.json");
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["status", "--json", "--output"])
+            .arg(&report)
+            .assert()
+            .stdout(predicate::str::is_empty());
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+        assert_eq!(json["profiles"][0]["name"], "cx");
+        // Reports can hold tokens (auth export --json-token), so keep them private
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&report).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        rafctl_cmd(home)
+            .args(["status", "--output"])
+            .arg(&report)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--json"));
+
+        rafctl_cmd(home)
+            .args(["status", "--json", "--output"])
+            .arg(home.join("missing-dir/status.json"))
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Failed to write"));
+        let leftovers = fs::read_dir(home)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn test_output_flag_with_profile_export() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let report = home.join("report.json");
+        let bundle = home.join("work.json");
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "codex"])
+            .assert()
+            .success();
+
+        // The global --output gets the report, the local --out gets the bundle
+        rafctl_cmd(home)
+            .arg("--json")
+            .arg("--output")
+            .arg(&report)
+            .args(["profile", "export", "work", "--out"])
+            .arg(&bundle)
+            .assert()
+            .success();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+        assert_eq!(json["profile"], "work");
+        assert_eq!(json["file"], bundle.display().to_string());
+        assert!(fs::read_to_string(&bundle)
+            .unwrap()
+            .contains("\"format_version\": 1"));
+    }
+
+    #[test]
+    fn test_corrupted_profile_is_reported() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "good", "--tool", "codex"])
+            .assert()
+            .success();
+        let broken = home.join(".rafctl").join("profiles").join("broken");
+        fs::create_dir_all(&broken).unwrap();
+        fs::write(broken.join("meta.yaml"), "name: broken\ntool: [oops\n").unwrap();
+
+        rafctl_cmd(home)
+            .args(["status", "--json"])
+            .assert()
+            .stdout(predicate::str::contains("\"good\""))
+            .stderr(predicate::str::contains("Skipping profile 'broken'"))
+            .stderr(predicate::str::contains("meta.yaml"));
+
+        rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["analytics", "--all"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Skipping profile 'broken'"));
+
+        rafctl_cmd(home)
+            .args(["doctor", "--json"])
+            .assert()
+            .stdout(predicate::str::contains("Profile 'broken'"));
+    }
+
+    #[test]
+    fn test_porcelain_output() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["--porcelain", "status"])
+            .assert()
+            .success()
+            .stdout("# rafctl-porcelain v1\n");
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args(["profile", "tag", "cx", "ci"])
+            .assert()
+            .success();
+
+        let output = rafctl_cmd(home)
+            .args(["--porcelain", "profile", "list"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let mut lines = stdout.lines();
+        assert_eq!(lines.next(), Some("# rafctl-porcelain v1"));
+        let record: Vec<&str> = lines.next().unwrap().split('\t').collect();
+        assert_eq!(record[0], "profile");
+        assert!(record.contains(&"name=cx"));
+        assert!(record.contains(&"tool=codex"));
+        assert!(record.contains(&"tags=ci"));
+        assert!(!record.iter().any(|f| f.starts_with("last_used=")));
+
+        rafctl_cmd(home)
+            .args(["--porcelain", "status", "cx"])
+            .assert()
+            .success()
+            .stdout(predicate::str::starts_with(
+                "# rafctl-porcelain v1\nstatus\tname=cx",
+            ))
+            .stdout(predicate::str::contains("\tauthenticated=false"));
+
+        rafctl_cmd(home)
+            .args(["--porcelain", "quota", "cx"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("quota\tprofile=cx"))
+            .stdout(predicate::str::contains(
+                "\terror=Quota monitoring only available",
+            ));
+    }
+
+    #[test]
+    fn test_status_empty() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["status"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No profiles found"));
+    }
+
+    #[test]
+    fn test_status_with_profiles() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "status-test", "--tool", "claude"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["status"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("status-test"));
+    }
+
+    #[test]
+    fn test_status_json_format() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "json-status", "--tool", "claude"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["--json", "status"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"profiles\""))
+            .stdout(predicate::str::contains("\"name\": \"json-status\""));
+    }
+
+    #[test]
+    fn test_status_plain_format() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "plain-status", "--tool", "claude"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["--plain", "status"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("NAME\tTOOL"));
+    }
+
+    #[test]
+    fn test_status_single_profile() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "single-status", "--tool", "claude"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["status", "single-status"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Profile: single-status"));
+    }
+
+    #[test]
+    fn test_status_check_exit_code() {
+        let temp = TempDir::new().unwrap();
+
+        rafctl_cmd(temp.path())
+            .args(["profile", "add", "fresh", "--tool", "codex"])
+            .assert()
+            .success();
+
+        rafctl_cmd(temp.path())
+            .args(["status", "--json"])
+            .assert()
+            .success();
+
+        rafctl_cmd(temp.path())
+            .args(["status", "--check", "--json"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("\"authenticated\": false"));
+
+        fs::write(
+            temp.path().join(".rafctl/profiles/fresh/auth.json"),
+            r#"{"token":"x"}"#,
+        )
+        .unwrap();
+
+        rafctl_cmd(temp.path())
+            .args(["status", "fresh", "--check"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn test_quota_exit_on_error() {
+        let temp = TempDir::new().unwrap();
+
+        rafctl_cmd(temp.path())
+            .args(["profile", "add", "quota-oauth", "--tool", "claude"])
+            .assert()
+            .success();
+
+        // No OAuth token is stored, so the fetch fails
+        rafctl_cmd(temp.path())
+            .args(["quota", "--json"])
+            .assert()
+            .success();
+
+        rafctl_cmd(temp.path())
+            .args(["quota", "--exit-on-error", "--json"])
+            .assert()
+            .code(2)
+            .stdout(predicate::str::contains("\"error\""));
+    }
+
+    #[test]
+    fn test_quota_watch_shows_stale_cache_on_error() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        fs::create_dir_all(home.join(".rafctl/cache")).unwrap();
+        fs::write(
+            home.join(".rafctl/cache/quota-work.json"),
+            r#"{"fetched_at":"2026-01-06T10:00:00Z","usage":{"five_hour":{"utilization":42.5,"resets_at":null},"seven_day":null}}"#,
+        )
+        .unwrap();
+
+        // Runs until killed; the first refresh is enough to check
+        let output = rafctl_cmd(home)
+            .args(["--plain", "quota", "work", "--watch", "--interval", "60"])
+            .timeout(std::time::Duration::from_secs(2))
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("work: 5h=42.5% 7d=-%"), "{}", stdout);
+        assert!(stdout.contains("work: stale="), "{}", stdout);
+    }
+}
+
+mod sessions_tests {
+    use super::*;
+
+    #[test]
+    fn test_sessions_replay_requires_session_id() {
+        let temp = TempDir::new().unwrap();
+
+        rafctl_cmd(temp.path())
+            .args(["sessions", "--replay"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("required by --replay"));
+    }
+
+    #[test]
+    fn test_sessions_migrate_moves_project_transcripts() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("app");
+        fs::create_dir_all(&project).unwrap();
+        let encoded: String = fs::canonicalize(&project)
+            .unwrap()
+            .to_string_lossy()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let global = temp.path().join(".claude/projects").join(&encoded);
+        fs::create_dir_all(&global).unwrap();
+        fs::write(global.join("s1.jsonl"), "{}\n").unwrap();
+
+        rafctl_cmd(temp.path())
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+
+        // Without a terminal the move needs an explicit --yes
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["sessions", "migrate", "--to", "work", "--project"])
+            .arg(&project)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--yes"));
+        assert!(global.join("s1.jsonl").exists());
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["sessions", "migrate", "--to", "work", "--yes", "--project"])
+            .arg(&project)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Moved 1 transcript file(s)"));
+
+        assert!(!global.join("s1.jsonl").exists());
+        assert!(temp
+            .path()
+            .join(".rafctl/profiles/work/projects")
+            .join(&encoded)
+            .join("s1.jsonl")
+            .exists());
+    }
+
+    #[test]
+    fn test_sessions_since_rejects_malformed_date() {
+        let temp = TempDir::new().unwrap();
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["sessions", "--since", "last-week"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Invalid date 'last-week': expected YYYY-MM-DD",
+            ));
+    }
+
+    #[test]
+    fn test_sessions_no_agents_hides_agent_counts() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join(".claude/projects/-Users-me-app");
+        fs::create_dir_all(&project).unwrap();
+        let transcript = [
+            r#"{"type":"user","sessionId":"s1","timestamp":"2026-01-06T10:00:00Z","message":{"role":"user","content":"hi"}}"#,
+            r#"{"type":"assistant","sessionId":"s1","timestamp":"2026-01-06T10:00:05Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Task","input":{"description":"explore"}},{"type":"tool_use","id":"t2","name":"Bash","input":{"command":"ls"}}]}}"#,
+        ]
+        .join("\n");
+        fs::write(project.join("s1.jsonl"), transcript + "\n").unwrap();
+
+        let sessions = |extra: &[&str]| -> serde_json::Value {
+            let output = rafctl_cmd(temp.path())
+                .env("HOME", temp.path())
+                .args(["--json", "sessions"])
+                .args(extra)
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            serde_json::from_slice(&output.stdout).unwrap()
+        };
+
+        let with_agents = sessions(&[]);
+        assert_eq!(with_agents["sessions"][0]["tool_calls"], 1);
+        assert_eq!(with_agents["sessions"][0]["agent_calls"], 1);
+
+        let without_agents = sessions(&["--no-agents"]);
+        assert_eq!(without_agents["sessions"][0]["tool_calls"], 1);
+        assert!(without_agents["sessions"][0].get("agent_calls").is_none());
+    }
+
+    #[test]
+    fn test_sessions_used_tool_filter() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join(".claude/projects/-Users-me-app");
+        fs::create_dir_all(&project).unwrap();
+        let transcript = |sid: &str, tools: &[&str]| {
+            let calls: Vec<String> = tools
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    format!(r#"{{"type":"tool_use","id":"t{i}","name":"{name}","input":{{}}}}"#)
+                })
+                .collect();
+            let user = format!(
+                r#"{{"type":"user","sessionId":"{sid}","timestamp":"2026-01-06T10:00:00Z","message":{{"role":"user","content":"hi"}}}}"#
+            );
+            let assistant = format!(
+                r#"{{"type":"assistant","sessionId":"{sid}","timestamp":"2026-01-06T10:00:05Z","message":{{"role":"assistant","content":[{}]}}}}"#,
+                calls.join(",")
+            );
+            format!("{user}\n{assistant}\n")
+        };
+        fs::write(
+            project.join("s1.jsonl"),
+            transcript("s1", &["Bash", "Read", "Bash"]),
+        )
+        .unwrap();
+        fs::write(project.join("s2.jsonl"), transcript("s2", &["Read"])).unwrap();
+
+        let output = rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["--json", "sessions", "--used-tool", "bash"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["total"], 1);
+        assert_eq!(json["sessions"][0]["session_id"], "s1");
+        assert_eq!(json["sessions"][0]["used_tool_calls"], 2);
+
+        let output = rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["--json", "sessions"])
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["total"], 2);
+        assert!(json["sessions"][0].get("used_tool_calls").is_none());
+    }
+
+    #[test]
+    fn test_sessions_export_markdown() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join(".claude/projects/-Users-me-app");
+        fs::create_dir_all(&project).unwrap();
+        let transcript = [
+            r#"{"type":"user","sessionId":"s1","cwd":"/Users/me/app","gitBranch":"main","timestamp":"2026-01-06T10:00:00Z","message":{"role":"user","content":"hi"}}"#,
+            r#"{"type":"assistant","sessionId":"s1","timestamp":"2026-01-06T10:00:05Z","message":{"role":"assistant","model":"claude-sonnet-4-5","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"ls | wc -l"}}]}}"#,
+        ]
+        .join("\n");
+        fs::write(project.join("s1.jsonl"), transcript + "\n").unwrap();
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["sessions", "s1", "--export", "md"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("# Session s1"))
+            .stdout(predicate::str::contains("- **Git Branch:** main"))
+            .stdout(predicate::str::contains("| Bash | 1 | 100% |"))
+            .stdout(predicate::str::contains("`ls \\| wc -l`"));
+
+        let out = temp.path().join("s1.md");
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["sessions", "s1", "--export", "md", "--out"])
+            .arg(&out)
+            .assert()
+            .success()
+            .stdout(predicate::str::is_empty());
+        assert!(fs::read_to_string(&out)
+            .unwrap()
+            .contains("- **Model:** claude-sonnet-4-5"));
+    }
+
+    #[test]
+    fn test_sessions_summary_cmd_pipes_export() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join(".claude/projects/-Users-me-app");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join("s1.jsonl"),
+            r#"{"type":"user","sessionId":"s1","cwd":"/Users/me/app","timestamp":"2026-01-06T10:00:00Z","message":{"role":"user","content":"hi"}}"#,
+        )
+        .unwrap();
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["sessions", "s1", "--summary-cmd", "grep -c '^# Session s1'"])
+            .assert()
+            .success()
+            .stdout("1\n");
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["sessions", "s1", "--summary-cmd", "exit 2"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "summary hook failed with exit code 2",
+            ));
+    }
+
+    #[test]
+    fn test_sessions_detail_reports_tokens_and_cost() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join(".claude/projects/-Users-me-app");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join("s1.jsonl"),
+            r#"{"type":"assistant","sessionId":"s1","message":{"id":"m1","role":"assistant","model":"claude-sonnet-4-5","content":"hi","usage":{"input_tokens":1000000,"output_tokens":100000,"cache_read_input_tokens":5}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            project.join("s2.jsonl"),
+            r#"{"type":"assistant","sessionId":"s2","message":{"role":"assistant","content":"hi"}}"#,
+        )
+        .unwrap();
+
+        let output = rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["--json", "sessions", "s1"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["tokens"]["input"], 1_000_000);
+        assert_eq!(json["tokens"]["cache_read"], 5);
+        assert_eq!(json["cost_estimated"], 4.5);
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["sessions", "s2"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Est. Cost:   —"));
+    }
+
+    #[test]
+    fn test_search_matches_tool_targets() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join(".claude/projects/-Users-me-app");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join("s1.jsonl"),
+            r#"{"type":"assistant","sessionId":"s1","timestamp":"2026-01-06T10:00:00Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo test --workspace"}},{"type":"tool_use","id":"t2","name":"Read","input":{"file_path":"/app/Cargo.toml"}}]}}"#,
+        )
+        .unwrap();
+        fs::write(
+            project.join("s2.jsonl"),
+            r#"{"type":"assistant","sessionId":"s2","timestamp":"2026-01-07T10:00:00Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"npm install"}}]}}"#,
+        )
+        .unwrap();
+
+        let search = |args: &[&str]| -> serde_json::Value {
+            let output = rafctl_cmd(temp.path())
+                .env("HOME", temp.path())
+                .args(["--json", "search"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            serde_json::from_slice(&output.stdout).unwrap()
+        };
+
+        let all = search(&["CARGO"]);
+        assert_eq!(all["total"], 1);
+        assert_eq!(all["results"][0]["session_id"], "s1");
+        assert_eq!(all["results"][0]["matches"], 2);
+        assert_eq!(all["results"][0]["snippet"], "cargo test --workspace");
+
+        let read_only = search(&["cargo", "--tool", "read"]);
+        assert_eq!(read_only["results"][0]["matches"], 1);
+        assert_eq!(read_only["results"][0]["tool"], "Read");
+
+        assert_eq!(search(&["docker"])["total"], 0);
+    }
+
+    #[test]
+    fn test_watch_session_ambiguous_lists_candidates() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join(".claude/projects/-Users-me-app");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("abc123-0001.jsonl"), "").unwrap();
+        fs::write(project.join("abc456-0002.jsonl"), "").unwrap();
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["watch", "--session", "abc"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("ambiguous"))
+            .stderr(predicate::str::contains("abc123-0001"))
+            .stderr(predicate::str::contains("abc456-0002"));
+    }
+
+    #[test]
+    fn test_watch_session_not_found() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join(".claude/projects/-Users-me-app");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("abc123-0001.jsonl"), "").unwrap();
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["watch", "--session", "zzz"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Session 'zzz' not found"))
+            .stderr(predicate::str::contains("rafctl sessions"))
+            .stderr(predicate::str::contains("Profile").not());
+    }
+}
+
+mod config_tests {
+    use super::*;
+
+    #[test]
+    fn test_config_prune_cache() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let cache = home.join(".rafctl/cache");
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "codex"])
+            .assert()
+            .success();
+        fs::create_dir_all(&cache).unwrap();
+        fs::write(cache.join("quota-work.json"), "{}").unwrap();
+        fs::write(cache.join("quota-deleted.json"), "{}").unwrap();
+
+        let output = rafctl_cmd(home)
+            .args(["--json", "config", "prune-cache"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["removed"], 1);
+        assert_eq!(json["files"][0]["reason"], "orphaned");
+        assert!(cache.join("quota-work.json").exists());
+        assert!(!cache.join("quota-deleted.json").exists());
+
+        // `config show` prunes opportunistically
+        fs::write(cache.join("quota-deleted.json"), "{}").unwrap();
+        rafctl_cmd(home)
+            .args(["config", "show"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Pruned 1 stale cache file(s)"));
+        assert!(!cache.join("quota-deleted.json").exists());
+
+        rafctl_cmd(home)
+            .args(["config", "prune-cache"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("already clean"));
+    }
+
+    #[test]
+    fn test_config_dir_flag_isolates_invocations() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let first = home.join("first");
+        let second = home.join("second");
+
+        // The flag wins over RAFCTL_CONFIG_DIR, which rafctl_cmd sets
+        rafctl_cmd(home)
+            .arg("--config-dir")
+            .arg(&first)
+            .args(["profile", "add", "alpha", "--tool", "codex"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .arg("--config-dir")
+            .arg(&second)
+            .args(["profile", "add", "beta", "--tool", "codex"])
+            .assert()
+            .success();
+
+        assert!(first.join("profiles/alpha/meta.yaml").exists());
+        assert!(second.join("profiles/beta/meta.yaml").exists());
+        assert!(!home.join(".rafctl/profiles").exists());
+
+        rafctl_cmd(home)
+            .args(["--plain", "profile", "list", "--config-dir"])
+            .arg(&first)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("alpha"))
+            .stdout(predicate::str::contains("beta").not());
+        rafctl_cmd(home)
+            .args(["config", "path", "--config-dir"])
+            .arg(&second)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(second.display().to_string()));
+    }
+
+    #[test]
+    fn test_config_reset_preserves_profiles() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args(["config", "set-default", "work"])
+            .assert()
+            .success();
+
+        // Corrupt config must not block the reset
+        fs::write(home.join(".rafctl/config.yaml"), "default_profile: [oops").unwrap();
+
+        rafctl_cmd(home)
+            .args(["config", "reset", "--yes"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("profiles preserved"));
+
+        rafctl_cmd(home)
+            .args(["--json", "config", "show"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"default_profile\": null"));
+
+        rafctl_cmd(home)
+            .args(["profile", "list"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("work"));
+    }
+
+    #[test]
+    fn test_config_backup_restore() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let archive = home.join("rafctl-backup.tar");
+
+        for (name, tool) in [("work", "claude"), ("cx", "codex")] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", tool])
+                .assert()
+                .success();
+        }
+        rafctl_cmd(home)
+            .args(["config", "set-default", "work"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["config", "backup", archive.to_str().unwrap()])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Backed up 2 profile(s)"))
+            .stdout(predicate::str::contains("re-authenticate: work"));
+
+        rafctl_cmd(home)
+            .args(["profile", "remove", "cx", "--yes"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args(["profile", "add", "later", "--tool", "gemini"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args([
+                "config",
+                "restore",
+                archive.to_str().unwrap(),
+                "--merge",
+                "--yes",
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Restored 1 profile(s)"))
+            .stdout(predicate::str::contains("Kept existing profile(s): work"));
+        assert!(home.join(".rafctl/profiles/cx/meta.yaml").exists());
+        assert!(home.join(".rafctl/profiles/later/meta.yaml").exists());
+
+        rafctl_cmd(home)
+            .args(["config", "restore", archive.to_str().unwrap()])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--yes"));
+        assert!(home.join(".rafctl/profiles/later").exists());
+
+        rafctl_cmd(home)
+            .args(["config", "restore", archive.to_str().unwrap(), "--yes"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Restored 2 profile(s)"))
+            .stdout(predicate::str::contains("Re-authenticate"));
+        assert!(!home.join(".rafctl/profiles/later").exists());
+        let config = fs::read_to_string(home.join(".rafctl/config.yaml")).unwrap();
+        assert!(config.contains("work"));
+
+        rafctl_cmd(home)
+            .args([
+                "config",
+                "restore",
+                home.join("missing.tar").to_str().unwrap(),
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_config_restore_truncated_archive_keeps_profiles() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let archive = home.join("rafctl-backup.tar");
+
+        for (name, tool) in [("work", "claude"), ("cx", "codex")] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", tool])
+                .assert()
+                .success();
+        }
+        fs::write(home.join(".rafctl/profiles/cx/auth.json"), "{}").unwrap();
+        rafctl_cmd(home)
+            .args(["config", "backup", archive.to_str().unwrap()])
+            .assert()
+            .success();
+        let mode = fs::metadata(&archive).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // Cut the archive inside an entry, after the manifest
+        let content = fs::read(&archive).unwrap();
+        let truncated = archive.with_file_name("truncated.tar");
+        fs::write(&truncated, &content[..content.len() / 1024 * 512 + 100]).unwrap();
+
+        rafctl_cmd(home)
+            .args(["config", "restore", truncated.to_str().unwrap(), "--yes"])
+            .assert()
+            .failure();
+        assert!(home.join(".rafctl/profiles/work/meta.yaml").exists());
+        assert!(home.join(".rafctl/profiles/cx/auth.json").exists());
+        let leftovers: Vec<_> = fs::read_dir(home)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with(".rafctl."))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn test_config_export_import_roundtrip() {
+        let source = TempDir::new().unwrap();
+        let target = TempDir::new().unwrap();
+
+        rafctl_cmd(source.path())
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        rafctl_cmd(source.path())
+            .args(["config", "set-default", "work"])
+            .assert()
+            .success();
+
+        let exported = source.path().join("settings.yaml");
+        rafctl_cmd(source.path())
+            .args(["config", "export", "--out"])
+            .arg(&exported)
+            .assert()
+            .success();
+        let content = fs::read_to_string(&exported).unwrap();
+        assert!(content.contains("default_profile: work"));
+        assert!(!content.contains("last_used_profile"));
+
+        rafctl_cmd(target.path())
+            .args(["config", "import"])
+            .arg(&exported)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("does not exist on this machine"));
+
+        rafctl_cmd(target.path())
+            .args(["--json", "config", "show"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"default_profile\": \"work\""));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_config_edit_validates_result() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let config_path = home.join(".rafctl/config.yaml");
+
+        // The fake editor copies $NEW_CONFIG over the file it is given
+        let editor = home.join("editor.sh");
+        fs::write(&editor, "#!/bin/sh\ncp \"$NEW_CONFIG\" \"$1\"\n").unwrap();
+        fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let good = home.join("good.yaml");
+        fs::write(&good, "default_profile: work\n").unwrap();
+        rafctl_cmd(home)
+            .env("EDITOR", &editor)
+            .env("NEW_CONFIG", &good)
+            .args(["config", "edit"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Global config updated"));
+        assert_eq!(
+            fs::read_to_string(&config_path).unwrap(),
+            "default_profile: work\n"
+        );
+
+        let bad = home.join("bad.yaml");
+        fs::write(&bad, "default_profile: [oops\n").unwrap();
+        rafctl_cmd(home)
+            .env("EDITOR", &editor)
+            .env("NEW_CONFIG", &bad)
+            .args(["config", "edit"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("previous version was kept"))
+            .stderr(predicate::str::contains("default_profile: [oops"));
+        assert_eq!(
+            fs::read_to_string(&config_path).unwrap(),
+            "default_profile: work\n"
+        );
+    }
+
+    #[test]
+    fn test_config_env_json_masks_secrets() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .env("ANTHROPIC_API_KEY", "sk-ant-REDACTED")
+            .env_remove("RAFCTL_DEFAULT_PROFILE")
+            .args(["--json", "config", "env"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"RAFCTL_CONFIG_DIR\""))
+            .stdout(predicate::str::contains("****1234"))
+            .stdout(predicate::str::contains("secretvalue").not());
+    }
+
+    #[test]
+    fn test_config_list_env_alias() {
+        let temp = TempDir::new().unwrap();
+
+        rafctl_cmd(temp.path())
+            .args(["config", "list-env"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("RAFCTL_DEFAULT_PROFILE"));
+    }
+
+    #[test]
+    fn test_config_show() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["config", "show"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Configuration"))
+            .stdout(predicate::str::contains("Default profile"));
+    }
+
+    #[test]
+    fn test_config_show_json() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["--json", "config", "show"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"default_profile\""))
+            .stdout(predicate::str::contains("\"config_directory\""));
+    }
+
+    #[test]
+    fn test_config_set_default() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        // Create profile first
+        rafctl_cmd(home)
+            .args(["profile", "add", "default-test", "--tool", "claude"])
+            .assert()
+            .success();
+
+        // Set as default
+        rafctl_cmd(home)
+            .args(["config", "set-default", "default-test"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Default profile set"));
+
+        // Verify in config show
+        rafctl_cmd(home)
+            .args(["config", "show"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("default-test"));
+    }
+
+    #[test]
+    fn test_config_clear_default() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "clear-test", "--tool", "claude"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["config", "set-default", "clear-test"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["config", "clear-default"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Default profile cleared"));
+    }
+
+    #[test]
+    fn test_config_set_default_nonexistent() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["config", "set-default", "nonexistent"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("not found"));
+    }
+
+    #[test]
+    fn test_config_path() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["config", "path"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(".rafctl"));
+    }
+
+    #[test]
+    fn test_config_show_paths_json() {
+        let temp = TempDir::new().unwrap();
+        let config_dir = temp.path().join(".rafctl");
+
+        let output = rafctl_cmd(temp.path())
+            .args(["config", "show", "--paths", "--json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["config_dir"], config_dir.display().to_string());
+        assert_eq!(
+            json["oauth_lock"],
+            config_dir.join("oauth.lock").display().to_string()
+        );
+        for key in [
+            "profiles_dir",
+            "config_file",
+            "transcripts_dir",
+            "stats_cache",
+            "cache_dir",
+        ] {
+            assert!(json.get(key).is_some(), "missing {}", key);
+        }
+    }
+
+    #[test]
+    fn test_doctor_json_lists_checks() {
+        let temp = TempDir::new().unwrap();
+
+        // Keyring availability varies by machine, so only the shape is asserted
+        let output = rafctl_cmd(temp.path())
+            .args(["doctor", "--json"])
+            .output()
+            .unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let names: Vec<&str> = json["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"Config directory"));
+        assert!(names.contains(&"Keyring"));
+        assert!(names.contains(&"OAuth lock"));
+        assert_eq!(output.status.success(), json["ok"].as_bool().unwrap());
+    }
+
+    #[test]
+    fn test_config_clear_lock_removes_stale_lock() {
+        let temp = TempDir::new().unwrap();
+        let lock_path = temp.path().join(".rafctl/oauth.lock");
+
+        rafctl_cmd(temp.path())
+            .args(["config", "clear-lock", "--yes"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No OAuth lock file found"));
+
+        fs::create_dir_all(lock_path.parent().unwrap()).unwrap();
+        fs::write(&lock_path, "work\n").unwrap();
+
+        rafctl_cmd(temp.path())
+            .args(["config", "clear-lock", "--yes"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Removed stale OAuth lock"));
+
+        assert!(!lock_path.exists());
+    }
+}
+
+mod history_tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_records_history() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        // Stand-in for the codex binary that exits with a non-zero code
+        let bin_dir = home.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let fake_codex = bin_dir.join("codex");
+        fs::write(&fake_codex, "#!/bin/sh\nexit 3\n").unwrap();
+        fs::set_permissions(&fake_codex, fs::Permissions::from_mode(0o755)).unwrap();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex", "--no-dir"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .env("PATH", &bin_dir)
+            .args(["run", "cx", "--", "exec", "hello"])
+            .assert()
+            .code(3);
+
+        let output = rafctl_cmd(home)
+            .args(["--json", "profile", "history", "cx"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let entries = json["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["exit_code"], 3);
+        assert_eq!(entries[0]["args"], serde_json::json!(["exec", "hello"]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_snapshot_config_is_linked_from_history() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let bin_dir = home.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let fake_codex = bin_dir.join("codex");
+        fs::write(&fake_codex, "#!/bin/sh\nexit 0\n").unwrap();
+        fs::set_permissions(&fake_codex, fs::Permissions::from_mode(0o755)).unwrap();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
+            .assert()
+            .success();
+        fs::write(home.join(".rafctl/profiles/cx/auth.json"), "{}").unwrap();
+        let settings = home.join(".rafctl/profiles/cx/settings.json");
+        fs::write(&settings, r#"{"theme":"dark"}"#).unwrap();
+
+        rafctl_cmd(home)
+            .env("PATH", &bin_dir)
+            .args(["run", "cx", "--snapshot-config"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Config snapshot:"));
+        fs::write(&settings, r#"{"theme":"light"}"#).unwrap();
+
+        let output = rafctl_cmd(home)
+            .args(["--json", "profile", "history", "cx"])
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let snapshot = std::path::PathBuf::from(json["entries"][0]["snapshot"].as_str().unwrap());
+
+        assert!(snapshot.starts_with(home.join(".rafctl/snapshots/cx")));
+        assert!(snapshot.join("meta.yaml").exists());
+        assert_eq!(
+            fs::read_to_string(snapshot.join("settings.json")).unwrap(),
+            r#"{"theme":"dark"}"#
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_env_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let bin_dir = home.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let fake_codex = bin_dir.join("codex");
+        fs::write(
+            &fake_codex,
+            "#!/bin/sh\necho \"greeting=$GREETING profile=$RAFCTL_PROFILE\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&fake_codex, fs::Permissions::from_mode(0o755)).unwrap();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
+            .assert()
+            .success();
+        fs::write(home.join(".rafctl/profiles/cx/auth.json"), "{}").unwrap();
+
+        let env_file = home.join("project.env");
+        fs::write(
+            &env_file,
+            "# project\nGREETING=\"hi there\"\nRAFCTL_PROFILE=from-file\n",
+        )
+        .unwrap();
+
+        rafctl_cmd(home)
+            .env("PATH", &bin_dir)
+            .args(["run", "cx", "--env-file"])
+            .arg(&env_file)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "greeting=hi there profile=from-file",
+            ));
+
+        fs::write(&env_file, "GREETING=hi\nnot a variable\n").unwrap();
+        rafctl_cmd(home)
+            .env("PATH", &bin_dir)
+            .args(["run", "cx", "--env-file"])
+            .arg(&env_file)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("line 2: expected KEY=VALUE"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hooks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let bin_dir = home.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let fake_codex = bin_dir.join("codex");
+        // `rafctl run` probes the tool with --version before launching
+        fs::write(
+            &fake_codex,
+            "#!/bin/sh\n[ \"$1\" = --version ] && exit 0\necho ran >> \"$HOOK_LOG\"\nexit 3\n",
+        )
+        .unwrap();
+        fs::set_permissions(&fake_codex, fs::Permissions::from_mode(0o755)).unwrap();
+        let path = format!("{}:/bin:/usr/bin", bin_dir.display());
+        let log = home.join("hooks.log");
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
+            .assert()
+            .success();
+        fs::write(home.join(".rafctl/profiles/cx/auth.json"), "{}").unwrap();
+
+        rafctl_cmd(home)
+            .args([
+                "profile",
+                "set-hook",
+                "cx",
+                "--pre",
+                "echo \"pre $RAFCTL_PROFILE\" >> \"$HOOK_LOG\"",
+                "--post",
+                "echo \"post $RAFCTL_EXIT_CODE\" >> \"$HOOK_LOG\"",
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Hooks updated for 'cx'"));
+
+        rafctl_cmd(home)
+            .env("PATH", &path)
+            .env("HOOK_LOG", &log)
+            .args(["run", "cx"])
+            .assert()
+            .code(3);
+        assert_eq!(fs::read_to_string(&log).unwrap(), "pre cx\nran\npost 3\n");
+
+        // A failing pre-run hook aborts before the tool starts
+        fs::remove_file(&log).unwrap();
+        rafctl_cmd(home)
+            .args(["profile", "set-hook", "cx", "--pre", "exit 4", "--post", ""])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .env("PATH", &path)
+            .env("HOOK_LOG", &log)
+            .args(["run", "cx"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "pre_run hook failed with exit code 4",
+            ));
+        assert!(!log.exists());
+
+        let meta = fs::read_to_string(home.join(".rafctl/profiles/cx/meta.yaml")).unwrap();
+        assert!(!meta.contains("post_run"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_cwd() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let bin_dir = home.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let fake_codex = bin_dir.join("codex");
+        fs::write(
+            &fake_codex,
+            "#!/bin/sh\n[ \"$1\" = --version ] && exit 0\necho \"$PWD $RAFCTL_PROFILE\" > \"$RUN_LOG\"\nenv | grep ^RAFCTL_ | sort > \"$RUN_LOG.env\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&fake_codex, fs::Permissions::from_mode(0o755)).unwrap();
+        let path = format!("{}:/bin:/usr/bin", bin_dir.display());
+        let log = home.join("run.log");
+        let workdir = home.join("project");
+        fs::create_dir_all(&workdir).unwrap();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "cx", "--tool", "codex"])
+            .assert()
+            .success();
+        fs::write(home.join(".rafctl/profiles/cx/auth.json"), "{}").unwrap();
+
+        rafctl_cmd(home)
+            .env("PATH", &path)
+            .env("RUN_LOG", &log)
+            .args(["run", "cx", "--cwd"])
+            .arg(&workdir)
+            .assert()
+            .success();
+        assert_eq!(
+            fs::read_to_string(&log).unwrap(),
+            format!("{} cx\n", workdir.display())
+        );
+        let env = fs::read_to_string(home.join("run.log.env")).unwrap();
+        for expected in [
+            "RAFCTL_PROFILE=cx".to_string(),
+            "RAFCTL_PROFILE_TOOL=codex".to_string(),
+            "RAFCTL_AUTH_MODE=tool-login".to_string(),
+            format!(
+                "RAFCTL_PROFILE_DIR={}",
+                home.join(".rafctl/profiles/cx").display()
+            ),
+            format!("RAFCTL_VERSION={}", env!("CARGO_PKG_VERSION")),
+        ] {
+            assert!(
+                env.lines().any(|l| l == expected),
+                "{} in\n{}",
+                expected,
+                env
+            );
+        }
+
+        fs::remove_file(&log).unwrap();
+        rafctl_cmd(home)
+            .env("PATH", &path)
+            .env("RUN_LOG", &log)
+            .args(["run", "cx", "--cwd"])
+            .arg(home.join("missing"))
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("missing"));
+        assert!(!log.exists());
+    }
+}
+
+mod analytics_tests {
+    use super::*;
+
+    #[test]
+    fn test_analytics_fail_if_empty() {
+        let temp = TempDir::new().unwrap();
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["analytics", "--cost"])
+            .assert()
+            .success();
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["analytics", "--cost", "--fail-if-empty"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("No usage data found"));
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["--json", "analytics", "--fail-if-empty"])
+            .assert()
+            .code(1);
+    }
+
+    #[test]
+    fn test_analytics_by_session_fail_if_empty() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join(".claude/projects/-Users-me-app");
+        fs::create_dir_all(&project).unwrap();
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["analytics", "--by-session", "--fail-if-empty"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("No sessions found"));
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let transcript = [
+            format!(
+                r#"{{"type":"user","sessionId":"s1","timestamp":"{}","message":{{"role":"user","content":"hi"}}}}"#,
+                now
+            ),
+            format!(
+                r#"{{"type":"assistant","sessionId":"s1","timestamp":"{}","message":{{"role":"assistant","model":"claude-sonnet-4-5","content":[],"usage":{{"input_tokens":100,"output_tokens":20}}}}}}"#,
+                now
+            ),
+        ]
+        .join("\n");
+        fs::write(project.join("s1.jsonl"), transcript + "\n").unwrap();
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["analytics", "--by-session", "--fail-if-empty"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn test_analytics_all_fail_if_empty() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["analytics", "--all", "--fail-if-empty"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("No profiles found"));
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["--json", "analytics", "--all", "--fail-if-empty"])
+            .assert()
+            .code(1);
+
+        let dir = home.join(".rafctl/profiles/work/claude");
+        fs::create_dir_all(&dir).unwrap();
+        let stats = serde_json::json!({
+            "version": 1,
+            "dailyActivity": [{
+                "date": chrono::Local::now().format("%Y-%m-%d").to_string(),
+                "messageCount": 4,
+                "sessionCount": 1,
+            }],
+        });
+        fs::write(dir.join("stats-cache.json"), stats.to_string()).unwrap();
+
+        rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["--json", "analytics", "--all", "--fail-if-empty"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn test_analytics_cost_prefers_ledger() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        let entry = serde_json::json!({
+            "recorded_at": chrono::Utc::now().to_rfc3339(),
+            "session_id": "abc",
+            "model": "claude-sonnet-4-5",
+            "input_tokens": 1000,
+            "output_tokens": 200,
+            "input_cost": 0.25,
+            "output_cost": 0.5,
+        });
+        fs::write(
+            home.join(".rafctl/profiles/work/costs.jsonl"),
+            format!("{}\n", entry),
+        )
+        .unwrap();
+
+        let output = rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["--json", "analytics", "work", "--cost"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["source"], "ledger");
+        assert_eq!(json["models"][0]["name"], "claude-sonnet-4-5");
+        assert_eq!(json["total_estimated"], 0.75);
+    }
+
+    #[test]
+    fn test_analytics_cost_mixes_ledger_and_estimate() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let today = chrono::Local::now().date_naive();
+        let earlier = today - chrono::Duration::days(3);
+        let outside = today - chrono::Duration::days(8);
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        let dir = home.join(".rafctl/profiles/work/claude");
+        fs::create_dir_all(&dir).unwrap();
+        let stats = serde_json::json!({
+            "version": 1,
+            "dailyModelTokens": [
+                {"date": today.to_string(), "tokensByModel": {"claude-sonnet-4-5": 5000}},
+                {"date": earlier.to_string(), "tokensByModel": {"claude-sonnet-4-5": 2000}},
+                {"date": outside.to_string(), "tokensByModel": {"claude-sonnet-4-5": 4000}},
+            ],
+        });
+        fs::write(dir.join("stats-cache.json"), stats.to_string()).unwrap();
+        // Only today's run was recorded with --capture-cost
+        let entry = serde_json::json!({
+            "recorded_at": chrono::Utc::now().to_rfc3339(),
+            "session_id": "abc",
+            "model": "claude-sonnet-4-5",
+            "input_tokens": 1000,
+            "output_tokens": 200,
+            "input_cost": 0.25,
+            "output_cost": 0.5,
+        });
+        fs::write(
+            home.join(".rafctl/profiles/work/costs.jsonl"),
+            format!("{}\n", entry),
+        )
+        .unwrap();
+
+        let output = rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["--json", "analytics", "work", "--cost", "--days", "2"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["source"], "mixed");
+        // Ledger tokens for today, stats-cache tokens for the earlier day; the
+        // day outside the --days window stays out
+        assert_eq!(json["models"][0]["input_tokens"], 3000);
+        assert!(json["total_estimated"].as_f64().unwrap() > 0.75);
+
+        let output = rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["--json", "analytics", "--compare", "work", "work"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json[0]["cost_source"], "mixed");
+    }
+
+    #[test]
+    fn test_analytics_errors_without_transcripts() {
+        let temp = TempDir::new().unwrap();
+
+        rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["analytics", "--errors"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Tool Error Rate"))
+            .stdout(predicate::str::contains("No sessions found"));
+
+        let output = rafctl_cmd(temp.path())
+            .env("HOME", temp.path())
+            .args(["--json", "analytics", "--errors", "--days", "3"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["days"], 3);
+        assert_eq!(json["daily"].as_array().unwrap().len(), 0);
+        assert_eq!(json["totals"]["error_rate"], 0.0);
+    }
+
+    #[test]
+    fn test_analytics_compare_profiles() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+        for (name, messages, tokens) in [("work", 10, 5000), ("home", 3, 1000)] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", "claude"])
+                .assert()
+                .success();
+            let dir = home.join(".rafctl/profiles").join(name).join("claude");
+            fs::create_dir_all(&dir).unwrap();
+            let stats = serde_json::json!({
+                "version": 1,
+                "dailyActivity": [{
+                    "date": today,
+                    "messageCount": messages,
+                    "sessionCount": 1,
+                    "toolCallCount": 2,
+                }],
+                "dailyModelTokens": [{
+                    "date": today,
+                    "tokensByModel": {"claude-sonnet-4-5": tokens},
+                }],
+            });
+            fs::write(dir.join("stats-cache.json"), stats.to_string()).unwrap();
+        }
+
+        let output = rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["--json", "analytics", "--compare", "work", "home"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let rows = json.as_array().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["profile"], "work");
+        assert_eq!(rows[0]["messages"], 10);
+        assert_eq!(rows[0]["tokens"], 5000);
+        assert_eq!(rows[1]["profile"], "home");
+        assert_eq!(rows[1]["cost_source"], "estimate");
+        assert!(rows[1]["estimated_cost"].as_f64().unwrap() > 0.0);
+
+        rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["analytics", "--compare", "work", "home"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Profile Comparison"))
+            .stdout(predicate::str::contains("Tool calls"));
+
+        rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["analytics", "--compare", "work"])
+            .assert()
+            .failure();
+        rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["analytics", "--compare", "work", "missing"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("not found"));
+    }
+
+    #[test]
+    fn test_stats_import_strategies() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        let dir = home.join(".rafctl/profiles/work/claude");
+        fs::create_dir_all(&dir).unwrap();
+        let local = serde_json::json!({
+            "version": 1,
+            "dailyActivity": [{"date": "2026-01-05", "messageCount": 10, "sessionCount": 1}],
+        });
+        fs::write(dir.join("stats-cache.json"), local.to_string()).unwrap();
+        let foreign = serde_json::json!({
+            "version": 1,
+            "dailyActivity": [
+                {"date": "2026-01-05", "messageCount": 4, "sessionCount": 1},
+                {"date": "2026-01-06", "messageCount": 7, "sessionCount": 2},
+            ],
+        });
+        let file = home.join("ci-stats.json");
+        fs::write(&file, foreign.to_string()).unwrap();
+
+        let read_days = || {
+            let stats: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(dir.join("stats-cache.json")).unwrap())
+                    .unwrap();
+            stats["dailyActivity"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|d| d["messageCount"].as_u64().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let output = rafctl_cmd(home)
+            .arg("--json")
+            .args(["stats", "import"])
+            .arg(&file)
+            .args(["--profile", "work"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["strategy"], "sum");
+        assert_eq!(json["days"], 2);
+        assert_eq!(json["overlapping_days"], 1);
+        assert_eq!(read_days(), vec![14, 7]);
+
+        rafctl_cmd(home)
+            .args(["stats", "import"])
+            .arg(&file)
+            .args(["--profile", "work", "--strategy", "max"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Imported 2 day(s)"));
+        assert_eq!(read_days(), vec![14, 7]);
+
+        fs::write(&file, "not json").unwrap();
+        rafctl_cmd(home)
+            .args(["stats", "import"])
+            .arg(&file)
+            .args(["--profile", "work"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_stats_export_round_trips_through_import() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        for name in ["work", "archive"] {
+            rafctl_cmd(home)
+                .args(["profile", "add", name, "--tool", "claude"])
+                .assert()
+                .success();
+        }
+        let dir = home.join(".rafctl/profiles/work/claude");
+        fs::create_dir_all(&dir).unwrap();
+        let stats = serde_json::json!({
+            "version": 1,
+            "totalSessions": 3,
+            "dailyActivity": [
+                {"date": "2026-01-05", "messageCount": 10, "sessionCount": 3, "toolCallCount": 4}
+            ],
+            "dailyModelTokens": [{"date": "2026-01-05", "tokensByModel": {"claude-opus-4-5": 900}}],
+            "modelUsage": {},
+        });
+        fs::write(dir.join("stats-cache.json"), stats.to_string()).unwrap();
+
+        let output = rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["stats", "export", "--profile", "work"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let exported: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(exported, stats);
+
+        let file = home.join("work-stats.json");
+        rafctl_cmd(home)
+            .env("HOME", home)
+            .args(["stats", "export", "--profile", "work", "--out"])
+            .arg(&file)
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Exported 1 day(s)"));
+        rafctl_cmd(home)
+            .args(["stats", "import"])
+            .arg(&file)
+            .args(["--profile", "archive"])
+            .assert()
+            .success();
+
+        let imported: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(home.join(".rafctl/profiles/archive/claude/stats-cache.json"))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(imported, stats);
+    }
+
+    #[test]
+    fn test_analytics_show_pricing_with_overrides() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        fs::create_dir_all(home.join(".rafctl")).unwrap();
+        fs::write(
+            home.join(".rafctl/pricing.toml"),
+            "[\"claude-opus-4-1\"]\ninput_per_million = 15.0\noutput_per_million = 75.0\n",
+        )
+        .unwrap();
+
+        let output = rafctl_cmd(home)
+            .args(["--json", "analytics", "--show-pricing"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let models = json["models"].as_array().unwrap();
+        let opus = models
+            .iter()
+            .find(|m| m["pattern"] == "claude-opus-4-1")
+            .unwrap();
+        assert_eq!(opus["source"], "override");
+        assert_eq!(opus["input_per_million"], 15.0);
+        assert!(models.iter().any(|m| m["source"] == "builtin"));
+    }
+}
+
+mod pre_check_quota_tests {
+    use super::*;
+
+    #[test]
+    fn test_run_pre_check_quota_aborts_at_limit() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "backup", "--tool", "claude"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args([
+                "profile",
+                "add",
+                "work",
+                "--tool",
+                "claude",
+                "--fallback",
+                "backup",
+            ])
+            .assert()
+            .success();
+
+        let cache = serde_json::json!({
+            "fetched_at": chrono::Utc::now().to_rfc3339(),
+            "usage": {
+                "five_hour": {"utilization": 100.0, "resets_at": null},
+                "seven_day": null
+            }
+        });
+        fs::create_dir_all(home.join(".rafctl/cache")).unwrap();
+        fs::write(
+            home.join(".rafctl/cache/quota-work.json"),
+            cache.to_string(),
+        )
+        .unwrap();
+
+        rafctl_cmd(home)
+            .args(["run", "work", "--pre-check-quota"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("reached its 5-hour limit"))
+            .stderr(predicate::str::contains("rafctl run backup"));
+    }
+}
+
+mod parallel_tests {
+    use super::*;
+    use rafctl::core::lock::acquire_lock;
+
+    #[test]
+    fn test_oauth_run_refused_while_lock_held() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        rafctl_cmd(home)
+            .args(["profile", "add", "personal", "--tool", "claude"])
+            .assert()
+            .success();
+
+        let _held = acquire_lock(&home.join(".rafctl/oauth.lock"), "work")
+            .unwrap()
+            .unwrap();
+
+        rafctl_cmd(home)
+            .args(["run", "personal"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("OAuth mode conflict"))
+            .stderr(predicate::str::contains("profile 'work'"));
+    }
+
+    // On macOS an OAuth launch would swap tokens in the real login keychain
+    #[test]
+    #[cfg(not(target_os = "macos"))]
+    fn test_oauth_run_allowed_for_same_profile() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let bin_dir = home.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let fake_tool = bin_dir.join("claude");
+        fs::write(&fake_tool, "#!/bin/sh\nexit 0\n").unwrap();
+        fs::set_permissions(&fake_tool, fs::Permissions::from_mode(0o755)).unwrap();
+        let path = format!(
+            "{}:{}",
+            bin_dir.display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+        fs::write(home.join(".rafctl/profiles/work/.claude.json"), "{}").unwrap();
+
+        let _held = acquire_lock(&home.join(".rafctl/oauth.lock"), "work")
+            .unwrap()
+            .unwrap();
+
+        // A second instance of the lock holder passes the lock check without
+        // --force and without a warning; the launch then stops at the keychain
+        for extra in [&[][..], &["--force"][..]] {
+            rafctl_cmd(home)
+                .env("PATH", &path)
+                .args(["run", "work"])
+                .args(extra)
+                .assert()
+                .stderr(predicate::str::contains("OAuth mode conflict").not())
+                .stderr(predicate::str::contains("Ignoring OAuth lock").not());
+        }
+    }
+
+    #[test]
+    fn test_api_key_runs_ignore_oauth_lock() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        for name in ["api-one", "api-two"] {
+            rafctl_cmd(home)
+                .args([
+                    "profile",
+                    "add",
+                    name,
+                    "--tool",
+                    "claude",
+                    "--auth-mode",
+                    "api-key",
+                ])
+                .assert()
+                .success();
+        }
+
+        let _held = acquire_lock(&home.join(".rafctl/oauth.lock"), "work")
+            .unwrap()
+            .unwrap();
+
+        // Launching may still fail (no key, no claude binary), but never on the lock
+        for name in ["api-one", "api-two"] {
+            rafctl_cmd(home)
+                .args(["run", name])
+                .assert()
+                .stderr(predicate::str::contains("OAuth mode conflict").not());
+        }
+    }
+}
+
+mod isolation_tests {
+    use super::*;
+
+    #[test]
+    fn test_two_profiles_have_separate_directories() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        // Create two profiles
+        rafctl_cmd(home)
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "personal", "--tool", "claude"])
+            .assert()
+            .success();
+
+        // Verify separate directories exist
+        let rafctl_dir = home.join(".rafctl").join("profiles");
+        assert!(rafctl_dir.join("work").exists());
+        assert!(rafctl_dir.join("personal").exists());
+
+        // Verify meta.yaml files
+        assert!(rafctl_dir.join("work").join("meta.yaml").exists());
+        assert!(rafctl_dir.join("personal").join("meta.yaml").exists());
+    }
+
+    #[test]
+    fn test_profile_config_isolation() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        rafctl_cmd(home)
+            .args(["profile", "add", "isolated", "--tool", "claude"])
+            .assert()
+            .success();
+
+        // The profile should have its own claude subdirectory ready
+        let profile_dir = home.join(".rafctl").join("profiles").join("isolated");
+        assert!(profile_dir.exists());
+
+        // Create a marker file in the profile's claude dir
+        let claude_dir = profile_dir.join("claude");
+        fs::create_dir_all(&claude_dir).unwrap();
+        fs::write(claude_dir.join("marker.txt"), "test").unwrap();
+
+        // Verify the marker exists only in this profile
+        assert!(claude_dir.join("marker.txt").exists());
+    }
+}
+
+mod completion_tests {
     use super::*;
 
     #[test]
@@ -541,6 +3711,62 @@ mod completion_tests {
             .success()
             .stdout(predicate::str::contains("complete"));
     }
+
+    #[test]
+    fn test_powershell_completion_hints_profile_names() {
+        let output = cargo_bin_cmd!("rafctl")
+            .args(["completion", "powershell"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let script = String::from_utf8(output.stdout).unwrap();
+
+        assert!(script.contains("Register-ArgumentCompleter"));
+        let hook = script
+            .lines()
+            .find(|l| l.contains("$command -in @("))
+            .expect("profile hint block");
+        assert!(hook.contains("'rafctl;run'"));
+        assert!(hook.contains("'rafctl;auth;login'"));
+        assert!(!hook.contains("'rafctl;profile;add'"));
+        assert!(script.contains("rafctl __complete profiles"));
+    }
+
+    #[test]
+    fn test_complete_profiles_lists_names() {
+        let temp = TempDir::new().unwrap();
+        rafctl_cmd(temp.path())
+            .args(["profile", "add", "work", "--tool", "claude"])
+            .assert()
+            .success();
+
+        rafctl_cmd(temp.path())
+            .args(["__complete", "profiles"])
+            .assert()
+            .success()
+            .stdout("work\n");
+    }
+
+    #[test]
+    fn test_shell_completions_hint_profile_names() {
+        for (shell, marker) in [
+            ("bash", "\"rafctl;run\"|"),
+            ("zsh", "'rafctl;run'"),
+            ("fish", "'rafctl;run'"),
+        ] {
+            let output = cargo_bin_cmd!("rafctl")
+                .args(["completion", shell])
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            let script = String::from_utf8(output.stdout).unwrap();
+
+            assert!(script.contains("rafctl __complete profiles"), "{shell}");
+            assert!(script.contains(marker), "{shell}");
+            assert!(script.contains("rafctl;auth;login"), "{shell}");
+            assert!(!script.contains("rafctl;profile;add"), "{shell}");
+        }
+    }
 }
 
 mod no_color_tests {